@@ -122,6 +122,7 @@ where
         max_block_time: default::max_block_time(),
         trusting_period: None,
         memo_prefix: Memo::default(),
+        clear_on_start: None,
         proof_specs: Default::default(),
         trust_threshold: TrustThreshold::default(),
         gas_price: GasPrice {
@@ -129,8 +130,11 @@ where
             denom: asset.base.to_owned(),
         },
         packet_filter: packet_filter.unwrap_or_default(),
+        connection_filter: Default::default(),
         address_type: AddressType::default(),
         sequential_batch_tx: false,
+        client_filter: Default::default(),
+        denom_filter: Default::default(),
         extension_options: Vec::new(),
     })
 }
@@ -170,10 +170,24 @@ fn spawn_telemetry_server(config: &Config) -> Result<(), Box<dyn Error + Send +
     Ok(())
 }
 
+fn warn_blocked_transfer(config: &Config) {
+    for chain in config.chains.iter() {
+        if chain.packet_filter.blocks_transfer() {
+            warn!(
+                "the packet filter of chain {} denies all channels on the `transfer` port, \
+                 no token transfer will be relayed for this chain",
+                chain.id
+            );
+        }
+    }
+}
+
 fn make_supervisor<Chain: ChainHandle>(
     config: Config,
     force_full_scan: bool,
 ) -> Result<SupervisorHandle, Box<dyn Error + Send + Sync>> {
+    warn_blocked_transfer(&config);
+
     let registry = SharedRegistry::<Chain>::new(config.clone());
     spawn_telemetry_server(&config)?;
 
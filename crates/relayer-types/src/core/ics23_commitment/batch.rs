@@ -0,0 +1,145 @@
+//! Verification of the commitment proofs of a batch of packets at once, as
+//! when relaying the packets sent on a channel in the same block.
+
+use crate::prelude::*;
+
+use crate::core::ics04_channel::commitment::PacketCommitment;
+use crate::core::ics04_channel::packet::Sequence;
+use crate::core::ics23_commitment::commitment::{
+    CommitmentPrefix, CommitmentProofBytes, CommitmentRoot,
+};
+use crate::core::ics23_commitment::error::Error;
+use crate::core::ics23_commitment::merkle::apply_prefix;
+use crate::core::ics23_commitment::specs::ProofSpecs;
+use crate::core::ics24_host::identifier::{ChannelId, PortId};
+use crate::core::ics24_host::path::{CommitmentsPath, Path};
+
+/// A packet commitment to verify, with the port, channel and sequence of
+/// the packet it commits to and the proof of its membership.
+pub type PacketCommitmentItem = (
+    PortId,
+    ChannelId,
+    Sequence,
+    PacketCommitment,
+    CommitmentProofBytes,
+);
+
+/// Verifies that each of the given packet commitments is stored at the
+/// commitment path of its packet under `prefix`, in the state committed to
+/// by `root`.
+///
+/// Returns whether the proof of each item verified, in the order of the
+/// items, so that a single invalid proof does not fail the whole batch.
+/// Fails only if the batch cannot be verified at all, i.e. if `root` is empty.
+pub fn verify_packet_commitments(
+    specs: &ProofSpecs,
+    root: &CommitmentRoot,
+    prefix: &CommitmentPrefix,
+    items: &[PacketCommitmentItem],
+) -> Result<Vec<bool>, Error> {
+    if root.as_bytes().is_empty() {
+        return Err(Error::empty_merkle_root());
+    }
+
+    let results = items
+        .iter()
+        .map(|(port_id, channel_id, sequence, commitment, proof)| {
+            let path = Path::Commitments(CommitmentsPath {
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                sequence: *sequence,
+            });
+
+            proof
+                .verify_membership(
+                    specs,
+                    root,
+                    apply_prefix(prefix, vec![path.to_string()]),
+                    commitment.clone().into_vec(),
+                )
+                .is_ok()
+        })
+        .collect();
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::core::ics23_commitment::merkle::MerkleProof;
+    use crate::core::ics23_commitment::mock::build_tendermint_tree;
+
+    fn commitment_path(sequence: u64) -> String {
+        Path::Commitments(CommitmentsPath {
+            port_id: PortId::transfer(),
+            channel_id: ChannelId::new(0),
+            sequence: sequence.into(),
+        })
+        .to_string()
+    }
+
+    fn commitment(sequence: u64) -> PacketCommitment {
+        format!("commitment-{}", sequence).into_bytes().into()
+    }
+
+    #[test]
+    fn verify_batch_with_one_wrong_commitment() {
+        let store_pairs = (1..=3)
+            .map(|sequence| {
+                (
+                    commitment_path(sequence).into_bytes(),
+                    commitment(sequence).into_vec(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let (store_root, store_proofs) = build_tendermint_tree(&store_pairs).unwrap();
+
+        let (root, app_proofs) = build_tendermint_tree(&[
+            (b"ibc".to_vec(), store_root.into_vec()),
+            (b"bank".to_vec(), b"bank-root".to_vec()),
+        ])
+        .unwrap();
+
+        let specs = ProofSpecs::from(vec![ics23::tendermint_spec(), ics23::tendermint_spec()]);
+        let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+
+        // The proofs of the store are sorted by sequence, and the one of the
+        // `ibc` store comes after the one of `bank`.
+        let items = store_proofs
+            .iter()
+            .zip(1..=3)
+            .map(|(store_proof, sequence)| {
+                let proof = MerkleProof {
+                    proofs: vec![
+                        store_proof.proofs[0].clone(),
+                        app_proofs[1].proofs[0].clone(),
+                    ],
+                };
+
+                let commitment = if sequence == 2 {
+                    commitment(4)
+                } else {
+                    commitment(sequence)
+                };
+
+                (
+                    PortId::transfer(),
+                    ChannelId::new(0),
+                    sequence.into(),
+                    commitment,
+                    CommitmentProofBytes::try_from(proof).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let results = verify_packet_commitments(&specs, &root, &prefix, &items).unwrap();
+        assert_eq!(results, vec![true, false, true]);
+
+        assert!(
+            verify_packet_commitments(&specs, &CommitmentRoot::empty(), &prefix, &items).is_err()
+        );
+    }
+}
@@ -1,18 +1,25 @@
 use crate::core::ics23_commitment::error::Error;
+use crate::core::ics24_host::identifier::ClientId;
+use crate::core::ics24_host::path::{ClientConsensusStatePath, Path};
 use crate::prelude::*;
 use crate::proofs::ProofError;
+use crate::Height;
 
 use core::{convert::TryFrom, fmt};
 use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
 use subtle_encoding::{Encoding, Hex};
 
-use super::merkle::MerkleProof;
+use ibc_proto::ibc::core::commitment::v1::MerklePath;
+
+use super::merkle::{apply_prefix, MerkleProof};
+use super::specs::ProofSpecs;
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct CommitmentRoot {
-    #[serde(serialize_with = "crate::serializers::ser_hex_upper")]
+    #[serde(with = "crate::serializers::hex_upper_or_bytes")]
     bytes: Vec<u8>,
 }
 
@@ -24,6 +31,11 @@ impl fmt::Debug for CommitmentRoot {
 }
 
 impl CommitmentRoot {
+    /// A root without any bytes, which no proof can be verified against.
+    pub fn empty() -> Self {
+        Self { bytes: Vec::new() }
+    }
+
     pub fn from_bytes(bytes: &[u8]) -> Self {
         Self {
             bytes: Vec::from(bytes),
@@ -37,6 +49,30 @@ impl CommitmentRoot {
     pub fn into_vec(self) -> Vec<u8> {
         self.bytes
     }
+
+    /// Compares two roots in constant time with respect to their contents,
+    /// to be used when checking a computed root against a trusted one.
+    /// Roots of different lengths are never equal.
+    pub fn ct_eq(&self, other: &CommitmentRoot) -> bool {
+        self.bytes.as_slice().ct_eq(other.bytes.as_slice()).into()
+    }
+
+    /// Returns the index of the first byte at which the two roots differ,
+    /// or `None` if they are equal. If one root is a prefix of the other,
+    /// the length of the shorter one is returned.
+    ///
+    /// This is meant as a debugging aid, not to check roots against each
+    /// other, for which [`CommitmentRoot::ct_eq`] should be used.
+    pub fn diff(&self, other: &CommitmentRoot) -> Option<usize> {
+        self.bytes
+            .iter()
+            .zip(other.bytes.iter())
+            .position(|(a, b)| a != b)
+            .or_else(|| {
+                (self.bytes.len() != other.bytes.len())
+                    .then(|| self.bytes.len().min(other.bytes.len()))
+            })
+    }
 }
 
 impl From<Vec<u8>> for CommitmentRoot {
@@ -45,8 +81,72 @@ impl From<Vec<u8>> for CommitmentRoot {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct CommitmentPath;
+/// The path of a value committed to in the store, e.g. `clients/07-tendermint-0/clientState`,
+/// held as the sequence of its `/`-separated key segments.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CommitmentPath {
+    key_path: Vec<Vec<u8>>,
+}
+
+impl CommitmentPath {
+    pub fn new(path: String) -> Self {
+        Self {
+            key_path: path.split('/').map(|key| key.as_bytes().to_vec()).collect(),
+        }
+    }
+
+    /// Builds the commitment path of the given store [`Path`], so that proofs
+    /// can be verified against the exact key the value is stored under.
+    pub fn from_path(path: &Path) -> Self {
+        Self::new(path.to_string())
+    }
+
+    /// Builds the commitment path of the consensus state of the client with
+    /// [`ClientId`] at the given [`Height`], formatted as the SDK stores it,
+    /// i.e. `clients/<client_id>/consensusStates/<revision_number>-<revision_height>`.
+    pub fn consensus_state(client_id: ClientId, height: Height) -> Self {
+        Self::from_path(&Path::ClientConsensusState(ClientConsensusStatePath {
+            client_id,
+            epoch: height.revision_number(),
+            height: height.revision_height(),
+        }))
+    }
+
+    pub fn key_path(&self) -> &[Vec<u8>] {
+        &self.key_path
+    }
+
+    /// Returns the [`MerklePath`] of this path in the store under `prefix`,
+    /// as ICS23 proofs key the whole path under the store prefix, e.g.
+    /// `["ibc", "clients/07-tendermint-0/clientState"]`.
+    pub fn to_merkle_path(&self, prefix: &CommitmentPrefix) -> MerklePath {
+        apply_prefix(prefix, vec![self.to_string()])
+    }
+}
+
+impl From<String> for CommitmentPath {
+    fn from(path: String) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<Path> for CommitmentPath {
+    fn from(path: Path) -> Self {
+        Self::from_path(&path)
+    }
+}
+
+impl fmt::Display for CommitmentPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, key) in self.key_path.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{}", String::from_utf8_lossy(key))?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Clone, PartialEq, Eq, Serialize)]
 #[serde(transparent)]
@@ -62,6 +162,39 @@ impl fmt::Debug for CommitmentProofBytes {
     }
 }
 
+impl CommitmentProofBytes {
+    /// Decodes this proof as a [`MerkleProof`] and verifies that `value` is
+    /// committed to at `path` under the given `root`, walking each of the
+    /// chained ICS23 proofs up to it.
+    pub fn verify_membership(
+        &self,
+        specs: &ProofSpecs,
+        root: &CommitmentRoot,
+        path: MerklePath,
+        value: Vec<u8>,
+    ) -> Result<(), Error> {
+        self.to_merkle_proof()?
+            .verify_membership(specs, root.clone().into(), path, value, 0)
+    }
+
+    /// Decodes this proof as a [`MerkleProof`] and verifies that nothing is
+    /// committed to at `path` under the given `root`, eg. for a packet
+    /// receipt when handling a timeout on an unordered channel.
+    pub fn verify_non_membership(
+        &self,
+        specs: &ProofSpecs,
+        root: &CommitmentRoot,
+        path: MerklePath,
+    ) -> Result<(), Error> {
+        self.to_merkle_proof()?
+            .verify_non_membership(specs, root.clone().into(), path)
+    }
+
+    fn to_merkle_proof(&self) -> Result<MerkleProof, Error> {
+        RawMerkleProof::try_from(self.clone()).map(MerkleProof::from)
+    }
+}
+
 impl TryFrom<Vec<u8>> for CommitmentProofBytes {
     type Error = ProofError;
 
@@ -115,6 +248,11 @@ pub struct CommitmentPrefix {
 }
 
 impl CommitmentPrefix {
+    /// Creates a prefix from the given bytes, which must not be empty.
+    pub fn try_new(content: Vec<u8>) -> Result<Self, Error> {
+        Self::try_from(content)
+    }
+
     pub fn as_bytes(&self) -> &[u8] {
         &self.bytes
     }
@@ -146,6 +284,19 @@ impl fmt::Debug for CommitmentPrefix {
     }
 }
 
+/// Renders the prefix as UTF-8 if valid, eg. `ibc`, or as upper-case hex otherwise.
+impl fmt::Display for CommitmentPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match core::str::from_utf8(self.as_bytes()) {
+            Ok(s) => write!(f, "{}", s),
+            Err(_) => {
+                let hex = Hex::upper_case().encode_to_string(self.as_bytes()).unwrap();
+                write!(f, "{}", hex)
+            }
+        }
+    }
+}
+
 impl Serialize for CommitmentPrefix {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -168,3 +319,251 @@ pub mod test_util {
         RawMerkleProof { proofs: mproofs }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commitment_root_ct_eq_agrees_with_eq() {
+        let root = CommitmentRoot::from_bytes(&[0xAB; 32]);
+        let same = CommitmentRoot::from_bytes(&[0xAB; 32]);
+
+        let mut bytes = [0xAB; 32];
+        bytes[31] = 0xAC;
+        let different = CommitmentRoot::from_bytes(&bytes);
+        let shorter = CommitmentRoot::from_bytes(&[0xAB; 31]);
+
+        for other in [&same, &different, &shorter] {
+            assert_eq!(root.ct_eq(other), root == *other);
+        }
+
+        assert!(root.ct_eq(&same));
+        assert!(!root.ct_eq(&different));
+        assert!(!root.ct_eq(&shorter));
+    }
+
+    fn proof_fixture() -> (CommitmentRoot, CommitmentProofBytes) {
+        let pairs = vec![
+            (b"a".to_vec(), b"value-a".to_vec()),
+            (b"b".to_vec(), b"value-b".to_vec()),
+        ];
+
+        let (root, mut proofs) =
+            crate::core::ics23_commitment::mock::build_tendermint_tree(&pairs).unwrap();
+
+        (root, proofs.remove(0).try_into().unwrap())
+    }
+
+    fn path(key: &str) -> MerklePath {
+        MerklePath {
+            key_path: vec![key.to_string()],
+        }
+    }
+
+    #[test]
+    fn commitment_proof_verify_membership() {
+        let specs = ProofSpecs::from(vec![ics23::tendermint_spec()]);
+        let (root, proof) = proof_fixture();
+
+        proof
+            .verify_membership(&specs, &root, path("a"), b"value-a".to_vec())
+            .unwrap();
+
+        assert!(proof
+            .verify_membership(&specs, &root, path("a"), b"value-b".to_vec())
+            .is_err());
+        assert!(proof
+            .verify_non_membership(&specs, &root, path("a"))
+            .is_err());
+
+        let mut corrupted: Vec<u8> = proof.into();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xFF;
+        let corrupted = CommitmentProofBytes::try_from(corrupted).unwrap();
+
+        assert!(corrupted
+            .verify_membership(&specs, &root, path("a"), b"value-a".to_vec())
+            .is_err());
+    }
+
+    #[test]
+    fn commitment_prefix_try_new() {
+        assert!(CommitmentPrefix::try_new(Vec::new()).is_err());
+
+        let prefix = CommitmentPrefix::try_new(b"ibc".to_vec()).unwrap();
+        assert_eq!(prefix.as_bytes(), b"ibc");
+        assert_eq!(prefix.to_string(), "ibc");
+
+        let prefix = CommitmentPrefix::try_new(vec![0xFF, 0x01]).unwrap();
+        assert_eq!(prefix.to_string(), "FF01");
+    }
+
+    #[test]
+    fn commitment_root_empty() {
+        let empty = CommitmentRoot::empty();
+
+        assert!(empty.as_bytes().is_empty());
+        assert_eq!(empty, CommitmentRoot::from_bytes(&[]));
+        assert_eq!(serde_json::to_string(&empty).unwrap(), r#""""#);
+    }
+
+    #[test]
+    fn commitment_root_diff() {
+        let root = CommitmentRoot::from_bytes(&[0xAB; 32]);
+
+        let mut bytes = [0xAB; 32];
+        bytes[5] = 0x00;
+        bytes[20] = 0x00;
+        let different = CommitmentRoot::from_bytes(&bytes);
+
+        assert_eq!(root.diff(&root.clone()), None);
+        assert_eq!(root.diff(&different), Some(5));
+        assert_eq!(different.diff(&root), Some(5));
+        assert_eq!(
+            root.diff(&CommitmentRoot::from_bytes(&[0xAB; 31])),
+            Some(31)
+        );
+    }
+
+    #[test]
+    fn commitment_root_serde_roundtrip() {
+        let root = CommitmentRoot::from_bytes(&[0x01, 0xAB, 0xFF, 0x00]);
+
+        let bytes = bincode::serialize(&root).unwrap();
+        let decoded: CommitmentRoot = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded, root);
+
+        let json = serde_json::to_string(&root).unwrap();
+        assert_eq!(json, r#""01ABFF00""#);
+        let decoded: CommitmentRoot = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, root);
+    }
+
+    #[test]
+    fn commitment_path_from_packet_paths() {
+        use crate::core::ics24_host::identifier::{ChannelId, PortId};
+        use crate::core::ics24_host::path::{AcksPath, CommitmentsPath};
+
+        let commitment = CommitmentPath::from_path(&Path::Commitments(CommitmentsPath {
+            port_id: PortId::transfer(),
+            channel_id: ChannelId::new(3),
+            sequence: 7.into(),
+        }));
+        assert_eq!(
+            commitment.key_path(),
+            &[
+                b"commitments".to_vec(),
+                b"ports".to_vec(),
+                b"transfer".to_vec(),
+                b"channels".to_vec(),
+                b"channel-3".to_vec(),
+                b"sequences".to_vec(),
+                b"7".to_vec(),
+            ]
+        );
+        assert_eq!(
+            commitment.to_string(),
+            "commitments/ports/transfer/channels/channel-3/sequences/7"
+        );
+
+        let ack = CommitmentPath::from(Path::Acks(AcksPath {
+            port_id: PortId::transfer(),
+            channel_id: ChannelId::new(0),
+            sequence: 1.into(),
+        }));
+        assert_eq!(
+            ack.to_string(),
+            "acks/ports/transfer/channels/channel-0/sequences/1"
+        );
+
+        let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+        assert_eq!(
+            ack.to_merkle_path(&prefix).key_path,
+            vec!["ibc", "acks/ports/transfer/channels/channel-0/sequences/1"]
+        );
+    }
+
+    #[test]
+    fn verify_consensus_state_proof_with_height_path() {
+        use crate::core::ics23_commitment::mock::build_tendermint_tree;
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str("07-tendermint-0").unwrap();
+        let height = Height::new(1, 42).unwrap();
+
+        let path = CommitmentPath::consensus_state(client_id, height);
+        assert_eq!(
+            path.to_string(),
+            "clients/07-tendermint-0/consensusStates/1-42"
+        );
+
+        let value = b"consensus-state".to_vec();
+        let (store_root, store_proofs) = build_tendermint_tree(&[(
+            b"clients/07-tendermint-0/consensusStates/1-42".to_vec(),
+            value.clone(),
+        )])
+        .unwrap();
+
+        let (root, app_proofs) =
+            build_tendermint_tree(&[(b"ibc".to_vec(), store_root.into_vec())]).unwrap();
+
+        let proof = MerkleProof {
+            proofs: vec![
+                store_proofs[0].proofs[0].clone(),
+                app_proofs[0].proofs[0].clone(),
+            ],
+        };
+
+        let specs = ProofSpecs::from(vec![ics23::tendermint_spec(), ics23::tendermint_spec()]);
+        let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+
+        proof
+            .verify_membership(
+                &specs,
+                root.clone().into(),
+                path.to_merkle_path(&prefix),
+                value.clone(),
+                0,
+            )
+            .unwrap();
+
+        for wrong in [
+            "clients/07-tendermint-0/consensusStates/42",
+            "clients/07-tendermint-0/consensusStates/1/42",
+            "clients/07-tendermint-0/consensusStates/1-0042",
+        ] {
+            assert!(proof
+                .verify_membership(
+                    &specs,
+                    root.clone().into(),
+                    CommitmentPath::from(wrong.to_string()).to_merkle_path(&prefix),
+                    value.clone(),
+                    0,
+                )
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn commitment_path_as_map_key() {
+        use std::collections::HashMap;
+
+        let client_state = CommitmentPath::from("clients/07-tendermint-0/clientState".to_string());
+        let connection = CommitmentPath::from("connections/connection-0".to_string());
+
+        let mut cache = HashMap::new();
+        cache.insert(client_state.clone(), true);
+        cache.insert(connection.clone(), false);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&client_state), Some(&true));
+        assert_eq!(cache.get(&connection), Some(&false));
+        assert_eq!(
+            cache.get(&CommitmentPath::new(
+                "clients/07-tendermint-1/clientState".to_string()
+            )),
+            None
+        );
+    }
+}
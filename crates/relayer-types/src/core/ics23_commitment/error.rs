@@ -1,6 +1,12 @@
+use crate::prelude::*;
+use core::fmt;
+
 use flex_error::{define_error, TraceError};
 use prost::DecodeError;
 
+use crate::core::ics24_host::identifier::ChainId;
+use crate::Height;
+
 define_error! {
     #[derive(Debug, PartialEq, Eq)]
     Error {
@@ -33,7 +39,53 @@ define_error! {
         InvalidMerkleProof
             |_| { "invalid merkle proof" },
 
+        HeightMismatch
+            { proof: Height, trusted: Height }
+            |e| {
+                format_args!("proof height {} does not match the trusted consensus height {}",
+                    e.proof, e.trusted)
+            },
+
+        ProofTooDeep
+            { depth: usize, max_depth: usize }
+            |e| {
+                format_args!("proof depth {} exceeds the configured maximum depth {}",
+                    e.depth, e.max_depth)
+            },
+
         VerificationFailure
-            |_| { "proof verification failed" }
+            { reason: VerificationFailureReason }
+            |e| { format_args!("proof verification failed: {}", e.reason) },
+
+        UnregisteredProofSpecs
+            { chain_id: ChainId }
+            |e| { format_args!("no proof specs registered for chain {}", e.chain_id) }
+    }
+}
+
+/// The reason why an ics23 proof failed to verify.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerificationFailureReason {
+    /// The root computed from the proof does not match the expected root.
+    WrongRoot,
+    /// The key proven by the proof does not match the expected key.
+    KeyMismatch,
+    /// The value proven by the proof does not match the expected value.
+    ValueMismatch,
+    /// The proof does not conform to the proof spec.
+    SpecViolation(String),
+    /// The proof was rejected by ics23 for another reason.
+    Rejected,
+}
+
+impl fmt::Display for VerificationFailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongRoot => write!(f, "computed root does not match the expected root"),
+            Self::KeyMismatch => write!(f, "proven key does not match the expected key"),
+            Self::ValueMismatch => write!(f, "proven value does not match the expected value"),
+            Self::SpecViolation(detail) => write!(f, "proof spec violation: {detail}"),
+            Self::Rejected => write!(f, "proof rejected by ics23"),
+        }
     }
 }
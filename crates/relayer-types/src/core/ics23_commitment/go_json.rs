@@ -0,0 +1,419 @@
+//! Conversion of a [`MerkleProof`] into the JSON emitted by the Go relayer,
+//! to help diffing proofs between implementations.
+//!
+//! The Go relayer encodes proofs with `json.Marshal`, which follows the Go
+//! structs generated from the protobuf definitions: fields come in their
+//! declaration order and are omitted when set to their default value, enums
+//! are encoded as numbers, bytes as base64, and the variant of a `oneof` is
+//! nested under the name of its Go field and wrapper, eg. `{"Proof":{"Exist":...}}`.
+
+use crate::prelude::*;
+
+use ics23::commitment_proof::Proof;
+use ics23::{
+    batch_entry, compressed_batch_entry, BatchEntry, BatchProof, CommitmentProof,
+    CompressedBatchEntry, CompressedBatchProof, CompressedExistenceProof,
+    CompressedNonExistenceProof, ExistenceProof, InnerOp, LeafOp, NonExistenceProof,
+};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use subtle_encoding::base64;
+
+use super::merkle::MerkleProof;
+
+impl MerkleProof {
+    /// Returns the proof as encoded by the Go relayer for its `MerkleProof`.
+    pub fn to_go_merkle_json(&self) -> Value {
+        serde_json::to_value(GoMerkleProof::from(self)).expect("a proof can always be encoded")
+    }
+
+    /// Returns the proof as encoded by the Go relayer for its `MerkleProof`,
+    /// byte for byte, with the fields in the order of the Go structs.
+    pub fn to_go_merkle_json_string(&self) -> String {
+        serde_json::to_string(&GoMerkleProof::from(self)).expect("a proof can always be encoded")
+    }
+}
+
+fn serialize_bytes<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    let encoded = base64::encode(data);
+    serializer.serialize_str(core::str::from_utf8(&encoded).expect("base64 is ASCII"))
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+#[derive(Serialize)]
+struct GoMerkleProof<'a> {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    proofs: Vec<GoCommitmentProof<'a>>,
+}
+
+impl<'a> From<&'a MerkleProof> for GoMerkleProof<'a> {
+    fn from(proof: &'a MerkleProof) -> Self {
+        Self {
+            proofs: proof.proofs.iter().map(GoCommitmentProof::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GoCommitmentProof<'a> {
+    #[serde(rename = "Proof")]
+    proof: Option<GoProof<'a>>,
+}
+
+#[derive(Serialize)]
+enum GoProof<'a> {
+    Exist(GoExistenceProof<'a>),
+    Nonexist(GoNonExistenceProof<'a>),
+    Batch(GoBatchProof<'a>),
+    Compressed(GoCompressedBatchProof<'a>),
+}
+
+impl<'a> From<&'a CommitmentProof> for GoCommitmentProof<'a> {
+    fn from(proof: &'a CommitmentProof) -> Self {
+        let proof = proof.proof.as_ref().map(|proof| match proof {
+            Proof::Exist(p) => GoProof::Exist(p.into()),
+            Proof::Nonexist(p) => GoProof::Nonexist(p.into()),
+            Proof::Batch(p) => GoProof::Batch(p.into()),
+            Proof::Compressed(p) => GoProof::Compressed(p.into()),
+        });
+
+        Self { proof }
+    }
+}
+
+#[derive(Serialize)]
+struct GoLeafOp<'a> {
+    #[serde(skip_serializing_if = "is_default")]
+    hash: i32,
+    #[serde(skip_serializing_if = "is_default")]
+    prehash_key: i32,
+    #[serde(skip_serializing_if = "is_default")]
+    prehash_value: i32,
+    #[serde(skip_serializing_if = "is_default")]
+    length: i32,
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    prefix: &'a [u8],
+}
+
+impl<'a> From<&'a LeafOp> for GoLeafOp<'a> {
+    fn from(leaf: &'a LeafOp) -> Self {
+        Self {
+            hash: leaf.hash,
+            prehash_key: leaf.prehash_key,
+            prehash_value: leaf.prehash_value,
+            length: leaf.length,
+            prefix: &leaf.prefix,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GoInnerOp<'a> {
+    #[serde(skip_serializing_if = "is_default")]
+    hash: i32,
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    prefix: &'a [u8],
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    suffix: &'a [u8],
+}
+
+impl<'a> From<&'a InnerOp> for GoInnerOp<'a> {
+    fn from(inner: &'a InnerOp) -> Self {
+        Self {
+            hash: inner.hash,
+            prefix: &inner.prefix,
+            suffix: &inner.suffix,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GoExistenceProof<'a> {
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    key: &'a [u8],
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    value: &'a [u8],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    leaf: Option<GoLeafOp<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    path: Vec<GoInnerOp<'a>>,
+}
+
+impl<'a> From<&'a ExistenceProof> for GoExistenceProof<'a> {
+    fn from(proof: &'a ExistenceProof) -> Self {
+        Self {
+            key: &proof.key,
+            value: &proof.value,
+            leaf: proof.leaf.as_ref().map(GoLeafOp::from),
+            path: proof.path.iter().map(GoInnerOp::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GoNonExistenceProof<'a> {
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    key: &'a [u8],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    left: Option<GoExistenceProof<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    right: Option<GoExistenceProof<'a>>,
+}
+
+impl<'a> From<&'a NonExistenceProof> for GoNonExistenceProof<'a> {
+    fn from(proof: &'a NonExistenceProof) -> Self {
+        Self {
+            key: &proof.key,
+            left: proof.left.as_ref().map(GoExistenceProof::from),
+            right: proof.right.as_ref().map(GoExistenceProof::from),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GoBatchProof<'a> {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    entries: Vec<GoBatchEntry<'a>>,
+}
+
+#[derive(Serialize)]
+struct GoBatchEntry<'a> {
+    #[serde(rename = "Proof")]
+    proof: Option<GoBatchEntryProof<'a>>,
+}
+
+#[derive(Serialize)]
+enum GoBatchEntryProof<'a> {
+    Exist(GoExistenceProof<'a>),
+    Nonexist(GoNonExistenceProof<'a>),
+}
+
+impl<'a> From<&'a BatchProof> for GoBatchProof<'a> {
+    fn from(proof: &'a BatchProof) -> Self {
+        Self {
+            entries: proof.entries.iter().map(GoBatchEntry::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a BatchEntry> for GoBatchEntry<'a> {
+    fn from(entry: &'a BatchEntry) -> Self {
+        let proof = entry.proof.as_ref().map(|proof| match proof {
+            batch_entry::Proof::Exist(p) => GoBatchEntryProof::Exist(p.into()),
+            batch_entry::Proof::Nonexist(p) => GoBatchEntryProof::Nonexist(p.into()),
+        });
+
+        Self { proof }
+    }
+}
+
+#[derive(Serialize)]
+struct GoCompressedExistenceProof<'a> {
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    key: &'a [u8],
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    value: &'a [u8],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    leaf: Option<GoLeafOp<'a>>,
+    #[serde(skip_serializing_if = "<[i32]>::is_empty")]
+    path: &'a [i32],
+}
+
+impl<'a> From<&'a CompressedExistenceProof> for GoCompressedExistenceProof<'a> {
+    fn from(proof: &'a CompressedExistenceProof) -> Self {
+        Self {
+            key: &proof.key,
+            value: &proof.value,
+            leaf: proof.leaf.as_ref().map(GoLeafOp::from),
+            path: &proof.path,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GoCompressedNonExistenceProof<'a> {
+    #[serde(
+        skip_serializing_if = "<[u8]>::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    key: &'a [u8],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    left: Option<GoCompressedExistenceProof<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    right: Option<GoCompressedExistenceProof<'a>>,
+}
+
+impl<'a> From<&'a CompressedNonExistenceProof> for GoCompressedNonExistenceProof<'a> {
+    fn from(proof: &'a CompressedNonExistenceProof) -> Self {
+        Self {
+            key: &proof.key,
+            left: proof.left.as_ref().map(GoCompressedExistenceProof::from),
+            right: proof.right.as_ref().map(GoCompressedExistenceProof::from),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GoCompressedBatchProof<'a> {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    entries: Vec<GoCompressedBatchEntry<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    lookup_inners: Vec<GoInnerOp<'a>>,
+}
+
+#[derive(Serialize)]
+struct GoCompressedBatchEntry<'a> {
+    #[serde(rename = "Proof")]
+    proof: Option<GoCompressedBatchEntryProof<'a>>,
+}
+
+#[derive(Serialize)]
+enum GoCompressedBatchEntryProof<'a> {
+    Exist(GoCompressedExistenceProof<'a>),
+    Nonexist(GoCompressedNonExistenceProof<'a>),
+}
+
+impl<'a> From<&'a CompressedBatchProof> for GoCompressedBatchProof<'a> {
+    fn from(proof: &'a CompressedBatchProof) -> Self {
+        Self {
+            entries: proof
+                .entries
+                .iter()
+                .map(GoCompressedBatchEntry::from)
+                .collect(),
+            lookup_inners: proof.lookup_inners.iter().map(GoInnerOp::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a CompressedBatchEntry> for GoCompressedBatchEntry<'a> {
+    fn from(entry: &'a CompressedBatchEntry) -> Self {
+        let proof = entry.proof.as_ref().map(|proof| match proof {
+            compressed_batch_entry::Proof::Exist(p) => GoCompressedBatchEntryProof::Exist(p.into()),
+            compressed_batch_entry::Proof::Nonexist(p) => {
+                GoCompressedBatchEntryProof::Nonexist(p.into())
+            }
+        });
+
+        Self { proof }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The output of `json.Marshal` for the `MerkleProof` built by the Go
+    /// program next to it, which mirrors the proof built by [`go_merkle_proof`].
+    const GO_MERKLE_PROOF_JSON: &str =
+        include_str!("../../../tests/support/ics23/go_merkle_proof.json");
+
+    fn exist(proof: ExistenceProof) -> CommitmentProof {
+        CommitmentProof {
+            proof: Some(Proof::Exist(proof)),
+        }
+    }
+
+    fn iavl_leaf(prefix: Vec<u8>) -> LeafOp {
+        LeafOp {
+            hash: 1,
+            prehash_key: 0,
+            prehash_value: 1,
+            length: 1,
+            prefix,
+        }
+    }
+
+    /// An existence proof of a client state, a non-existence proof of another
+    /// one, and the proof of the `ibc` store root in the multistore.
+    fn go_merkle_proof() -> MerkleProof {
+        MerkleProof {
+            proofs: vec![
+                exist(ExistenceProof {
+                    key: b"clients/07-tendermint-0/clientState".to_vec(),
+                    value: vec![0x0A, 0x01, 0x01],
+                    leaf: Some(iavl_leaf(vec![0x00, 0x02, 0x02])),
+                    path: vec![InnerOp {
+                        hash: 1,
+                        prefix: vec![0x02, 0x04, 0x02],
+                        suffix: vec![0x20],
+                    }],
+                }),
+                CommitmentProof {
+                    proof: Some(Proof::Nonexist(NonExistenceProof {
+                        key: b"clients/07-tendermint-1/clientState".to_vec(),
+                        left: None,
+                        right: Some(ExistenceProof {
+                            key: b"clients/07-tendermint-2/clientState".to_vec(),
+                            value: vec![0x0A, 0x01, 0x02],
+                            leaf: Some(iavl_leaf(vec![0x00, 0x02, 0x02])),
+                            path: vec![],
+                        }),
+                    })),
+                },
+                exist(ExistenceProof {
+                    key: b"ibc".to_vec(),
+                    value: vec![0xDE, 0xAD, 0xBE, 0xEF],
+                    leaf: Some(iavl_leaf(vec![0x00])),
+                    path: vec![],
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn to_go_merkle_json_matches_go_relayer() {
+        let proof = go_merkle_proof();
+
+        assert_eq!(
+            proof.to_go_merkle_json_string(),
+            GO_MERKLE_PROOF_JSON.trim_end()
+        );
+
+        let expected: Value = serde_json::from_str(GO_MERKLE_PROOF_JSON).unwrap();
+        assert_eq!(proof.to_go_merkle_json(), expected);
+    }
+
+    #[test]
+    fn to_go_merkle_json_omits_defaults() {
+        assert_eq!(
+            MerkleProof { proofs: vec![] }.to_go_merkle_json_string(),
+            "{}"
+        );
+
+        let proof = MerkleProof {
+            proofs: vec![CommitmentProof { proof: None }],
+        };
+        assert_eq!(
+            proof.to_go_merkle_json_string(),
+            r#"{"proofs":[{"Proof":null}]}"#
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use crate::prelude::*;
+use core::fmt;
+use subtle_encoding::{Encoding, Hex};
 use tendermint::merkle::proof::Proof as TendermintProof;
 
 use ibc_proto::ibc::core::commitment::v1::MerklePath;
@@ -6,13 +8,14 @@ use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
 use ibc_proto::ibc::core::commitment::v1::MerkleRoot;
 use ics23::commitment_proof::Proof;
 use ics23::{
-    calculate_existence_root, verify_membership, verify_non_membership, CommitmentProof,
-    NonExistenceProof,
+    batch_entry, calculate_existence_root, verify_membership, verify_non_membership,
+    CommitmentProof, ExistenceProof, HashOp, InnerOp, LeafOp, LengthOp, NonExistenceProof,
 };
 
 use crate::core::ics23_commitment::commitment::{CommitmentPrefix, CommitmentRoot};
-use crate::core::ics23_commitment::error::Error;
+use crate::core::ics23_commitment::error::{Error, VerificationFailureReason};
 use crate::core::ics23_commitment::specs::ProofSpecs;
+use crate::Height;
 
 pub fn apply_prefix(prefix: &CommitmentPrefix, mut path: Vec<String>) -> MerklePath {
     let mut key_path: Vec<String> = vec![format!("{:?}", prefix)];
@@ -67,7 +70,156 @@ impl From<MerkleProof> for RawMerkleProof {
     }
 }
 
+/// A step of a proof verification trace, recording the hash computed by
+/// applying an inner operation of one of the commitment proofs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    /// The position of the commitment proof in [`MerkleProof::proofs`].
+    pub proof_index: usize,
+    /// The position of the inner operation in the path of the proof.
+    pub inner_index: usize,
+    /// The inner operation applied in this step.
+    pub op: InnerOp,
+    /// The hash of the child node the operation is applied to, i.e. the
+    /// leaf hash for the first operation of a proof.
+    pub child: Vec<u8>,
+    /// The hash computed by the operation.
+    pub hash: Vec<u8>,
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = |data: &[u8]| Hex::upper_case().encode_to_string(data).unwrap();
+
+        write!(
+            f,
+            "proof {} inner op {} ({:?}): {} -> {}",
+            self.proof_index,
+            self.inner_index,
+            HashOp::from_i32(self.op.hash).unwrap_or(HashOp::NoHash),
+            hex(&self.child),
+            hex(&self.hash),
+        )
+    }
+}
+
 impl MerkleProof {
+    /// Returns the key asserted by each of the commitment proofs, ordered
+    /// from leaf to root as in [`MerkleProof::proofs`]. Proofs that are
+    /// neither existence nor non-existence proofs do not carry a single
+    /// key and are skipped.
+    pub fn proven_keys(&self) -> Vec<Vec<u8>> {
+        self.proofs
+            .iter()
+            .filter_map(|proof| match &proof.proof {
+                Some(Proof::Exist(existence_proof)) => Some(existence_proof.key.clone()),
+                Some(Proof::Nonexist(non_existence_proof)) => Some(non_existence_proof.key.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the depth of the proof, i.e. the total number of inner
+    /// operations across all the commitment proofs. For a non-existence
+    /// proof, the deeper of its neighbouring existence proofs is counted,
+    /// and for a batch proof, its deepest entry.
+    pub fn depth(&self) -> usize {
+        self.proofs
+            .iter()
+            .map(|proof| match &proof.proof {
+                Some(Proof::Exist(existence_proof)) => existence_proof.path.len(),
+                Some(Proof::Nonexist(non_existence_proof)) => {
+                    non_existence_depth(non_existence_proof)
+                }
+                Some(Proof::Batch(batch_proof)) => batch_proof
+                    .entries
+                    .iter()
+                    .map(|entry| match &entry.proof {
+                        Some(batch_entry::Proof::Exist(existence_proof)) => {
+                            existence_proof.path.len()
+                        }
+                        Some(batch_entry::Proof::Nonexist(non_existence_proof)) => {
+                            non_existence_depth(non_existence_proof)
+                        }
+                        None => 0,
+                    })
+                    .max()
+                    .unwrap_or_default(),
+                Some(Proof::Compressed(_)) | None => 0,
+            })
+            .sum()
+    }
+
+    /// Verifies the membership of `value` at the given `path` under `prefix`,
+    /// like [`MerkleProof::verify_membership`], and returns alongside the
+    /// result a trace of the intermediate hashes computed by each inner
+    /// operation of the proofs, from the leaves up to the root, regardless
+    /// of whether the verification succeeded.
+    pub fn verify_with_trace(
+        &self,
+        specs: &ProofSpecs,
+        root: MerkleRoot,
+        prefix: &CommitmentPrefix,
+        path: Vec<String>,
+        value: Vec<u8>,
+    ) -> (Result<(), Error>, Vec<TraceStep>) {
+        let keys = apply_prefix(prefix, path);
+        let result = self.verify_membership(specs, root, keys, value, 0);
+
+        (result, self.trace())
+    }
+
+    fn trace(&self) -> Vec<TraceStep> {
+        let mut steps = Vec::new();
+
+        for (proof_index, proof) in self.proofs.iter().enumerate() {
+            let existence_proof = match &proof.proof {
+                Some(Proof::Exist(existence_proof)) => existence_proof,
+                Some(Proof::Nonexist(non_existence_proof)) => {
+                    match non_existence_proof
+                        .left
+                        .as_ref()
+                        .or(non_existence_proof.right.as_ref())
+                    {
+                        Some(existence_proof) => existence_proof,
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+
+            // Compute the hash of each node by calculating the root of the
+            // proof truncated to the path up to that node.
+            let hash_at = |depth: usize| {
+                let truncated = ExistenceProof {
+                    path: existence_proof.path[..depth].to_vec(),
+                    ..existence_proof.clone()
+                };
+
+                calculate_existence_root::<ics23::HostFunctionsManager>(&truncated)
+                    .unwrap_or_default()
+            };
+
+            let mut child = hash_at(0);
+
+            for (inner_index, op) in existence_proof.path.iter().enumerate() {
+                let hash = hash_at(inner_index + 1);
+
+                steps.push(TraceStep {
+                    proof_index,
+                    inner_index,
+                    op: op.clone(),
+                    child,
+                    hash: hash.clone(),
+                });
+
+                child = hash;
+            }
+        }
+
+        steps
+    }
+
     pub fn verify_membership(
         &self,
         specs: &ProofSpecs,
@@ -107,6 +259,9 @@ impl MerkleProof {
         {
             match &proof.proof {
                 Some(Proof::Exist(existence_proof)) => {
+                    check_existence(existence_proof, spec, key.as_bytes(), &value)
+                        .map_err(Error::verification_failure)?;
+
                     subroot =
                         calculate_existence_root::<ics23::HostFunctionsManager>(existence_proof)
                             .map_err(|_| Error::invalid_merkle_proof())?;
@@ -118,7 +273,9 @@ impl MerkleProof {
                         key.as_bytes(),
                         &value,
                     ) {
-                        return Err(Error::verification_failure());
+                        return Err(Error::verification_failure(
+                            VerificationFailureReason::Rejected,
+                        ));
                     }
                     value = subroot.clone();
                 }
@@ -127,7 +284,9 @@ impl MerkleProof {
         }
 
         if root.hash != subroot {
-            return Err(Error::verification_failure());
+            return Err(Error::verification_failure(
+                VerificationFailureReason::WrongRoot,
+            ));
         }
 
         Ok(())
@@ -173,7 +332,9 @@ impl MerkleProof {
                     &subroot,
                     key.as_bytes(),
                 ) {
-                    return Err(Error::verification_failure());
+                    return Err(Error::verification_failure(
+                        VerificationFailureReason::Rejected,
+                    ));
                 }
 
                 // verify membership proofs starting from index 1 with value = subroot
@@ -182,6 +343,302 @@ impl MerkleProof {
             _ => Err(Error::invalid_merkle_proof()),
         }
     }
+
+    /// Verifies the membership of `value` at the given `keys` against the
+    /// `root` of the trusted consensus state at `trusted_height`, checking
+    /// first that the proof was produced at that same height, as a root from
+    /// any other height, even an adjacent one, cannot verify the proof.
+    pub fn verify_membership_at_height(
+        &self,
+        specs: &ProofSpecs,
+        root: MerkleRoot,
+        trusted_height: Height,
+        proof_height: Height,
+        keys: MerklePath,
+        value: Vec<u8>,
+    ) -> Result<(), Error> {
+        check_proof_height(proof_height, trusted_height)?;
+        self.verify_membership(specs, root, keys, value, 0)
+    }
+
+    /// Verifies the non-membership at the given `keys` against the `root` of
+    /// the trusted consensus state at `trusted_height`, checking first that
+    /// the proof was produced at that same height.
+    pub fn verify_non_membership_at_height(
+        &self,
+        specs: &ProofSpecs,
+        root: MerkleRoot,
+        trusted_height: Height,
+        proof_height: Height,
+        keys: MerklePath,
+    ) -> Result<(), Error> {
+        check_proof_height(proof_height, trusted_height)?;
+        self.verify_non_membership(specs, root, keys)
+    }
+
+    /// Verifies the membership of `value` at the given `keys`, like
+    /// [`MerkleProof::verify_membership`], checking first that the
+    /// [`depth`](MerkleProof::depth) of the proof is at most `max_depth`, so
+    /// that an adversarial chain cannot make the verifier hash arbitrarily
+    /// deep proofs.
+    pub fn verify_membership_with_max_depth(
+        &self,
+        specs: &ProofSpecs,
+        root: MerkleRoot,
+        keys: MerklePath,
+        value: Vec<u8>,
+        max_depth: usize,
+    ) -> Result<(), Error> {
+        check_proof_depth(self, max_depth)?;
+        self.verify_membership(specs, root, keys, value, 0)
+    }
+
+    /// Verifies the non-membership at the given `keys`, like
+    /// [`MerkleProof::verify_non_membership`], checking first that the
+    /// [`depth`](MerkleProof::depth) of the proof is at most `max_depth`.
+    pub fn verify_non_membership_with_max_depth(
+        &self,
+        specs: &ProofSpecs,
+        root: MerkleRoot,
+        keys: MerklePath,
+        max_depth: usize,
+    ) -> Result<(), Error> {
+        check_proof_depth(self, max_depth)?;
+        self.verify_non_membership(specs, root, keys)
+    }
+
+    /// Verifies the membership of `value` at the given `keys`, like
+    /// [`MerkleProof::verify_membership`], but hashing the leaf and inner
+    /// nodes of the existence proofs with the given `hasher` instead of the
+    /// hash operations specified in the proofs.
+    ///
+    /// This is meant for experimental chains whose merkle trees use a hash
+    /// function that is not supported by ics23. Since the hash operations
+    /// are overridden, the proofs are not checked against any proof specs.
+    pub fn verify_membership_with_hasher(
+        &self,
+        root: MerkleRoot,
+        keys: MerklePath,
+        value: Vec<u8>,
+        start_index: usize,
+        hasher: Box<dyn Fn(&[u8]) -> Vec<u8>>,
+    ) -> Result<(), Error> {
+        // validate arguments
+        if self.proofs.is_empty() {
+            return Err(Error::empty_merkle_proof());
+        }
+        if root.hash.is_empty() {
+            return Err(Error::empty_merkle_root());
+        }
+        if keys.key_path.len() != self.proofs.len() {
+            return Err(Error::number_of_keys_mismatch());
+        }
+        if value.is_empty() {
+            return Err(Error::empty_verified_value());
+        }
+
+        let mut value = value;
+        // keys are represented from root-to-leaf
+        for (proof, key) in self
+            .proofs
+            .iter()
+            .zip(keys.key_path.iter().rev())
+            .skip(start_index)
+        {
+            match &proof.proof {
+                Some(Proof::Exist(existence_proof)) => {
+                    if existence_proof.key != key.as_bytes() {
+                        return Err(Error::verification_failure(
+                            VerificationFailureReason::KeyMismatch,
+                        ));
+                    }
+                    if existence_proof.value != value {
+                        return Err(Error::verification_failure(
+                            VerificationFailureReason::ValueMismatch,
+                        ));
+                    }
+
+                    value = calculate_existence_root_with_hasher(existence_proof, &*hasher)?;
+                }
+                _ => return Err(Error::invalid_merkle_proof()),
+            }
+        }
+
+        if root.hash != value {
+            return Err(Error::verification_failure(
+                VerificationFailureReason::WrongRoot,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn non_existence_depth(proof: &NonExistenceProof) -> usize {
+    let left = proof.left.as_ref().map_or(0, |left| left.path.len());
+    let right = proof.right.as_ref().map_or(0, |right| right.path.len());
+
+    left.max(right)
+}
+
+fn check_proof_height(proof_height: Height, trusted_height: Height) -> Result<(), Error> {
+    if proof_height != trusted_height {
+        return Err(Error::height_mismatch(proof_height, trusted_height));
+    }
+
+    Ok(())
+}
+
+/// Checks that the depth of the proof, as given by [`MerkleProof::depth`],
+/// does not exceed the given maximum depth.
+fn check_proof_depth(proof: &MerkleProof, max_depth: usize) -> Result<(), Error> {
+    let depth = proof.depth();
+    if depth > max_depth {
+        return Err(Error::proof_too_deep(depth, max_depth));
+    }
+
+    Ok(())
+}
+
+/// Checks the existence proof against the expected key and value and the
+/// proof spec, mirroring the checks done by ics23 so that the reason of a
+/// failure can be reported.
+fn check_existence(
+    proof: &ExistenceProof,
+    spec: &ics23::ProofSpec,
+    key: &[u8],
+    value: &[u8],
+) -> Result<(), VerificationFailureReason> {
+    if let (Some(leaf), Some(leaf_spec)) = (&proof.leaf, &spec.leaf_spec) {
+        if leaf.hash != leaf_spec.hash
+            || leaf.prehash_key != leaf_spec.prehash_key
+            || leaf.prehash_value != leaf_spec.prehash_value
+            || leaf.length != leaf_spec.length
+        {
+            return Err(VerificationFailureReason::SpecViolation(
+                "leaf operation does not match the leaf spec".to_string(),
+            ));
+        }
+        if !leaf.prefix.starts_with(&leaf_spec.prefix) {
+            return Err(VerificationFailureReason::SpecViolation(
+                "leaf prefix does not match the leaf spec".to_string(),
+            ));
+        }
+    }
+
+    if let Some(inner_spec) = &spec.inner_spec {
+        if proof.path.iter().any(|inner| inner.hash != inner_spec.hash) {
+            return Err(VerificationFailureReason::SpecViolation(
+                "inner operation hash does not match the inner spec".to_string(),
+            ));
+        }
+    }
+
+    let depth = proof.path.len();
+    if spec.min_depth > 0 && depth < spec.min_depth as usize {
+        return Err(VerificationFailureReason::SpecViolation(format!(
+            "proof depth {depth} is below the minimum depth {}",
+            spec.min_depth
+        )));
+    }
+    if spec.max_depth > 0 && depth > spec.max_depth as usize {
+        return Err(VerificationFailureReason::SpecViolation(format!(
+            "proof depth {depth} exceeds the maximum depth {}",
+            spec.max_depth
+        )));
+    }
+
+    if proof.key != key {
+        return Err(VerificationFailureReason::KeyMismatch);
+    }
+    if proof.value != value {
+        return Err(VerificationFailureReason::ValueMismatch);
+    }
+
+    Ok(())
+}
+
+fn calculate_existence_root_with_hasher(
+    proof: &ExistenceProof,
+    hasher: &dyn Fn(&[u8]) -> Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let leaf = proof
+        .leaf
+        .as_ref()
+        .ok_or_else(Error::invalid_merkle_proof)?;
+
+    let mut hash = apply_leaf_with_hasher(leaf, &proof.key, &proof.value, hasher)?;
+    for inner in &proof.path {
+        hash = apply_inner_with_hasher(inner, &hash, hasher);
+    }
+
+    Ok(hash)
+}
+
+fn apply_leaf_with_hasher(
+    leaf: &LeafOp,
+    key: &[u8],
+    value: &[u8],
+    hasher: &dyn Fn(&[u8]) -> Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let mut data = leaf.prefix.clone();
+    data.extend(prepare_leaf_data(
+        leaf.prehash_key,
+        leaf.length,
+        key,
+        hasher,
+    )?);
+    data.extend(prepare_leaf_data(
+        leaf.prehash_value,
+        leaf.length,
+        value,
+        hasher,
+    )?);
+
+    Ok(hash_with_hasher(leaf.hash, &data, hasher))
+}
+
+fn apply_inner_with_hasher(
+    inner: &InnerOp,
+    child: &[u8],
+    hasher: &dyn Fn(&[u8]) -> Vec<u8>,
+) -> Vec<u8> {
+    let mut data = inner.prefix.clone();
+    data.extend_from_slice(child);
+    data.extend_from_slice(&inner.suffix);
+
+    hash_with_hasher(inner.hash, &data, hasher)
+}
+
+fn prepare_leaf_data(
+    prehash: i32,
+    length: i32,
+    data: &[u8],
+    hasher: &dyn Fn(&[u8]) -> Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let hashed = hash_with_hasher(prehash, data, hasher);
+
+    match LengthOp::from_i32(length) {
+        Some(LengthOp::NoPrefix) => Ok(hashed),
+        Some(LengthOp::VarProto) => {
+            let mut prefixed = Vec::new();
+            prost::encoding::encode_varint(hashed.len() as u64, &mut prefixed);
+            prefixed.extend(hashed);
+            Ok(prefixed)
+        }
+        Some(LengthOp::Require32Bytes) if hashed.len() == 32 => Ok(hashed),
+        Some(LengthOp::Require64Bytes) if hashed.len() == 64 => Ok(hashed),
+        _ => Err(Error::invalid_merkle_proof()),
+    }
+}
+
+/// Hashes `data` with `hasher`, unless `hash_op` is [`HashOp::NoHash`].
+fn hash_with_hasher(hash_op: i32, data: &[u8], hasher: &dyn Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    if hash_op == HashOp::NoHash as i32 {
+        data.to_vec()
+    } else {
+        hasher(data)
+    }
 }
 
 // TODO move to ics23
@@ -262,3 +719,421 @@ pub fn convert_tm_to_ics_merkle_proof(tm_proof: &TendermintProof) -> Result<Merk
 
     Ok(MerkleProof::from(RawMerkleProof { proofs }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::core::ics23_commitment::error::ErrorDetail;
+    use crate::core::ics23_commitment::mock::build_tendermint_tree;
+
+    use crate::core::ics24_host::identifier::ClientId;
+    use crate::core::ics24_host::path::ClientStatePath;
+
+    fn existence_proof(key: &[u8]) -> CommitmentProof {
+        CommitmentProof {
+            proof: Some(Proof::Exist(ExistenceProof {
+                key: key.to_vec(),
+                value: vec![0x0A, 0x01, 0xFF],
+                leaf: None,
+                path: vec![],
+            })),
+        }
+    }
+
+    /// A trivial hasher that reverses its input, standing in for a hash
+    /// function that is not supported by ics23.
+    fn reverse_hasher(data: &[u8]) -> Vec<u8> {
+        data.iter().rev().copied().collect()
+    }
+
+    #[test]
+    fn verify_membership_with_custom_hasher() {
+        let leaf = LeafOp {
+            hash: HashOp::Sha256 as i32,
+            prehash_key: HashOp::NoHash as i32,
+            prehash_value: HashOp::Sha256 as i32,
+            length: LengthOp::VarProto as i32,
+            prefix: vec![0x00],
+        };
+
+        let inner = InnerOp {
+            hash: HashOp::Sha256 as i32,
+            prefix: vec![0x01],
+            suffix: vec![0x02],
+        };
+
+        let proof = ExistenceProof {
+            key: b"key".to_vec(),
+            value: b"value".to_vec(),
+            leaf: Some(leaf),
+            path: vec![inner],
+        };
+
+        // leaf = reverse(0x00 || len(key) || key || len(value) || reverse(value))
+        let mut leaf_data = vec![0x00, 3];
+        leaf_data.extend_from_slice(b"key");
+        leaf_data.push(5);
+        leaf_data.extend(reverse_hasher(b"value"));
+        let leaf_hash = reverse_hasher(&leaf_data);
+
+        // root = reverse(0x01 || leaf || 0x02)
+        let mut inner_data = vec![0x01];
+        inner_data.extend(leaf_hash);
+        inner_data.push(0x02);
+        let root_hash = reverse_hasher(&inner_data);
+
+        let merkle_proof = MerkleProof {
+            proofs: vec![CommitmentProof {
+                proof: Some(Proof::Exist(proof)),
+            }],
+        };
+
+        let keys = MerklePath {
+            key_path: vec!["key".to_string()],
+        };
+
+        merkle_proof
+            .verify_membership_with_hasher(
+                MerkleRoot {
+                    hash: root_hash.clone(),
+                },
+                keys.clone(),
+                b"value".to_vec(),
+                0,
+                Box::new(reverse_hasher),
+            )
+            .unwrap();
+
+        let mut wrong_root = root_hash;
+        wrong_root[0] ^= 0xFF;
+
+        assert!(merkle_proof
+            .verify_membership_with_hasher(
+                MerkleRoot { hash: wrong_root },
+                keys,
+                b"value".to_vec(),
+                0,
+                Box::new(reverse_hasher),
+            )
+            .is_err());
+    }
+
+    fn tendermint_leaf_proof(leaf: LeafOp) -> (MerkleProof, MerkleRoot) {
+        let existence_proof = ExistenceProof {
+            key: b"key".to_vec(),
+            value: b"value".to_vec(),
+            leaf: Some(leaf),
+            path: vec![],
+        };
+
+        let root = calculate_existence_root::<ics23::HostFunctionsManager>(&existence_proof)
+            .expect("valid existence proof");
+
+        let proof = MerkleProof {
+            proofs: vec![CommitmentProof {
+                proof: Some(Proof::Exist(existence_proof)),
+            }],
+        };
+
+        (proof, MerkleRoot { hash: root })
+    }
+
+    fn failure_reason(result: Result<(), Error>) -> VerificationFailureReason {
+        match result.expect_err("expected verification to fail").detail() {
+            ErrorDetail::VerificationFailure(e) => e.reason.clone(),
+            e => panic!("expected verification failure, got {e}"),
+        }
+    }
+
+    #[test]
+    fn verify_membership_failure_reasons() {
+        let spec = ics23::tendermint_spec();
+        let specs = ProofSpecs::from(vec![spec.clone()]);
+        let leaf = spec.leaf_spec.expect("tendermint spec has a leaf spec");
+
+        let (proof, root) = tendermint_leaf_proof(leaf.clone());
+
+        let keys = MerklePath {
+            key_path: vec!["key".to_string()],
+        };
+
+        proof
+            .verify_membership(&specs, root.clone(), keys.clone(), b"value".to_vec(), 0)
+            .unwrap();
+
+        let mut wrong_root = root.clone();
+        wrong_root.hash[0] ^= 0xFF;
+
+        assert_eq!(
+            failure_reason(proof.verify_membership(
+                &specs,
+                wrong_root,
+                keys.clone(),
+                b"value".to_vec(),
+                0
+            )),
+            VerificationFailureReason::WrongRoot
+        );
+
+        let other_keys = MerklePath {
+            key_path: vec!["other".to_string()],
+        };
+
+        assert_eq!(
+            failure_reason(proof.verify_membership(
+                &specs,
+                root.clone(),
+                other_keys,
+                b"value".to_vec(),
+                0
+            )),
+            VerificationFailureReason::KeyMismatch
+        );
+
+        assert_eq!(
+            failure_reason(proof.verify_membership(
+                &specs,
+                root.clone(),
+                keys.clone(),
+                b"other".to_vec(),
+                0
+            )),
+            VerificationFailureReason::ValueMismatch
+        );
+
+        let (sha512_proof, sha512_root) = tendermint_leaf_proof(LeafOp {
+            hash: HashOp::Sha512 as i32,
+            ..leaf
+        });
+
+        assert!(matches!(
+            failure_reason(sha512_proof.verify_membership(
+                &specs,
+                sha512_root,
+                keys,
+                b"value".to_vec(),
+                0
+            )),
+            VerificationFailureReason::SpecViolation(_)
+        ));
+    }
+
+    #[test]
+    fn verify_membership_at_mismatched_height() {
+        let spec = ics23::tendermint_spec();
+        let specs = ProofSpecs::from(vec![spec.clone()]);
+        let leaf = spec.leaf_spec.expect("tendermint spec has a leaf spec");
+
+        let (proof, root) = tendermint_leaf_proof(leaf);
+
+        let keys = MerklePath {
+            key_path: vec!["key".to_string()],
+        };
+
+        let proof_height = Height::new(0, 10).unwrap();
+        let trusted_height = proof_height.increment();
+
+        proof
+            .verify_membership_at_height(
+                &specs,
+                root.clone(),
+                proof_height,
+                proof_height,
+                keys.clone(),
+                b"value".to_vec(),
+            )
+            .unwrap();
+
+        let result = proof.verify_membership_at_height(
+            &specs,
+            root,
+            trusted_height,
+            proof_height,
+            keys,
+            b"value".to_vec(),
+        );
+
+        match result.expect_err("expected height mismatch").detail() {
+            ErrorDetail::HeightMismatch(e) => {
+                assert_eq!(e.proof, proof_height);
+                assert_eq!(e.trusted, trusted_height);
+            }
+            e => panic!("expected height mismatch, got {e}"),
+        }
+    }
+
+    #[test]
+    fn depth_of_hand_built_proof() {
+        let inner = |prefix: u8| InnerOp {
+            hash: HashOp::Sha256 as i32,
+            prefix: vec![prefix, 0x02],
+            suffix: vec![0x20],
+        };
+
+        // A hand-built proof shaped like a client state proof: an existence
+        // proof with four dummy inner nodes, followed by an existence proof
+        // of the `ibc` store root with two dummy inner nodes.
+        let proof = MerkleProof {
+            proofs: vec![
+                CommitmentProof {
+                    proof: Some(Proof::Exist(ExistenceProof {
+                        key: b"clients/07-tendermint-0/clientState".to_vec(),
+                        value: vec![0x0A, 0x01, 0x01],
+                        leaf: None,
+                        path: vec![inner(0x02), inner(0x04), inner(0x06), inner(0x08)],
+                    })),
+                },
+                CommitmentProof {
+                    proof: Some(Proof::Exist(ExistenceProof {
+                        key: b"ibc".to_vec(),
+                        value: vec![0xDE, 0xAD, 0xBE, 0xEF],
+                        leaf: None,
+                        path: vec![inner(0x01), inner(0x01)],
+                    })),
+                },
+            ],
+        };
+
+        assert_eq!(proof.depth(), 6);
+
+        let non_existence = MerkleProof {
+            proofs: vec![CommitmentProof {
+                proof: Some(Proof::Nonexist(NonExistenceProof {
+                    key: b"missing".to_vec(),
+                    left: Some(ExistenceProof {
+                        key: b"left".to_vec(),
+                        value: vec![0x01],
+                        leaf: None,
+                        path: vec![inner(0x02)],
+                    }),
+                    right: Some(ExistenceProof {
+                        key: b"right".to_vec(),
+                        value: vec![0x01],
+                        leaf: None,
+                        path: vec![inner(0x02), inner(0x04), inner(0x06)],
+                    }),
+                })),
+            }],
+        };
+
+        assert_eq!(non_existence.depth(), 3);
+    }
+
+    #[test]
+    fn verify_membership_with_max_depth() {
+        let pairs = (0..8)
+            .map(|i| {
+                (
+                    format!("key-{i}").into_bytes(),
+                    format!("value-{i}").into_bytes(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let (root, proofs) = build_tendermint_tree(&pairs).unwrap();
+        let proof = &proofs[0];
+        let depth = proof.depth();
+        assert!(depth > 1);
+
+        let specs = ProofSpecs::from(vec![ics23::tendermint_spec()]);
+        let root = MerkleRoot::from(root);
+        let keys = MerklePath {
+            key_path: vec!["key-0".to_string()],
+        };
+
+        proof
+            .verify_membership_with_max_depth(
+                &specs,
+                root.clone(),
+                keys.clone(),
+                b"value-0".to_vec(),
+                depth,
+            )
+            .unwrap();
+
+        let result =
+            proof.verify_membership_with_max_depth(&specs, root, keys, b"value-0".to_vec(), 1);
+
+        match result.expect_err("expected proof to be too deep").detail() {
+            ErrorDetail::ProofTooDeep(e) => {
+                assert_eq!(e.depth, depth);
+                assert_eq!(e.max_depth, 1);
+            }
+            e => panic!("expected proof too deep, got {e}"),
+        }
+    }
+
+    #[test]
+    fn verify_with_trace_of_wrong_root() {
+        let spec = ics23::tendermint_spec();
+        let specs = ProofSpecs::from(vec![spec.clone(), spec.clone()]);
+        let leaf = spec.leaf_spec.expect("tendermint spec has a leaf spec");
+
+        let inner = |sibling: u8| InnerOp {
+            hash: HashOp::Sha256 as i32,
+            prefix: vec![0x01],
+            suffix: vec![sibling; 32],
+        };
+
+        let proof = MerkleProof {
+            proofs: vec![
+                CommitmentProof {
+                    proof: Some(Proof::Exist(ExistenceProof {
+                        key: b"key".to_vec(),
+                        value: b"value".to_vec(),
+                        leaf: Some(leaf.clone()),
+                        path: vec![inner(0xAA), inner(0xBB)],
+                    })),
+                },
+                CommitmentProof {
+                    proof: Some(Proof::Exist(ExistenceProof {
+                        key: b"ibc".to_vec(),
+                        value: vec![0xDE, 0xAD, 0xBE, 0xEF],
+                        leaf: Some(leaf),
+                        path: vec![inner(0xCC)],
+                    })),
+                },
+            ],
+        };
+
+        let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+
+        let (result, trace) = proof.verify_with_trace(
+            &specs,
+            MerkleRoot {
+                hash: vec![0x00; 32],
+            },
+            &prefix,
+            vec!["key".to_string()],
+            b"value".to_vec(),
+        );
+
+        assert!(result.is_err());
+        assert_eq!(trace.len(), proof.depth());
+
+        // Each step of a proof is applied to the hash computed by the previous one.
+        assert_eq!(trace[1].child, trace[0].hash);
+        assert_eq!((trace[2].proof_index, trace[2].inner_index), (1, 0));
+    }
+
+    #[test]
+    fn proven_keys_of_hand_built_proof() {
+        let path = ClientStatePath(ClientId::default()).to_string();
+
+        // A hand-built proof shaped like a client state proof, with dummy
+        // leaves and no inner nodes, followed by an empty commitment proof.
+        let proof = MerkleProof {
+            proofs: vec![
+                existence_proof(path.as_bytes()),
+                existence_proof(b"ibc"),
+                CommitmentProof { proof: None },
+            ],
+        };
+
+        assert_eq!(
+            proof.proven_keys(),
+            vec![path.as_bytes().to_vec(), b"ibc".to_vec()]
+        );
+    }
+}
@@ -1 +1,154 @@
+//! Helpers to build commitment roots and proofs from known key-value pairs,
+//! without a live chain, for self-contained verification tests.
 
+use crate::prelude::*;
+
+use ics23::commitment_proof::Proof;
+use ics23::{calculate_existence_root, CommitmentProof, ExistenceProof, InnerOp};
+
+use crate::core::ics23_commitment::commitment::CommitmentRoot;
+use crate::core::ics23_commitment::error::Error;
+use crate::core::ics23_commitment::merkle::MerkleProof;
+
+/// Builds a simple merkle tree from the given key-value pairs, hashed as
+/// specified by [`ics23::tendermint_spec`], and returns its root together
+/// with an existence proof for each pair.
+///
+/// The pairs are sorted by key, and the proofs are returned in that order.
+pub fn build_tendermint_tree(
+    pairs: &[(Vec<u8>, Vec<u8>)],
+) -> Result<(CommitmentRoot, Vec<MerkleProof>), Error> {
+    if pairs.is_empty() {
+        return Err(Error::empty_verified_value());
+    }
+
+    let leaf = ics23::tendermint_spec()
+        .leaf_spec
+        .ok_or_else(Error::invalid_merkle_proof)?;
+
+    let mut pairs = pairs.to_vec();
+    pairs.sort_by(|(key_a, _), (key_b, _)| key_a.cmp(key_b));
+
+    let proofs = pairs
+        .into_iter()
+        .map(|(key, value)| ExistenceProof {
+            key,
+            value,
+            leaf: Some(leaf.clone()),
+            path: vec![],
+        })
+        .collect::<Vec<_>>();
+
+    let (root, proofs) = build_subtree(proofs)?;
+
+    let proofs = proofs
+        .into_iter()
+        .map(|proof| MerkleProof {
+            proofs: vec![CommitmentProof {
+                proof: Some(Proof::Exist(proof)),
+            }],
+        })
+        .collect();
+
+    Ok((CommitmentRoot::from(root), proofs))
+}
+
+/// Builds the subtree over the given leaf proofs, splitting them as in a
+/// Tendermint simple merkle tree, and returns the subtree root with the
+/// proofs extended up to it.
+fn build_subtree(mut proofs: Vec<ExistenceProof>) -> Result<(Vec<u8>, Vec<ExistenceProof>), Error> {
+    if proofs.len() > 1 {
+        let right = proofs.split_off(split_point(proofs.len()));
+
+        let (left_hash, mut left) = build_subtree(proofs)?;
+        let (right_hash, mut right) = build_subtree(right)?;
+
+        for proof in &mut left {
+            proof.path.push(InnerOp {
+                hash: ics23::HashOp::Sha256 as i32,
+                prefix: vec![0x01],
+                suffix: right_hash.clone(),
+            });
+        }
+
+        for proof in &mut right {
+            let mut prefix = vec![0x01];
+            prefix.extend_from_slice(&left_hash);
+
+            proof.path.push(InnerOp {
+                hash: ics23::HashOp::Sha256 as i32,
+                prefix,
+                suffix: vec![],
+            });
+        }
+
+        proofs = left;
+        proofs.append(&mut right);
+    }
+
+    let root = calculate_existence_root::<ics23::HostFunctionsManager>(&proofs[0])
+        .map_err(|_| Error::invalid_merkle_proof())?;
+
+    Ok((root, proofs))
+}
+
+/// The largest power of two strictly less than `len`, as used by Tendermint
+/// to split the leaves of a simple merkle tree.
+fn split_point(len: usize) -> usize {
+    let mut point = 1;
+    while point * 2 < len {
+        point *= 2;
+    }
+    point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc_proto::ibc::core::commitment::v1::MerklePath;
+
+    use crate::core::ics23_commitment::specs::ProofSpecs;
+
+    #[test]
+    fn verify_membership_in_three_leaf_tree() {
+        let pairs = vec![
+            (b"c".to_vec(), b"value-c".to_vec()),
+            (b"a".to_vec(), b"value-a".to_vec()),
+            (b"b".to_vec(), b"value-b".to_vec()),
+        ];
+
+        let (root, proofs) = build_tendermint_tree(&pairs).unwrap();
+
+        let specs = ProofSpecs::from(vec![ics23::tendermint_spec()]);
+
+        for ((key, value), proof) in [("a", "value-a"), ("b", "value-b"), ("c", "value-c")]
+            .iter()
+            .zip(proofs.iter())
+        {
+            proof
+                .verify_membership(
+                    &specs,
+                    root.clone().into(),
+                    MerklePath {
+                        key_path: vec![key.to_string()],
+                    },
+                    value.as_bytes().to_vec(),
+                    0,
+                )
+                .unwrap();
+        }
+
+        assert!(proofs[0]
+            .verify_membership(
+                &specs,
+                root.into(),
+                MerklePath {
+                    key_path: vec!["a".to_string()],
+                },
+                b"value-b".to_vec(),
+                0,
+            )
+            .is_err());
+    }
+}
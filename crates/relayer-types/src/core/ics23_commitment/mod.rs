@@ -1,8 +1,13 @@
 //! ICS 23: Commitment implementation of a cryptographic scheme that verifies
 //! state transitions between chains.
 
+pub mod batch;
 pub mod commitment;
 pub mod error;
+pub mod go_json;
 pub mod merkle;
 pub mod mock;
+pub mod package;
+pub mod proof_json;
+pub mod registry;
 pub mod specs;
@@ -0,0 +1,178 @@
+//! A proof packaged together with everything needed to verify it, so that it
+//! can be transported across processes and verified on its own.
+
+use crate::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::ics23_commitment::commitment::{
+    CommitmentPrefix, CommitmentProofBytes, CommitmentRoot,
+};
+use crate::core::ics23_commitment::error::Error;
+use crate::core::ics23_commitment::merkle::apply_prefix;
+use crate::core::ics23_commitment::specs::ProofSpecs;
+
+/// A membership proof of `value` at `path` under `prefix`, bundled with the
+/// `root` it commits to and the proof `specs` of the chain it was queried
+/// from, which are needed to verify it as well.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiablePackage {
+    pub root: CommitmentRoot,
+    #[serde(with = "prefix_bytes")]
+    pub prefix: CommitmentPrefix,
+    pub path: String,
+    #[serde(with = "crate::serializers::hex_upper_or_bytes")]
+    pub value: Vec<u8>,
+    #[serde(with = "proof_bytes")]
+    pub proof: CommitmentProofBytes,
+    pub specs: ProofSpecs,
+}
+
+impl VerifiablePackage {
+    /// Verifies that the packaged `value` is committed to at `path` under
+    /// `prefix` in the state committed to by `root`.
+    pub fn verify(&self) -> Result<(), Error> {
+        self.proof.verify_membership(
+            &self.specs,
+            &self.root,
+            apply_prefix(&self.prefix, vec![self.path.clone()]),
+            self.value.clone(),
+        )
+    }
+}
+
+/// Serializes a [`CommitmentPrefix`] as its raw bytes, since its own
+/// serialization is a lossy rendering meant for display.
+mod prefix_bytes {
+    use crate::prelude::*;
+
+    use serde::{de, Deserializer, Serializer};
+
+    use crate::core::ics23_commitment::commitment::CommitmentPrefix;
+    use crate::serializers::hex_upper_or_bytes;
+
+    pub fn serialize<S: Serializer>(
+        prefix: &CommitmentPrefix,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        hex_upper_or_bytes::serialize(prefix.as_bytes(), serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<CommitmentPrefix, D::Error> {
+        let bytes: Vec<u8> = hex_upper_or_bytes::deserialize(deserializer)?;
+        CommitmentPrefix::try_from(bytes).map_err(de::Error::custom)
+    }
+}
+
+/// Serializes [`CommitmentProofBytes`] as its raw bytes, rejecting an empty
+/// proof on deserialization.
+mod proof_bytes {
+    use crate::prelude::*;
+
+    use serde::{de, Deserializer, Serializer};
+
+    use crate::core::ics23_commitment::commitment::CommitmentProofBytes;
+    use crate::serializers::hex_upper_or_bytes;
+
+    pub fn serialize<S: Serializer>(
+        proof: &CommitmentProofBytes,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        hex_upper_or_bytes::serialize(Vec::<u8>::from(proof.clone()), serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<CommitmentProofBytes, D::Error> {
+        let bytes: Vec<u8> = hex_upper_or_bytes::deserialize(deserializer)?;
+        CommitmentProofBytes::try_from(bytes).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::core::ics23_commitment::merkle::MerkleProof;
+    use crate::core::ics23_commitment::mock::build_tendermint_tree;
+
+    /// Captures a package as a chain would produce it: the value is stored
+    /// under `path` in a module store, whose root is in turn stored under the
+    /// prefix in the app store committed to by the packaged root.
+    fn captured_package() -> VerifiablePackage {
+        let path = "clients/07-tendermint-0/clientState".to_string();
+        let value = b"client-state".to_vec();
+
+        let (store_root, store_proofs) = build_tendermint_tree(&[
+            (path.as_bytes().to_vec(), value.clone()),
+            (b"connections/connection-0".to_vec(), b"connection".to_vec()),
+        ])
+        .unwrap();
+
+        let (app_root, app_proofs) = build_tendermint_tree(&[
+            (b"ibc".to_vec(), store_root.into_vec()),
+            (b"bank".to_vec(), b"bank-root".to_vec()),
+        ])
+        .unwrap();
+
+        // `store_proofs` are sorted by key, `clients/..` coming first, and so
+        // are `app_proofs`, `bank` coming before `ibc`.
+        let proof = MerkleProof {
+            proofs: vec![
+                store_proofs[0].proofs[0].clone(),
+                app_proofs[1].proofs[0].clone(),
+            ],
+        };
+
+        VerifiablePackage {
+            root: app_root,
+            prefix: CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap(),
+            path,
+            value,
+            proof: CommitmentProofBytes::try_from(proof).unwrap(),
+            specs: ProofSpecs::from(vec![ics23::tendermint_spec(), ics23::tendermint_spec()]),
+        }
+    }
+
+    #[test]
+    fn verify_captured_package() {
+        let package = captured_package();
+        assert!(package.verify().is_ok());
+
+        let tampered = VerifiablePackage {
+            value: b"other-client-state".to_vec(),
+            ..package.clone()
+        };
+        assert!(tampered.verify().is_err());
+
+        let tampered = VerifiablePackage {
+            prefix: CommitmentPrefix::try_from(b"bank".to_vec()).unwrap(),
+            ..package
+        };
+        assert!(tampered.verify().is_err());
+    }
+
+    #[test]
+    fn serde_json_round_trip() {
+        let package = captured_package();
+
+        let json = serde_json::to_string(&package).unwrap();
+        let decoded: VerifiablePackage = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded, package);
+        assert!(decoded.verify().is_ok());
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let package = captured_package();
+
+        let bytes = bincode::serialize(&package).unwrap();
+        let decoded: VerifiablePackage = bincode::deserialize(&bytes).unwrap();
+
+        assert_eq!(decoded, package);
+        assert!(decoded.verify().is_ok());
+    }
+}
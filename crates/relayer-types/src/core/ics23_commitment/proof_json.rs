@@ -0,0 +1,133 @@
+//! Serde helpers for (de)serializing a Tendermint merkle [`Proof`] as JSON,
+//! with the key and data of every proof op encoded as hex strings, which are
+//! serialized in upper case and deserialized regardless of their case.
+//!
+//! This is meant for human-friendly test fixtures, and can be used on a
+//! field through `#[serde(with = "ibc_relayer_types::core::ics23_commitment::proof_json")]`.
+
+use crate::prelude::*;
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use subtle_encoding::{Encoding, Hex};
+use tendermint::merkle::proof::{Proof, ProofOp};
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HexProof {
+    ops: Vec<HexProofOp>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HexProofOp {
+    field_type: String,
+    key: String,
+    data: String,
+}
+
+pub fn serialize<S>(proof: &Proof, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let hex = Hex::upper_case();
+
+    let ops = proof
+        .ops
+        .iter()
+        .map(|op| {
+            Ok(HexProofOp {
+                field_type: op.field_type.clone(),
+                key: hex.encode_to_string(&op.key).map_err(S::Error::custom)?,
+                data: hex.encode_to_string(&op.data).map_err(S::Error::custom)?,
+            })
+        })
+        .collect::<Result<Vec<_>, S::Error>>()?;
+
+    HexProof { ops }.serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Proof, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let hex = Hex::upper_case();
+
+    let ops = HexProof::deserialize(deserializer)?
+        .ops
+        .into_iter()
+        .map(|op| {
+            Ok(ProofOp {
+                field_type: op.field_type,
+                key: hex
+                    .decode(op.key.to_uppercase())
+                    .map_err(D::Error::custom)?,
+                data: hex
+                    .decode(op.data.to_uppercase())
+                    .map_err(D::Error::custom)?,
+            })
+        })
+        .collect::<Result<Vec<_>, D::Error>>()?;
+
+    Ok(Proof { ops })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fixture {
+        #[serde(with = "super")]
+        proof: Proof,
+    }
+
+    #[test]
+    fn proof_json_round_trip() {
+        let fixture = Fixture {
+            proof: Proof {
+                ops: vec![
+                    ProofOp {
+                        field_type: "ics23:iavl".to_string(),
+                        key: b"clients/07-tendermint-0/clientState".to_vec(),
+                        data: vec![0x0A, 0x01, 0xFF],
+                    },
+                    ProofOp {
+                        field_type: "ics23:simple".to_string(),
+                        key: b"ibc".to_vec(),
+                        data: vec![0x12, 0x34],
+                    },
+                ],
+            },
+        };
+
+        let json = serde_json::to_string(&fixture).unwrap();
+        assert!(json.contains(r#""key":"696263""#));
+        assert!(json.contains(r#""data":"0A01FF""#));
+
+        let parsed: Fixture = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, fixture);
+    }
+
+    #[test]
+    fn proof_json_lowercase_hex() {
+        let json =
+            r#"{"proof":{"ops":[{"field_type":"ics23:iavl","key":"696263","data":"0a01ff"}]}}"#;
+        let mixed =
+            r#"{"proof":{"ops":[{"field_type":"ics23:iavl","key":"696263","data":"0a01Ff"}]}}"#;
+
+        let expected = Fixture {
+            proof: Proof {
+                ops: vec![ProofOp {
+                    field_type: "ics23:iavl".to_string(),
+                    key: b"ibc".to_vec(),
+                    data: vec![0x0A, 0x01, 0xFF],
+                }],
+            },
+        };
+
+        assert_eq!(serde_json::from_str::<Fixture>(json).unwrap(), expected);
+        assert_eq!(serde_json::from_str::<Fixture>(mixed).unwrap(), expected);
+    }
+}
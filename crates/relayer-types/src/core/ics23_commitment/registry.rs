@@ -0,0 +1,138 @@
+//! A registry of the proof specs of the chains a relayer verifies proofs for,
+//! so that the specs of each chain are set up once rather than per verification.
+
+use crate::prelude::*;
+
+use alloc::collections::BTreeMap;
+use ibc_proto::ibc::core::commitment::v1::MerklePath;
+
+use crate::core::ics23_commitment::commitment::CommitmentRoot;
+use crate::core::ics23_commitment::error::Error;
+use crate::core::ics23_commitment::merkle::MerkleProof;
+use crate::core::ics23_commitment::specs::ProofSpecs;
+use crate::core::ics24_host::identifier::ChainId;
+
+/// Maps the identifier of each registered chain to its [`ProofSpecs`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProofSpecsRegistry {
+    specs: BTreeMap<ChainId, ProofSpecs>,
+}
+
+impl ProofSpecsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the proof specs of the chain with the given [`ChainId`],
+    /// returning the specs previously registered for it, if any.
+    pub fn register_specs(&mut self, chain_id: ChainId, specs: ProofSpecs) -> Option<ProofSpecs> {
+        self.specs.insert(chain_id, specs)
+    }
+
+    /// Returns the proof specs registered for the chain with the given [`ChainId`].
+    pub fn specs(&self, chain_id: &ChainId) -> Option<&ProofSpecs> {
+        self.specs.get(chain_id)
+    }
+
+    /// Verifies the membership of `value` at the given path in the state of the
+    /// chain with [`ChainId`] committed to by `root`, as with
+    /// [`MerkleProof::verify_membership`], using the proof specs registered
+    /// for that chain.
+    pub fn verify_membership(
+        &self,
+        chain_id: &ChainId,
+        proof: &MerkleProof,
+        root: &CommitmentRoot,
+        keys: MerklePath,
+        value: Vec<u8>,
+    ) -> Result<(), Error> {
+        let specs = self
+            .specs(chain_id)
+            .ok_or_else(|| Error::unregistered_proof_specs(chain_id.clone()))?;
+
+        proof.verify_membership(specs, root.clone().into(), keys, value, 0)
+    }
+
+    /// Verifies the non-membership of the given path in the state of the chain
+    /// with [`ChainId`] committed to by `root`, as with
+    /// [`MerkleProof::verify_non_membership`], using the proof specs registered
+    /// for that chain.
+    pub fn verify_non_membership(
+        &self,
+        chain_id: &ChainId,
+        proof: &MerkleProof,
+        root: &CommitmentRoot,
+        keys: MerklePath,
+    ) -> Result<(), Error> {
+        let specs = self
+            .specs(chain_id)
+            .ok_or_else(|| Error::unregistered_proof_specs(chain_id.clone()))?;
+
+        proof.verify_non_membership(specs, root.clone().into(), keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::core::ics23_commitment::error::ErrorDetail;
+    use crate::core::ics23_commitment::mock::build_tendermint_tree;
+
+    #[test]
+    fn verify_membership_with_registered_specs() {
+        let pairs = vec![
+            (b"a".to_vec(), b"value-a".to_vec()),
+            (b"b".to_vec(), b"value-b".to_vec()),
+        ];
+
+        let (root, proofs) = build_tendermint_tree(&pairs).unwrap();
+
+        let tendermint_chain = ChainId::new("tendermint".to_string(), 1);
+        let iavl_chain = ChainId::new("iavl".to_string(), 1);
+        let unknown_chain = ChainId::new("unknown".to_string(), 1);
+
+        let mut registry = ProofSpecsRegistry::new();
+        registry.register_specs(
+            tendermint_chain.clone(),
+            ProofSpecs::from(vec![ics23::tendermint_spec()]),
+        );
+        registry.register_specs(
+            iavl_chain.clone(),
+            ProofSpecs::from(vec![ics23::iavl_spec()]),
+        );
+
+        let path = || MerklePath {
+            key_path: vec!["a".to_string()],
+        };
+
+        registry
+            .verify_membership(
+                &tendermint_chain,
+                &proofs[0],
+                &root,
+                path(),
+                b"value-a".to_vec(),
+            )
+            .unwrap();
+
+        assert!(registry
+            .verify_membership(&iavl_chain, &proofs[0], &root, path(), b"value-a".to_vec())
+            .is_err());
+
+        match registry
+            .verify_membership(
+                &unknown_chain,
+                &proofs[0],
+                &root,
+                path(),
+                b"value-a".to_vec(),
+            )
+            .unwrap_err()
+            .detail()
+        {
+            ErrorDetail::UnregisteredProofSpecs(e) => assert_eq!(e.chain_id, unknown_chain),
+            e => panic!("expected unregistered proof specs, got {e}"),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// Prints the `go_merkle_proof.json` fixture of the tests of the
+// `core::ics23_commitment::go_json` module, the `json.Marshal` encoding of
+// the same `MerkleProof` as the one built by these tests.
+//
+// Run it from a Go module requiring `github.com/cosmos/ibc-go/v7`:
+//
+//	go run go_merkle_proof.go > go_merkle_proof.json
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+
+	commitmenttypes "github.com/cosmos/ibc-go/v7/modules/core/23-commitment/types"
+	ics23 "github.com/cosmos/ics23/go"
+)
+
+func iavlLeaf(prefix []byte) *ics23.LeafOp {
+	return &ics23.LeafOp{
+		Hash:         ics23.HashOp_SHA256,
+		PrehashKey:   ics23.HashOp_NO_HASH,
+		PrehashValue: ics23.HashOp_SHA256,
+		Length:       ics23.LengthOp_VAR_PROTO,
+		Prefix:       prefix,
+	}
+}
+
+func main() {
+	proof := commitmenttypes.MerkleProof{
+		Proofs: []*ics23.CommitmentProof{
+			{
+				Proof: &ics23.CommitmentProof_Exist{
+					Exist: &ics23.ExistenceProof{
+						Key:   []byte("clients/07-tendermint-0/clientState"),
+						Value: []byte{0x0a, 0x01, 0x01},
+						Leaf:  iavlLeaf([]byte{0x00, 0x02, 0x02}),
+						Path: []*ics23.InnerOp{
+							{
+								Hash:   ics23.HashOp_SHA256,
+								Prefix: []byte{0x02, 0x04, 0x02},
+								Suffix: []byte{0x20},
+							},
+						},
+					},
+				},
+			},
+			{
+				Proof: &ics23.CommitmentProof_Nonexist{
+					Nonexist: &ics23.NonExistenceProof{
+						Key: []byte("clients/07-tendermint-1/clientState"),
+						Right: &ics23.ExistenceProof{
+							Key:   []byte("clients/07-tendermint-2/clientState"),
+							Value: []byte{0x0a, 0x01, 0x02},
+							Leaf:  iavlLeaf([]byte{0x00, 0x02, 0x02}),
+						},
+					},
+				},
+			},
+			{
+				Proof: &ics23.CommitmentProof_Exist{
+					Exist: &ics23.ExistenceProof{
+						Key:   []byte("ibc"),
+						Value: []byte{0xde, 0xad, 0xbe, 0xef},
+						Leaf:  iavlLeaf([]byte{0x00}),
+					},
+				},
+			},
+		},
+	}
+
+	out, err := json.Marshal(&proof)
+	if err != nil {
+		panic(err)
+	}
+
+	fmt.Println(string(out))
+}
@@ -18,7 +18,9 @@ use serde_derive::{Deserialize, Serialize};
 use tendermint_light_client_verifier::types::TrustThreshold;
 
 use ibc_relayer_types::core::ics23_commitment::specs::ProofSpecs;
-use ibc_relayer_types::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer_types::core::ics24_host::identifier::{
+    ChainId, ChannelId, ClientId, ConnectionId, PortId,
+};
 use ibc_relayer_types::timestamp::ZERO_DURATION;
 
 use crate::chain::ChainType;
@@ -30,7 +32,7 @@ use crate::keyring::Store;
 
 pub use error::Error;
 
-pub use filter::PacketFilter;
+pub use filter::{ClientFilters, ConnectionFilters, DenomFilter, PacketFilter};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GasPrice {
@@ -143,6 +145,15 @@ impl Config {
         self.chains.iter().find(|c| c.id == *id)
     }
 
+    /// Returns whether the packet workers relaying from the given chain
+    /// should clear pending packets on start, taking into account the
+    /// chain-level override of the global `mode.packets.clear_on_start`.
+    pub fn clear_on_start_for(&self, id: &ChainId) -> bool {
+        self.find_chain(id)
+            .and_then(|c| c.clear_on_start)
+            .unwrap_or(self.mode.packets.clear_on_start)
+    }
+
     pub fn find_chain_mut(&mut self, id: &ChainId) -> Option<&mut ChainConfig> {
         self.chains.iter_mut().find(|c| c.id == *id)
     }
@@ -162,6 +173,32 @@ impl Config {
         }
     }
 
+    /// Returns true if filtering is disabled or if packets are allowed on
+    /// channels with the given connection hops on [`ChainId`].
+    /// Returns false otherwise.
+    pub fn connection_hops_allowed(
+        &self,
+        chain_id: &ChainId,
+        connection_hops: &[ConnectionId],
+    ) -> bool {
+        match self.find_chain(chain_id) {
+            Some(chain_config) => chain_config
+                .connection_filter
+                .are_connection_hops_allowed(connection_hops),
+            None => false,
+        }
+    }
+
+    /// Returns true if filtering is disabled or if the relayer services the
+    /// client [`ClientId`] hosted on [`ChainId`].
+    /// Returns false otherwise.
+    pub fn client_allowed(&self, chain_id: &ChainId, client_id: &ClientId) -> bool {
+        match self.find_chain(chain_id) {
+            Some(chain_config) => chain_config.client_filter.is_client_allowed(client_id),
+            None => false,
+        }
+    }
+
     pub fn chains_map(&self) -> BTreeMap<&ChainId, &ChainConfig> {
         self.chains.iter().map(|c| (&c.id, c)).collect()
     }
@@ -396,6 +433,12 @@ pub struct ChainConfig {
     #[serde(default)]
     pub memo_prefix: Memo,
 
+    /// Overrides the global `mode.packets.clear_on_start` setting for the
+    /// packet workers relaying from this chain. Inherits the global setting
+    /// when left unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clear_on_start: Option<bool>,
+
     // Note: These last few need to be last otherwise we run into `ValueAfterTable` error when serializing to TOML.
     //       That's because these are all tables and have to come last when serializing.
     #[serde(
@@ -413,6 +456,15 @@ pub struct ChainConfig {
     #[serde(default)]
     pub sequential_batch_tx: bool,
 
+    /// The clients that the relayer services on this chain, as a list of
+    /// client identifiers or wildcards. An empty list allows every client.
+    #[serde(
+        default,
+        rename = "clients",
+        skip_serializing_if = "ClientFilters::is_empty"
+    )]
+    pub client_filter: ClientFilters,
+
     // these two need to be last otherwise we run into `ValueAfterTable` error when serializing to TOML
     /// The trust threshold defines what fraction of the total voting power of a known
     /// and trusted validator set is sufficient for a commit to be accepted going forward.
@@ -424,6 +476,12 @@ pub struct ChainConfig {
     #[serde(default)]
     pub packet_filter: PacketFilter,
 
+    #[serde(default, skip_serializing_if = "ConnectionFilters::is_empty")]
+    pub connection_filter: ConnectionFilters,
+
+    #[serde(default, skip_serializing_if = "DenomFilter::is_allow_all")]
+    pub denom_filter: DenomFilter,
+
     #[serde(default)]
     pub address_type: AddressType,
     #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
@@ -1,4 +1,5 @@
 use flex_error::{define_error, TraceError};
+use ibc_relayer_types::core::ics24_host::error::ValidationError;
 
 define_error! {
     Error {
@@ -13,5 +14,39 @@ define_error! {
         Encode
             [ TraceError<toml::ser::Error> ]
             |_| { "invalid configuration" },
+
+        MissingChannelSeparator
+            { qualified: String }
+            |e| { format_args!("invalid qualified channel `{}`, expected `<port_id>/<channel_id>`", e.qualified) },
+
+        InvalidQualifiedChannel
+            { qualified: String }
+            [ ValidationError ]
+            |e| { format_args!("invalid identifier in qualified channel `{}`", e.qualified) },
+
+        MissingFilterPolicy
+            { arg: String }
+            |e| { format_args!("invalid packet filter `{}`, expected `<policy>:<port_id>/<channel_id>,...`", e.arg) },
+
+        UnknownFilterPolicy
+            { policy: String }
+            |e| { format_args!("unknown packet filter policy `{}`, expected `allow` or `deny`", e.policy) },
+
+        InvalidFilterPattern
+            { pattern: String }
+            [ TraceError<serde::de::value::Error> ]
+            |e| { format_args!("invalid port or channel pattern `{}`", e.pattern) },
+
+        FilterRoundTrip
+            { toml: String, parsed_toml: String }
+            |e| { format_args!("packet filter changed after being serialized as:\n{}\nand parsed back as:\n{}", e.toml, e.parsed_toml) },
+
+        FetchFilter
+            { url: String, reason: String }
+            |e| { format_args!("failed to fetch packet filter from `{}`: {}", e.url, e.reason) },
+
+        FetchFilterStatus
+            { url: String, status: u16 }
+            |e| { format_args!("failed to fetch packet filter from `{}`: unexpected HTTP status {}", e.url, e.status) },
     }
 }
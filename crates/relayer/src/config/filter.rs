@@ -1,14 +1,31 @@
 //! Custom `serde` deserializer for `FilterMatch`
 
 use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::str::FromStr;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use ibc_relayer_types::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc_relayer_types::core::ics04_channel::channel::IdentifiedChannelEnd;
+use ibc_relayer_types::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+use ibc_relayer_types::core::ics24_host::validate::{
+    validate_channel_identifier, validate_port_identifier,
+};
+use ibc_relayer_types::Height;
 use itertools::Itertools;
+use moka::sync::Cache as MokaCache;
+use serde::de::IntoDeserializer;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::config::Error;
+
+/// How long to wait for a remote packet filter policy to be fetched.
+#[cfg(feature = "remote-filter")]
+pub const REMOTE_FILTER_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Represents the ways in which packets can be filtered.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(
     rename_all = "lowercase",
     tag = "policy",
@@ -20,365 +37,4213 @@ pub enum PacketFilter {
     Allow(ChannelFilters),
     /// Deny packets from the specified channels.
     Deny(ChannelFilters),
+    /// Allow or deny packets according to the first matching rule,
+    /// denying packets from the channels that no rule matches.
+    Rules(Vec<FilterRule>),
     /// Allow any & all packets.
     AllowAll,
 }
 
-impl Default for PacketFilter {
-    /// By default, allows all channels & ports.
-    fn default() -> Self {
-        Self::AllowAll
-    }
+/// Returns the action of the first of the `rules` matching the given
+/// [`PortId`]-[`ChannelId`] pair among those for which `applies` holds,
+/// denying it if no such rule matches.
+fn rules_allow(
+    rules: &[FilterRule],
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    applies: impl Fn(&FilterRule) -> bool,
+) -> bool {
+    rules
+        .iter()
+        .find(|rule| applies(rule) && rule.matches(port_id, channel_id))
+        .map_or(false, |rule| rule.action == FilterAction::Allow)
 }
 
-impl PacketFilter {
-    /// Returns true if the packets can be relayed on the channel with [`PortId`] and [`ChannelId`],
-    /// false otherwise.
-    pub fn is_allowed(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
-        match self {
-            PacketFilter::Allow(filters) => filters.matches((port_id, channel_id)),
-            PacketFilter::Deny(filters) => !filters.matches((port_id, channel_id)),
-            PacketFilter::AllowAll => true,
+/// A rule of a [`PacketFilter::Rules`] policy, either allowing or denying
+/// packets on the channels matching both its port and channel patterns.
+///
+/// Rules are evaluated in order and only the first matching rule applies,
+/// so an earlier rule takes precedence over any later rule.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilterRule {
+    pub action: FilterAction,
+    pub port: PortFilterMatch,
+    pub channel: ChannelFilterMatch,
+    /// An allow rule only applies to packets with at most this many bytes of
+    /// data, and a deny rule only to packets with more bytes of data.
+    /// Rules without a limit ignore the packet data size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_data_bytes: Option<usize>,
+    /// The revision height of the source chain from which this rule is active.
+    /// Rules without it are always active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<u64>,
+    /// The time, in seconds since the Unix epoch, at which this rule expires.
+    /// Rules without it never expire.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+impl FilterRule {
+    pub fn new(action: FilterAction, port: PortFilterMatch, channel: ChannelFilterMatch) -> Self {
+        Self {
+            action,
+            port,
+            channel,
+            max_data_bytes: None,
+            min_height: None,
+            expires_at: None,
         }
     }
-}
 
-/// The internal representation of channel filter policies.
-#[derive(Clone, Debug, Default, Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct ChannelFilters(Vec<(PortFilterMatch, ChannelFilterMatch)>);
+    pub fn with_max_data_bytes(self, max_data_bytes: usize) -> Self {
+        Self {
+            max_data_bytes: Some(max_data_bytes),
+            ..self
+        }
+    }
 
-impl ChannelFilters {
-    /// Create a new filter from the given list of port/channel filters.
-    pub fn new(filters: Vec<(PortFilterMatch, ChannelFilterMatch)>) -> Self {
-        Self(filters)
+    pub fn with_min_height(self, min_height: u64) -> Self {
+        Self {
+            min_height: Some(min_height),
+            ..self
+        }
     }
 
-    /// Returns the number of filters.
-    pub fn len(&self) -> usize {
-        self.0.len()
+    pub fn with_expires_at(self, expires_at: u64) -> Self {
+        Self {
+            expires_at: Some(expires_at),
+            ..self
+        }
     }
 
-    /// Returns true if there are no filters, false otherwise.
-    pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+    /// Indicates whether this rule has expired at the time `now`.
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        is_expired_at(self.expires_at, now)
     }
 
-    /// Indicates whether a match for the given [`PortId`]-[`ChannelId`] pair
-    /// exists in the filter policy.
-    pub fn matches(&self, channel_port: (&PortId, &ChannelId)) -> bool {
-        let (port_id, channel_id) = channel_port;
-        self.0.iter().any(|(port_filter, chan_filter)| {
-            port_filter.matches(port_id) && chan_filter.matches(channel_id)
-        })
+    /// Indicates whether this rule applies to all the packets of the channels
+    /// it matches, rather than only to some of them depending on their data
+    /// or height.
+    fn applies_to_channel(&self) -> bool {
+        self.action == FilterAction::Allow
+            || (self.max_data_bytes.is_none() && self.min_height.is_none())
     }
 
-    /// Indicates whether this filter policy contains only exact patterns.
-    #[inline]
-    pub fn is_exact(&self) -> bool {
-        self.0.iter().all(|(port_filter, channel_filter)| {
-            port_filter.is_exact() && channel_filter.is_exact()
-        })
+    fn is_active_at(&self, height: Height) -> bool {
+        self.min_height
+            .map_or(true, |min_height| height.revision_height() >= min_height)
     }
 
-    /// An iterator over the [`PortId`]-[`ChannelId`] pairs that don't contain wildcards.
-    pub fn iter_exact(&self) -> impl Iterator<Item = (&PortId, &ChannelId)> {
-        self.0.iter().filter_map(|port_chan_filter| {
-            if let &(FilterPattern::Exact(ref port_id), FilterPattern::Exact(ref chan_id)) =
-                port_chan_filter
-            {
-                Some((port_id, chan_id))
-            } else {
-                None
-            }
-        })
+    /// Indicates whether this rule applies to a packet with `data_len` bytes of data.
+    fn applies_to_data_len(&self, data_len: usize) -> bool {
+        match self.action {
+            FilterAction::Allow => self.max_data_bytes.map_or(true, |max| data_len <= max),
+            FilterAction::Deny => self.max_data_bytes.map_or(true, |max| data_len > max),
+        }
+    }
+
+    /// Indicates whether this rule matches the given [`PortId`]-[`ChannelId`] pair.
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.port.matches(port_id) && self.channel.matches(channel_id)
     }
 }
 
-impl fmt::Display for ChannelFilters {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.0
-                .iter()
-                .map(|(pid, cid)| format!("{}/{}", pid, cid))
-                .join(", ")
-        )
+/// The action taken by a [`FilterRule`] on the packets of the channels it matches.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    Allow,
+    Deny,
+}
+
+/// The changes between two filters, as returned by [`PacketFilter::reconcile`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReconcilePlan {
+    /// Whether the policy of the filters differs, eg. from `allow` to `deny`.
+    pub policy_changed: bool,
+    /// The rules of the new filter which are not in the old one.
+    pub added: Vec<PolicyRule>,
+    /// The rules of the old filter which are not in the new one.
+    pub removed: Vec<PolicyRule>,
+}
+
+impl ReconcilePlan {
+    /// Returns true if the filters are the same, up to the order of their rules.
+    pub fn is_empty(&self) -> bool {
+        !self.policy_changed && self.added.is_empty() && self.removed.is_empty()
     }
 }
 
-impl Serialize for ChannelFilters {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        use serde::ser::SerializeSeq;
+/// A rule of a [`PacketFilter`], whichever its policy.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PolicyRule {
+    /// A rule of an `allow` or `deny` policy.
+    Channel(ChannelFilterRule),
+    /// A rule of a `rules` policy.
+    Ordered(FilterRule),
+}
+
+/// The semantics of the policy of a filter, as returned by [`PacketFilter::semantics`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FilterSemantics {
+    /// Packets are only relayed on the channels allowed by the filter.
+    AllowList,
+    /// Packets are relayed on all channels except those denied by the filter.
+    DenyList,
+    /// Packets are relayed on all channels.
+    Permissive,
+}
+
+/// Whether packets are relayed on the channels of a connection,
+/// as returned by [`PacketFilter::connection_coverage`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionCoverage {
+    /// Packets are allowed on all the channels of the connection.
+    FullyAllowed,
+    /// Packets are denied on all the channels of the connection.
+    FullyDenied,
+    /// Packets are allowed on some channels of the connection only.
+    PartiallyAllowed,
+}
+
+/// A set of channels of a port, as indexed by [`PacketFilter::to_port_index`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChannelAllowSet {
+    /// All the channels of the port.
+    All,
+    /// The channels with the given identifiers.
+    Exact(HashSet<ChannelId>),
+    /// The channels matching any of the given wildcards.
+    Pattern(Vec<Wildcard>),
+}
 
-        struct Pair<'a> {
-            a: &'a FilterPattern<PortId>,
-            b: &'a FilterPattern<ChannelId>,
+impl ChannelAllowSet {
+    /// Indicates whether the channel with [`ChannelId`] is in this set.
+    pub fn contains(&self, channel_id: &ChannelId) -> bool {
+        match self {
+            ChannelAllowSet::All => true,
+            ChannelAllowSet::Exact(channel_ids) => channel_ids.contains(channel_id),
+            ChannelAllowSet::Pattern(wildcards) => wildcards
+                .iter()
+                .any(|wildcard| wildcard.is_match(channel_id.as_str())),
         }
+    }
 
-        impl<'a> Serialize for Pair<'a> {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: Serializer,
-            {
-                let mut seq = serializer.serialize_seq(Some(2))?;
-                seq.serialize_element(self.a)?;
-                seq.serialize_element(self.b)?;
-                seq.end()
-            }
+    /// Adds the channels matching the given pattern to this set. Mixing exact
+    /// channels with wildcards turns the set into a pattern set, where each
+    /// exact channel is matched by a wildcard without any `*`.
+    fn insert(&mut self, channel: &ChannelFilterMatch) {
+        if channel.matches_everything() {
+            *self = ChannelAllowSet::All;
+            return;
         }
 
-        let mut outer_seq = serializer.serialize_seq(Some(self.0.len()))?;
+        match (&mut *self, channel) {
+            (ChannelAllowSet::All, _) => {}
+            (ChannelAllowSet::Exact(channel_ids), FilterPattern::Exact(channel_id)) => {
+                channel_ids.insert(channel_id.clone());
+            }
+            (ChannelAllowSet::Exact(channel_ids), FilterPattern::Wildcard(wildcard)) => {
+                let mut wildcards = channel_ids
+                    .iter()
+                    .filter_map(|channel_id| Wildcard::new(channel_id.to_string()).ok())
+                    .collect::<Vec<_>>();
+                wildcards.push(wildcard.clone());
 
-        for (port, channel) in &self.0 {
-            outer_seq.serialize_element(&Pair {
-                a: port,
-                b: channel,
-            })?;
+                *self = ChannelAllowSet::Pattern(wildcards);
+            }
+            (ChannelAllowSet::Pattern(wildcards), FilterPattern::Exact(channel_id)) => {
+                // Channel identifiers cannot contain `*`, so the wildcard matches it exactly
+                if let Ok(wildcard) = Wildcard::new(channel_id.to_string()) {
+                    wildcards.push(wildcard);
+                }
+            }
+            (ChannelAllowSet::Pattern(wildcards), FilterPattern::Wildcard(wildcard)) => {
+                wildcards.push(wildcard.clone());
+            }
         }
+    }
 
-        outer_seq.end()
+    fn from_pattern(channel: &ChannelFilterMatch) -> Self {
+        let mut set = ChannelAllowSet::Exact(HashSet::new());
+        set.insert(channel);
+        set
     }
 }
 
-/// Newtype wrapper for expressing wildcard patterns compiled to a [`regex::Regex`].
+/// The channels of a [`PacketFilter`] indexed by port, as returned by
+/// [`PacketFilter::to_port_index`].
 #[derive(Clone, Debug)]
-pub struct Wildcard {
-    pattern: String,
-    regex: regex::Regex,
+pub struct PortIndex {
+    /// Whether the channels in the sets are allowed, or denied.
+    action: FilterAction,
+    /// The channel sets of the ports specified exactly.
+    ports: HashMap<PortId, ChannelAllowSet>,
+    /// The channel sets of the ports specified with a wildcard.
+    wildcard_ports: Vec<(Wildcard, ChannelAllowSet)>,
+    /// The rules with an expiry, which are not materialized in the sets
+    /// since they stop matching once they expire, and are evaluated as is.
+    expiring: Vec<ChannelFilterRule>,
+    /// The rules of a `rules` filter, which cannot be materialized as sets
+    /// since they take precedence by their order, and are evaluated as is.
+    rules: Option<Vec<FilterRule>>,
 }
 
-impl Wildcard {
-    pub fn new(pattern: String) -> Result<Self, regex::Error> {
-        let escaped = regex::escape(&pattern).replace("\\*", "(?:.*)");
-        let regex = format!("^{escaped}$").parse()?;
-        Ok(Self { pattern, regex })
+impl PortIndex {
+    fn new(action: FilterAction) -> Self {
+        Self {
+            action,
+            ports: HashMap::new(),
+            wildcard_ports: Vec::new(),
+            expiring: Vec::new(),
+            rules: None,
+        }
     }
 
-    #[inline]
-    pub fn is_match(&self, text: &str) -> bool {
-        self.regex.is_match(text)
+    fn insert_rule(&mut self, rule: &ChannelFilterRule) {
+        if rule.expires_at.is_some() {
+            self.expiring.push(rule.clone());
+        } else {
+            self.insert(&rule.port, &rule.channel);
+        }
     }
-}
 
-impl FromStr for Wildcard {
-    type Err = regex::Error;
+    fn insert(&mut self, port: &PortFilterMatch, channel: &ChannelFilterMatch) {
+        match port {
+            FilterPattern::Exact(port_id) => {
+                self.ports
+                    .entry(port_id.clone())
+                    .and_modify(|set| set.insert(channel))
+                    .or_insert_with(|| ChannelAllowSet::from_pattern(channel));
+            }
+            FilterPattern::Wildcard(wildcard) => {
+                match self
+                    .wildcard_ports
+                    .iter_mut()
+                    .find(|(existing, _)| existing == wildcard)
+                {
+                    Some((_, set)) => set.insert(channel),
+                    None => self
+                        .wildcard_ports
+                        .push((wildcard.clone(), ChannelAllowSet::from_pattern(channel))),
+                }
+            }
+        }
+    }
 
-    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
-        Self::new(pattern.to_string())
+    /// Whether the channels in the sets of this index are allowed, or denied.
+    pub fn action(&self) -> FilterAction {
+        self.action
     }
-}
 
-impl fmt::Display for Wildcard {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.pattern)
+    /// Returns the set of channels of the port with [`PortId`] specified
+    /// exactly in the filter, if any.
+    pub fn get(&self, port_id: &PortId) -> Option<&ChannelAllowSet> {
+        self.ports.get(port_id)
+    }
+
+    /// Returns true if the packets can be relayed on the channel with [`PortId`]
+    /// and [`ChannelId`], as with [`PacketFilter::is_allowed`].
+    pub fn is_allowed(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.is_allowed_at_time(port_id, channel_id, SystemTime::now())
+    }
+
+    /// Returns true if the packets can be relayed on the channel with [`PortId`]
+    /// and [`ChannelId`] at the time `now`, as with [`PacketFilter::is_allowed_at_time`].
+    pub fn is_allowed_at_time(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        now: SystemTime,
+    ) -> bool {
+        if let Some(rules) = &self.rules {
+            return rules_allow(rules, port_id, channel_id, |rule| {
+                rule.applies_to_channel() && !rule.is_expired_at(now)
+            });
+        }
+
+        let in_exact_port = self
+            .ports
+            .get(port_id)
+            .map_or(false, |set| set.contains(channel_id));
+
+        let in_wildcard_port = || {
+            self.wildcard_ports.iter().any(|(wildcard, set)| {
+                wildcard.is_match(port_id.as_str()) && set.contains(channel_id)
+            })
+        };
+
+        let in_unexpired_rule = || {
+            self.expiring
+                .iter()
+                .any(|rule| !rule.is_expired_at(now) && rule.matches(port_id, channel_id))
+        };
+
+        let in_set = in_exact_port || in_wildcard_port() || in_unexpired_rule();
+
+        match self.action {
+            FilterAction::Allow => in_set,
+            FilterAction::Deny => !in_set,
+        }
     }
 }
 
-impl Serialize for Wildcard {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_str(&self.pattern)
+/// The default number of channels whose filter result is kept by a
+/// [`CachedPacketFilter`].
+pub const PACKET_FILTER_CACHE_CAPACITY: u64 = 1_000;
+
+/// A [`PacketFilter`] memoizing whether each channel is allowed, so that
+/// the patterns of the filter are matched only once per channel rather than
+/// on every packet relayed on it.
+///
+/// At most `capacity` channels are kept, the least recently used ones being
+/// evicted first.
+#[derive(Clone)]
+pub struct CachedPacketFilter {
+    filter: PacketFilter,
+    capacity: u64,
+    cache: MokaCache<(PortId, ChannelId), bool>,
+}
+
+impl CachedPacketFilter {
+    /// Wraps the given filter with a cache of at most `capacity` channels.
+    pub fn new(filter: PacketFilter, capacity: u64) -> Self {
+        Self {
+            filter,
+            capacity,
+            cache: MokaCache::new(capacity),
+        }
+    }
+
+    /// Returns the wrapped filter.
+    pub fn filter(&self) -> &PacketFilter {
+        &self.filter
+    }
+
+    /// Returns true if the packets can be relayed on the channel with [`PortId`]
+    /// and [`ChannelId`], as with [`PacketFilter::is_allowed`].
+    pub fn is_allowed(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        let key = (port_id.clone(), channel_id.clone());
+
+        if let Some(allowed) = self.cache.get(&key) {
+            return allowed;
+        }
+
+        let allowed = self.filter.is_allowed(port_id, channel_id);
+        self.cache.insert(key, allowed);
+        allowed
+    }
+
+    /// Replaces the wrapped filter, eg. when the configuration is reloaded,
+    /// discarding every result cached for the previous one.
+    ///
+    /// The cache is replaced rather than cleared, as it is shared with the
+    /// clones of this filter, which still wrap the previous one.
+    pub fn reload(&mut self, filter: PacketFilter) {
+        self.filter = filter;
+        self.cache = MokaCache::new(self.capacity);
     }
 }
 
-impl PartialEq for Wildcard {
-    fn eq(&self, other: &Self) -> bool {
-        self.pattern == other.pattern
+impl From<PacketFilter> for CachedPacketFilter {
+    fn from(filter: PacketFilter) -> Self {
+        Self::new(filter, PACKET_FILTER_CACHE_CAPACITY)
     }
 }
 
-/// Represents a single channel to be filtered in a [`ChannelFilters`] list.
-#[derive(Clone, Debug, PartialEq)]
-pub enum FilterPattern<T> {
-    /// A channel specified exactly with its [`PortId`] & [`ChannelId`].
-    Exact(T),
-    /// A glob of channel(s) specified with a wildcard in either or both [`PortId`] & [`ChannelId`].
-    Wildcard(Wildcard),
+impl fmt::Debug for CachedPacketFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedPacketFilter")
+            .field("filter", &self.filter)
+            .field("capacity", &self.capacity)
+            .field("cached", &self.cache.entry_count())
+            .finish()
+    }
 }
 
-impl<T> FilterPattern<T> {
-    /// Indicates whether this filter is specified in part with a wildcard.
-    pub fn is_wildcard(&self) -> bool {
-        matches!(self, Self::Wildcard(_))
+impl Default for PacketFilter {
+    /// By default, allows all channels & ports.
+    fn default() -> Self {
+        Self::AllowAll
     }
+}
 
-    /// Indicates whether this filter is specified as an exact match.
-    pub fn is_exact(&self) -> bool {
-        matches!(self, Self::Exact(_))
+impl PacketFilter {
+    /// Returns true if the packets can be relayed on the channel with [`PortId`] and [`ChannelId`],
+    /// false otherwise. Rules that have expired by now are ignored, as in
+    /// [`PacketFilter::is_allowed_at_time`].
+    ///
+    /// Rules with a `max_data_bytes` limit or a `min_height` only apply to some
+    /// of the packets of a channel, and are checked per packet by the relayer
+    /// with [`PacketFilter::is_packet_allowed`]. A channel is therefore
+    /// allowed here if any allow rule matches it, and only denied by the deny
+    /// rules without such conditions.
+    pub fn is_allowed(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.is_allowed_at_time(port_id, channel_id, SystemTime::now())
     }
 
-    /// Matches the given value via strict equality if the filter is an `Exact`, or via
-    /// wildcard matching if the filter is a `Pattern`.
-    pub fn matches(&self, value: &T) -> bool
-    where
-        T: PartialEq + ToString,
-    {
+    /// Returns true if a packet carrying `data` can be relayed on the channel
+    /// with [`PortId`] and [`ChannelId`], taking into account the
+    /// `max_data_bytes` limit of the matching filter rules, false otherwise.
+    pub fn is_allowed_with_data(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        data: &[u8],
+    ) -> bool {
+        let channel_port = (port_id, channel_id);
+        let now = SystemTime::now();
+
         match self {
-            FilterPattern::Exact(v) => value == v,
-            FilterPattern::Wildcard(regex) => regex.is_match(&value.to_string()),
+            PacketFilter::Allow(filters) => filters.matches_with(channel_port, |rule| {
+                rule.min_height.is_none()
+                    && rule.is_within_limit(data.len())
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::Deny(filters) => !filters.matches_with(channel_port, |rule| {
+                rule.min_height.is_none()
+                    && rule.is_beyond_limit(data.len())
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::Rules(rules) => rules_allow(rules, port_id, channel_id, |rule| {
+                rule.min_height.is_none()
+                    && rule.applies_to_data_len(data.len())
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::AllowAll => true,
         }
     }
 
-    /// Returns the contained value if this filter contains an `Exact` variant, or
-    /// `None` if it contains a `Pattern`.
-    pub fn exact_value(&self) -> Option<&T> {
+    /// Returns true if the packets can be relayed on the channel with [`PortId`]
+    /// and [`ChannelId`] when the source chain is at the given [`Height`], false
+    /// otherwise. Rules with a `min_height` are only active from that height on,
+    /// while rules without one are always active.
+    pub fn is_allowed_at_height(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        height: Height,
+    ) -> bool {
+        let channel_port = (port_id, channel_id);
+        let now = SystemTime::now();
+
         match self {
-            FilterPattern::Exact(value) => Some(value),
-            FilterPattern::Wildcard(_) => None,
+            PacketFilter::Allow(filters) => filters.matches_with(channel_port, |rule| {
+                rule.is_active_at(height) && !rule.is_expired_at(now)
+            }),
+            PacketFilter::Deny(filters) => !filters.matches_with(channel_port, |rule| {
+                rule.is_active_at(height)
+                    && rule.max_data_bytes.is_none()
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::Rules(rules) => rules_allow(rules, port_id, channel_id, |rule| {
+                rule.is_active_at(height)
+                    && (rule.action == FilterAction::Allow || rule.max_data_bytes.is_none())
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::AllowAll => true,
         }
     }
-}
 
-impl<T: fmt::Display> fmt::Display for FilterPattern<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    /// Returns true if a packet carrying `data`, sent when the source chain was
+    /// at the given [`Height`], can be relayed on the channel with [`PortId`]
+    /// and [`ChannelId`], false otherwise. This takes into account both the
+    /// `min_height` and the `max_data_bytes` of the matching filter rules, and
+    /// is the check performed by the relayer on every packet it relays.
+    pub fn is_packet_allowed(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        height: Height,
+        data: &[u8],
+    ) -> bool {
+        let channel_port = (port_id, channel_id);
+        let now = SystemTime::now();
+
         match self {
-            FilterPattern::Exact(value) => write!(f, "{}", value),
-            FilterPattern::Wildcard(regex) => write!(f, "{}", regex),
+            PacketFilter::Allow(filters) => filters.matches_with(channel_port, |rule| {
+                rule.is_active_at(height)
+                    && rule.is_within_limit(data.len())
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::Deny(filters) => !filters.matches_with(channel_port, |rule| {
+                rule.is_active_at(height)
+                    && rule.is_beyond_limit(data.len())
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::Rules(rules) => rules_allow(rules, port_id, channel_id, |rule| {
+                rule.is_active_at(height)
+                    && rule.applies_to_data_len(data.len())
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::AllowAll => true,
         }
     }
-}
 
-impl<T> Serialize for FilterPattern<T>
-where
-    T: ToString,
-{
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
+    /// Returns true if the packets can be relayed on the channel with [`PortId`]
+    /// and [`ChannelId`] at the time `now`, false otherwise. Rules that have
+    /// expired by then are ignored.
+    pub fn is_allowed_at_time(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        now: SystemTime,
+    ) -> bool {
+        let channel_port = (port_id, channel_id);
+
         match self {
-            FilterPattern::Exact(e) => serializer.serialize_str(&e.to_string()),
-            FilterPattern::Wildcard(t) => serializer.serialize_str(&t.to_string()),
+            PacketFilter::Allow(filters) => {
+                filters.matches_with(channel_port, |rule| !rule.is_expired_at(now))
+            }
+            PacketFilter::Deny(filters) => !filters.matches_with(channel_port, |rule| {
+                rule.min_height.is_none()
+                    && rule.max_data_bytes.is_none()
+                    && !rule.is_expired_at(now)
+            }),
+            PacketFilter::Rules(rules) => rules_allow(rules, port_id, channel_id, |rule| {
+                rule.applies_to_channel() && !rule.is_expired_at(now)
+            }),
+            PacketFilter::AllowAll => true,
         }
     }
-}
 
-/// Type alias for a [`FilterPattern`] containing a [`PortId`].
-pub type PortFilterMatch = FilterPattern<PortId>;
-/// Type alias for a [`FilterPattern`] containing a [`ChannelId`].
-pub type ChannelFilterMatch = FilterPattern<ChannelId>;
+    /// Returns true if the packets can be relayed on the channel with [`PortId`]
+    /// and [`ChannelId`] as in [`PacketFilter::is_allowed`], or if the channel
+    /// has no identifier yet, as is the case of a channel end whose identifier
+    /// is not assigned yet during the channel handshake.
+    ///
+    /// A channel with an identifier, including `channel-0`, is always subject
+    /// to the filter.
+    pub fn is_allowed_or_unassigned(
+        &self,
+        port_id: &PortId,
+        channel_id: Option<&ChannelId>,
+    ) -> bool {
+        channel_id.map_or(true, |channel_id| self.is_allowed(port_id, channel_id))
+    }
 
-impl<'de> Deserialize<'de> for PortFilterMatch {
-    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<PortFilterMatch, D::Error> {
-        deserializer.deserialize_string(port::PortFilterMatchVisitor)
+    /// Removes the rules that have expired at the time `now`.
+    pub fn prune_expired(&mut self, now: SystemTime) {
+        match self {
+            PacketFilter::Allow(filters) | PacketFilter::Deny(filters) => {
+                filters.prune_expired(now)
+            }
+            PacketFilter::Rules(rules) => rules.retain(|rule| !rule.is_expired_at(now)),
+            PacketFilter::AllowAll => {}
+        }
     }
-}
 
-impl<'de> Deserialize<'de> for ChannelFilterMatch {
-    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ChannelFilterMatch, D::Error> {
-        deserializer.deserialize_string(channel::ChannelFilterMatchVisitor)
+    /// Returns true if the packets can be relayed on the channel identified by
+    /// the given `<port_id>/<channel_id>` string, false otherwise.
+    pub fn is_allowed_qualified(&self, qualified: &str) -> Result<bool, Error> {
+        let (port_id, channel_id) = qualified
+            .split_once('/')
+            .ok_or_else(|| Error::missing_channel_separator(qualified.to_string()))?;
+
+        let port_id = PortId::from_str(port_id)
+            .map_err(|e| Error::invalid_qualified_channel(qualified.to_string(), e))?;
+        let channel_id = ChannelId::from_str(channel_id)
+            .map_err(|e| Error::invalid_qualified_channel(qualified.to_string(), e))?;
+
+        Ok(self.is_allowed(&port_id, &channel_id))
     }
-}
 
-pub(crate) mod port {
-    use super::*;
-    use ibc_relayer_types::core::ics24_host::identifier::PortId;
+    /// Parses a filter given on the command line in the form
+    /// `<policy>:<port>/<channel>,<port>/<channel>,...`, where the policy
+    /// is either `allow` or `deny`, e.g. `deny:transfer/channel-5,transfer/channel-9`.
+    /// Ports and channels may be given as wildcards.
+    pub fn from_cli_arg(s: &str) -> Result<PacketFilter, Error> {
+        let (policy, pairs) = s
+            .split_once(':')
+            .ok_or_else(|| Error::missing_filter_policy(s.to_string()))?;
 
-    pub struct PortFilterMatchVisitor;
+        let rules = pairs
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (port, channel) = pair
+                    .split_once('/')
+                    .ok_or_else(|| Error::missing_channel_separator(pair.to_string()))?;
 
-    impl<'de> de::Visitor<'de> for PortFilterMatchVisitor {
-        type Value = PortFilterMatch;
+                let port = PortFilterMatch::deserialize(port.into_deserializer())
+                    .map_err(|e| Error::invalid_filter_pattern(port.to_string(), e))?;
+                let channel = ChannelFilterMatch::deserialize(channel.into_deserializer())
+                    .map_err(|e| Error::invalid_filter_pattern(channel.to_string(), e))?;
 
-        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-            formatter.write_str("valid PortId or wildcard")
+                Ok(ChannelFilterRule::new(port, channel))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        match policy.trim() {
+            "allow" => Ok(PacketFilter::Allow(ChannelFilters::from_rules(rules))),
+            "deny" => Ok(PacketFilter::Deny(ChannelFilters::from_rules(rules))),
+            other => Err(Error::unknown_filter_policy(other.to_string())),
         }
+    }
 
-        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
-            if let Ok(port_id) = PortId::from_str(v) {
-                Ok(PortFilterMatch::Exact(port_id))
-            } else {
-                let wildcard = v.parse().map_err(E::custom)?;
-                Ok(PortFilterMatch::Wildcard(wildcard))
-            }
+    /// Returns a filter with the same policy, retaining only the rules
+    /// specified with exact port and channel identifiers.
+    pub fn exact_only(&self) -> PacketFilter {
+        match self {
+            PacketFilter::Allow(filters) => PacketFilter::Allow(filters.exact_only()),
+            PacketFilter::Deny(filters) => PacketFilter::Deny(filters.exact_only()),
+            PacketFilter::Rules(rules) => PacketFilter::Rules(
+                rules
+                    .iter()
+                    .filter(|rule| rule.port.is_exact() && rule.channel.is_exact())
+                    .cloned()
+                    .collect(),
+            ),
+            PacketFilter::AllowAll => PacketFilter::AllowAll,
         }
+    }
 
-        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
-            self.visit_str(&v)
+    /// Classifies the policy of this filter by whether it only allows the
+    /// channels it lists, allows all but the channels it lists, or allows all
+    /// channels. A `rules` policy denies the channels none of its rules match,
+    /// so it is classified as an allow list.
+    pub fn semantics(&self) -> FilterSemantics {
+        match self {
+            PacketFilter::Allow(_) | PacketFilter::Rules(_) => FilterSemantics::AllowList,
+            PacketFilter::Deny(_) => FilterSemantics::DenyList,
+            PacketFilter::AllowAll => FilterSemantics::Permissive,
         }
     }
-}
 
-pub(crate) mod channel {
-    use super::*;
-    use ibc_relayer_types::core::ics24_host::identifier::ChannelId;
+    /// Returns true if packets can be relayed on every one of the given
+    /// [`PortId`]-[`ChannelId`] pairs, e.g. all the channels of a connection.
+    pub fn covers_all(&self, channels: &[(PortId, ChannelId)]) -> bool {
+        channels
+            .iter()
+            .all(|(port_id, channel_id)| self.is_allowed(port_id, channel_id))
+    }
+
+    /// Returns an allow filter with an exact rule for each of the given
+    /// channels whose connection hops include the given [`ConnectionId`],
+    /// e.g. all the channels found on a chain, denying the channels of any
+    /// other connection.
+    pub fn from_connection_channels(
+        connection_id: &ConnectionId,
+        channels: &[IdentifiedChannelEnd],
+    ) -> PacketFilter {
+        let connection = ConnectionFilterMatch::Exact(connection_id.clone());
+
+        let channels = channels
+            .iter()
+            .filter(|channel| {
+                channel
+                    .channel_end
+                    .connection_hops()
+                    .iter()
+                    .any(|hop| connection.matches(hop))
+            })
+            .map(|channel| (channel.port_id.clone(), channel.channel_id.clone()))
+            .collect::<Vec<_>>();
+
+        PacketFilter::from_chain_channels(&channels)
+    }
+
+    /// Returns an allow filter with an exact rule for each of the given
+    /// [`PortId`]-[`ChannelId`] pairs, e.g. all the channels found on a chain,
+    /// to be used as a starting point for the filter of a newly added chain.
+    pub fn from_chain_channels(channels: &[(PortId, ChannelId)]) -> PacketFilter {
+        let rules = channels
+            .iter()
+            .map(|(port_id, channel_id)| {
+                ChannelFilterRule::new(
+                    FilterPattern::Exact(port_id.clone()),
+                    FilterPattern::Exact(channel_id.clone()),
+                )
+            })
+            .collect();
+
+        PacketFilter::Allow(ChannelFilters::empty().union(ChannelFilters::from_rules(rules)))
+    }
+
+    /// Lazily filters the given stream of [`PortId`]-[`ChannelId`] pairs,
+    /// yielding only the pairs on which packets can be relayed, without
+    /// buffering the stream.
+    pub fn filter_stream<'a, I>(&'a self, iter: I) -> impl Iterator<Item = (PortId, ChannelId)> + 'a
+    where
+        I: Iterator<Item = (PortId, ChannelId)> + 'a,
+    {
+        iter.filter(move |(port_id, channel_id)| self.is_allowed(port_id, channel_id))
+    }
+
+    /// Compiles an `allow` filter into a single anchored regex matching the
+    /// `<port_id>/<channel_id>` form of exactly the channels it allows, eg. to
+    /// match them in logs with external tools.
+    ///
+    /// Returns `None` for the other policies, for which the allowed channels
+    /// cannot simply be enumerated.
+    pub fn to_combined_regex(&self) -> Option<regex::Regex> {
+        let filters = match self {
+            PacketFilter::Allow(filters) => filters,
+            _ => return None,
+        };
+
+        let alternatives = filters
+            .0
+            .iter()
+            .map(|rule| {
+                format!(
+                    "(?:{}/{})",
+                    rule.port.regex_source(),
+                    rule.channel.regex_source()
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let combined = if alternatives.is_empty() {
+            // An allow filter without rules denies all channels
+            "^(?:a^)$".to_string()
+        } else {
+            format!("^(?:{})$", alternatives.join("|"))
+        };
+
+        combined.parse().ok()
+    }
+
+    /// Returns each [`PortId`]-[`ChannelId`] pair of the `universe`, in order,
+    /// together with whether this filter allows relaying packets on it.
+    pub fn decision_table(
+        &self,
+        universe: &[(PortId, ChannelId)],
+    ) -> Vec<(PortId, ChannelId, bool)> {
+        universe
+            .iter()
+            .map(|(port_id, channel_id)| {
+                (
+                    port_id.clone(),
+                    channel_id.clone(),
+                    self.is_allowed(port_id, channel_id),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns true if every channel of the `universe` that this filter allows
+    /// is also allowed by `other`, i.e. this filter is at least as strict as
+    /// `other` over the given [`PortId`]-[`ChannelId`] pairs.
+    pub fn is_subset_of(&self, other: &PacketFilter, universe: &[(PortId, ChannelId)]) -> bool {
+        universe.iter().all(|(port_id, channel_id)| {
+            !self.is_allowed(port_id, channel_id) || other.is_allowed(port_id, channel_id)
+        })
+    }
+
+    /// Classifies a connection by whether this filter allows packets on all,
+    /// none or only some of its given channels. A connection without any
+    /// channel is considered fully allowed.
+    pub fn connection_coverage(&self, channels: &[(PortId, ChannelId)]) -> ConnectionCoverage {
+        let allowed = channels
+            .iter()
+            .filter(|(port_id, channel_id)| self.is_allowed(port_id, channel_id))
+            .count();
+
+        if allowed == channels.len() {
+            ConnectionCoverage::FullyAllowed
+        } else if allowed == 0 {
+            ConnectionCoverage::FullyDenied
+        } else {
+            ConnectionCoverage::PartiallyAllowed
+        }
+    }
+
+    /// Returns true if this filter denies packets on every channel of the
+    /// canonical ICS-20 `transfer` port, which halts all token relaying.
+    ///
+    /// A rule only counts as covering every channel when its channel pattern
+    /// is the `*` wildcard, so eg. a deny rule for `transfer/channel-*` is
+    /// not considered to block the port.
+    pub fn blocks_transfer(&self) -> bool {
+        let transfer = PortId::transfer();
+
+        match self {
+            PacketFilter::Allow(filters) => {
+                !filters.0.iter().any(|rule| rule.port.matches(&transfer))
+            }
+            PacketFilter::Deny(filters) => filters.0.iter().any(|rule| {
+                rule.min_height.is_none()
+                    && rule.max_data_bytes.is_none()
+                    && rule.port.matches(&transfer)
+                    && rule.channel.matches_everything()
+            }),
+            PacketFilter::Rules(rules) => {
+                for rule in rules
+                    .iter()
+                    .filter(|rule| rule.applies_to_channel() && rule.port.matches(&transfer))
+                {
+                    if rule.action == FilterAction::Allow {
+                        return false;
+                    }
+
+                    if rule.channel.matches_everything() {
+                        return true;
+                    }
+                }
+
+                // The channels no rule matches are denied
+                true
+            }
+            PacketFilter::AllowAll => false,
+        }
+    }
+
+    /// Materializes this filter as sets of channels indexed by port, for fast
+    /// per-port decisions. The index answers the same as [`PacketFilter::is_allowed`].
+    pub fn to_port_index(&self) -> PortIndex {
+        match self {
+            PacketFilter::Allow(filters) => {
+                let mut index = PortIndex::new(FilterAction::Allow);
+                for rule in filters.0.iter() {
+                    index.insert_rule(rule);
+                }
+                index
+            }
+            PacketFilter::Deny(filters) => {
+                let mut index = PortIndex::new(FilterAction::Deny);
+                for rule in filters
+                    .0
+                    .iter()
+                    .filter(|rule| rule.min_height.is_none() && rule.max_data_bytes.is_none())
+                {
+                    index.insert_rule(rule);
+                }
+                index
+            }
+            PacketFilter::Rules(rules) => {
+                let mut index = PortIndex::new(FilterAction::Allow);
+                index.rules = Some(rules.clone());
+                index
+            }
+            PacketFilter::AllowAll => PortIndex::new(FilterAction::Deny),
+        }
+    }
+
+    /// Layers the `other` filter on top of this one, eg. a per-chain filter
+    /// on top of a shared base filter.
+    ///
+    /// The rules of `other` take precedence over the rules of this filter: a
+    /// channel matched by a rule of `other` is allowed or denied as `other`
+    /// says, otherwise as this filter says if one of its rules matches it.
+    /// The channels matched by neither are only allowed if neither filter is
+    /// an `allow` or `rules` filter, which deny the channels they do not match.
+    /// Concretely:
+    ///
+    /// - `allow_all` merged with any filter yields the other, more restrictive, filter.
+    /// - Two `allow` or two `deny` filters yield a filter with the same policy,
+    ///   made of the rules of both, see [`ChannelFilters::layer`].
+    /// - Otherwise, the filters yield a `rules` filter with the rules of `other`
+    ///   first, followed by the rules of this filter.
+    ///
+    /// Rules converted from an `allow` or `deny` filter to a `rules` filter keep
+    /// their port and channel patterns and their per-packet limits, i.e. their
+    /// `max_data_bytes`, `min_height` and `expires_at`.
+    pub fn merge(self, other: PacketFilter) -> PacketFilter {
+        use PacketFilter::*;
+
+        match (self, other) {
+            (AllowAll, other) => other,
+            (this, AllowAll) => this,
+            (Allow(a), Allow(b)) => Allow(a.layer(b)),
+            (Deny(a), Deny(b)) => Deny(a.layer(b)),
+            (this, other) => Rules(
+                other
+                    .into_ordered_rules()
+                    .into_iter()
+                    .chain(this.into_ordered_rules())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Converts the rules of this filter into ordered [`FilterRule`]s.
+    fn into_ordered_rules(self) -> Vec<FilterRule> {
+        match self {
+            PacketFilter::Allow(filters) => filters.into_ordered_rules(FilterAction::Allow),
+            PacketFilter::Deny(filters) => filters.into_ordered_rules(FilterAction::Deny),
+            PacketFilter::Rules(rules) => rules,
+            PacketFilter::AllowAll => Vec::new(),
+        }
+    }
+
+    /// Computes the changes from this filter to the `new` one, eg. when a
+    /// filter is reloaded from the configuration file: the rules which are
+    /// only in the new filter, the rules which are only in this one, and
+    /// whether the policy itself changed, eg. from `allow` to `deny`.
+    ///
+    /// Rules present in both filters are not reported, even if their
+    /// position changed.
+    pub fn reconcile(&self, new: &PacketFilter) -> ReconcilePlan {
+        let old_rules = self.policy_rules();
+        let new_rules = new.policy_rules();
+
+        ReconcilePlan {
+            policy_changed: core::mem::discriminant(self) != core::mem::discriminant(new),
+            added: new_rules
+                .iter()
+                .filter(|rule| !old_rules.contains(rule))
+                .cloned()
+                .collect(),
+            removed: old_rules
+                .iter()
+                .filter(|rule| !new_rules.contains(rule))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    fn policy_rules(&self) -> Vec<PolicyRule> {
+        match self {
+            PacketFilter::Allow(filters) | PacketFilter::Deny(filters) => {
+                filters.0.iter().cloned().map(PolicyRule::Channel).collect()
+            }
+            PacketFilter::Rules(rules) => rules.iter().cloned().map(PolicyRule::Ordered).collect(),
+            PacketFilter::AllowAll => Vec::new(),
+        }
+    }
+
+    /// Returns a fingerprint of this filter policy, which is the same for
+    /// policies with the same rules, in the same order.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Checks that this filter policy is parsed back unchanged after being
+    /// serialized to TOML, by comparing the parsed filter with this one.
+    pub fn assert_round_trip(&self) -> Result<(), Error> {
+        let toml = toml::to_string(self).map_err(Error::encode)?;
+        let parsed = toml::from_str::<PacketFilter>(&toml).map_err(Error::decode)?;
+
+        if &parsed == self {
+            Ok(())
+        } else {
+            let parsed_toml = toml::to_string(&parsed).map_err(Error::encode)?;
+            Err(Error::filter_round_trip(toml, parsed_toml))
+        }
+    }
+
+    /// Parses a TOML document holding several named filter presets, each
+    /// specified in its own `[filters.<name>]` table, and returns them
+    /// keyed by name.
+    pub fn load_named(toml: &str) -> Result<HashMap<String, PacketFilter>, Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct NamedFilters {
+            #[serde(default)]
+            filters: HashMap<String, PacketFilter>,
+        }
+
+        let named = toml::from_str::<NamedFilters>(toml).map_err(Error::decode)?;
+
+        Ok(named.filters)
+    }
+
+    /// Fetches a packet filter policy, specified as a TOML document, from
+    /// the given HTTP(S) `url`.
+    ///
+    /// Fails if the server cannot be reached within [`REMOTE_FILTER_TIMEOUT`],
+    /// if it does not respond with `200 OK`, or if the body is not a valid policy.
+    #[cfg(feature = "remote-filter")]
+    pub fn from_url(url: &str) -> Result<PacketFilter, Error> {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(REMOTE_FILTER_TIMEOUT)
+            .build();
+
+        let response = agent.get(url).call().map_err(|e| match e {
+            ureq::Error::Status(status, _) => Error::fetch_filter_status(url.to_string(), status),
+            ureq::Error::Transport(e) => Error::fetch_filter(url.to_string(), e.to_string()),
+        })?;
+
+        if response.status() != 200 {
+            return Err(Error::fetch_filter_status(
+                url.to_string(),
+                response.status(),
+            ));
+        }
+
+        let body = response.into_string().map_err(Error::io)?;
+
+        toml::from_str(&body).map_err(Error::decode)
+    }
+}
+
+/// The internal representation of channel filter policies.
+///
+/// Filters are specified either as a list of [`ChannelFilterRule`]s, or as a
+/// [`ChannelFilterProduct`] table with separate lists of ports and channels.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChannelFilters(Vec<ChannelFilterRule>);
+
+impl ChannelFilters {
+    /// Create a new filter from the given list of port/channel filters.
+    pub fn new(filters: Vec<(PortFilterMatch, ChannelFilterMatch)>) -> Self {
+        Self(
+            filters
+                .into_iter()
+                .map(|(port, channel)| ChannelFilterRule::new(port, channel))
+                .collect(),
+        )
+    }
+
+    /// Create a filter without any rule, which denies all channels under an
+    /// `allow` policy and allows all channels under a `deny` policy.
+    pub fn empty() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Create a new filter from the given list of rules.
+    pub fn from_rules(rules: Vec<ChannelFilterRule>) -> Self {
+        Self(rules)
+    }
+
+    /// Create a new filter matching any port of the given product together
+    /// with any of its channels.
+    pub fn from_product(product: ChannelFilterProduct) -> Self {
+        Self(product.into_rules())
+    }
+
+    /// Returns the number of filters.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no filters, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Indicates whether a match for the given [`PortId`]-[`ChannelId`] pair
+    /// exists in the filter policy.
+    pub fn matches(&self, channel_port: (&PortId, &ChannelId)) -> bool {
+        let (port_id, channel_id) = channel_port;
+        self.0.iter().any(|rule| rule.matches(port_id, channel_id))
+    }
+
+    /// Returns the most specific rule matching the given [`PortId`]-[`ChannelId`]
+    /// pair, regardless of its position in the list. Rules with more exact
+    /// patterns are more specific, then rules with longer literal prefixes
+    /// before their wildcards. Ties are broken by list order.
+    pub fn best_match(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Option<&ChannelFilterRule> {
+        let mut best: Option<(&ChannelFilterRule, (usize, usize))> = None;
+
+        for rule in self
+            .0
+            .iter()
+            .filter(|rule| rule.matches(port_id, channel_id))
+        {
+            let specificity = rule.specificity();
+
+            if best.map_or(true, |(_, best_specificity)| specificity > best_specificity) {
+                best = Some((rule, specificity));
+            }
+        }
+
+        best.map(|(rule, _)| rule)
+    }
+
+    /// Returns the filters made of the rules of both `self` and `other`,
+    /// in order, without duplicate rules.
+    pub fn union(self, other: ChannelFilters) -> ChannelFilters {
+        let mut rules: Vec<ChannelFilterRule> = Vec::with_capacity(self.len() + other.len());
+
+        for rule in self.0.into_iter().chain(other.0) {
+            if !rules.contains(&rule) {
+                rules.push(rule);
+            }
+        }
+
+        Self(rules)
+    }
+
+    /// Returns the filters made of the rules of `self` layered with the rules
+    /// of `other`: a rule of `other` with the same port and channel patterns as
+    /// a rule of `self` is merged into it, each of its per-packet limits taking
+    /// precedence over the one of the rule of `self`, while the other rules of
+    /// `other` are appended.
+    pub fn layer(self, other: ChannelFilters) -> ChannelFilters {
+        let mut rules = self.0;
+
+        for rule in other.0 {
+            match rules
+                .iter_mut()
+                .find(|existing| existing.port == rule.port && existing.channel == rule.channel)
+            {
+                Some(existing) => {
+                    existing.max_data_bytes = rule.max_data_bytes.or(existing.max_data_bytes);
+                    existing.min_height = rule.min_height.or(existing.min_height);
+                    existing.expires_at = rule.expires_at.or(existing.expires_at);
+                }
+                None => rules.push(rule),
+            }
+        }
+
+        Self(rules)
+    }
+
+    /// Converts the rules of this filter into ordered [`FilterRule`]s with
+    /// the given action, keeping their port and channel patterns and their
+    /// per-packet limits.
+    fn into_ordered_rules(self, action: FilterAction) -> Vec<FilterRule> {
+        self.0
+            .into_iter()
+            .map(|rule| FilterRule {
+                max_data_bytes: rule.max_data_bytes,
+                min_height: rule.min_height,
+                expires_at: rule.expires_at,
+                ..FilterRule::new(action, rule.port, rule.channel)
+            })
+            .collect()
+    }
+
+    /// Removes the rules that have expired at the time `now`.
+    pub fn prune_expired(&mut self, now: SystemTime) {
+        self.0.retain(|rule| !rule.is_expired_at(now));
+    }
+
+    /// Returns the smallest filter matching exactly the given channels among
+    /// the `universe` of all known channels.
+    ///
+    /// The channels of a port are collapsed into a single `port/*` rule only
+    /// if they include every channel of that port in the `universe`, otherwise
+    /// they are kept as exact rules, so that the filter never matches a known
+    /// channel which was not given.
+    pub fn minimal_cover(
+        channels: &[(PortId, ChannelId)],
+        universe: &[(PortId, ChannelId)],
+    ) -> ChannelFilters {
+        let mut ports: Vec<(&PortId, Vec<&ChannelId>)> = Vec::new();
+
+        for (port_id, channel_id) in channels.iter().unique() {
+            match ports.iter_mut().find(|(port, _)| *port == port_id) {
+                Some((_, port_channels)) => port_channels.push(channel_id),
+                None => ports.push((port_id, vec![channel_id])),
+            }
+        }
+
+        let mut rules = Vec::new();
+
+        for (port_id, port_channels) in ports {
+            let is_full_port = universe
+                .iter()
+                .filter(|(universe_port, _)| universe_port == port_id)
+                .all(|(_, channel_id)| port_channels.contains(&channel_id));
+
+            if is_full_port {
+                rules.push(ChannelFilterRule::new(
+                    FilterPattern::Exact(port_id.clone()),
+                    FilterPattern::Wildcard(Wildcard::any()),
+                ));
+            } else {
+                rules.extend(port_channels.into_iter().map(|channel_id| {
+                    ChannelFilterRule::new(
+                        FilterPattern::Exact(port_id.clone()),
+                        FilterPattern::Exact(channel_id.clone()),
+                    )
+                }));
+            }
+        }
+
+        Self(rules)
+    }
+
+    /// Indicates whether a rule matching the given [`PortId`]-[`ChannelId`]
+    /// pair also satisfies the given predicate.
+    fn matches_with(
+        &self,
+        channel_port: (&PortId, &ChannelId),
+        predicate: impl Fn(&ChannelFilterRule) -> bool,
+    ) -> bool {
+        let (port_id, channel_id) = channel_port;
+        self.0
+            .iter()
+            .any(|rule| rule.matches(port_id, channel_id) && predicate(rule))
+    }
+
+    /// Returns the positions of the rules with a port or channel pattern that
+    /// is not a plain glob, i.e. that uses syntax such as character classes,
+    /// alternation or raw regex groups, which is harder to reason about.
+    pub fn advanced_rules(&self) -> Vec<usize> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.port.is_advanced() || rule.channel.is_advanced())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Indicates whether this filter policy contains only exact patterns.
+    #[inline]
+    pub fn is_exact(&self) -> bool {
+        self.0
+            .iter()
+            .all(|rule| rule.port.is_exact() && rule.channel.is_exact())
+    }
+
+    /// Returns the filter policy made of only the rules that don't contain wildcards.
+    pub fn exact_only(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter(|rule| rule.port.is_exact() && rule.channel.is_exact())
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// An iterator over the [`PortId`]-[`ChannelId`] pairs that don't contain wildcards.
+    pub fn iter_exact(&self) -> impl Iterator<Item = (&PortId, &ChannelId)> {
+        self.0.iter().filter_map(|rule| {
+            if let (FilterPattern::Exact(ref port_id), FilterPattern::Exact(ref chan_id)) =
+                (&rule.port, &rule.channel)
+            {
+                Some((port_id, chan_id))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Merges the rules with a wildcard port pattern and the same channel
+    /// pattern into a single rule, whose port pattern is the alternation of
+    /// their port patterns (see [`Wildcard::any_of`]), eg. `ica*` and `transfer*`
+    /// into `ica*|transfer*`, so that fewer patterns are evaluated. The merged
+    /// rule takes the place of the first of the rules it replaces.
+    ///
+    /// Rules with a data size limit, a minimum height or an expiry are left
+    /// as they are, so the channels allowed or denied by the filters do not change.
+    /// The merged rules are serialized back as one rule per alternative.
+    pub fn coalesce_wildcards(self) -> Self {
+        fn is_coalescable(rule: &ChannelFilterRule) -> bool {
+            rule.port.is_wildcard()
+                && rule.max_data_bytes.is_none()
+                && rule.min_height.is_none()
+                && rule.expires_at.is_none()
+        }
+
+        fn case_insensitive(rule: &ChannelFilterRule) -> bool {
+            matches!(&rule.port, FilterPattern::Wildcard(w) if w.is_case_insensitive())
+        }
+
+        let mut rules: Vec<ChannelFilterRule> = Vec::with_capacity(self.len());
+        let mut alternatives: Vec<Vec<Wildcard>> = Vec::with_capacity(self.len());
+
+        for rule in self.0 {
+            let group = if is_coalescable(&rule) {
+                rules.iter().position(|other| {
+                    is_coalescable(other)
+                        && other.channel == rule.channel
+                        && case_insensitive(other) == case_insensitive(&rule)
+                })
+            } else {
+                None
+            };
+
+            let port = match &rule.port {
+                FilterPattern::Wildcard(wildcard) => Some(wildcard.clone()),
+                FilterPattern::Exact(_) => None,
+            };
+
+            match group {
+                Some(i) => alternatives[i].extend(port),
+                None => {
+                    alternatives.push(port.into_iter().collect());
+                    rules.push(rule);
+                }
+            }
+        }
+
+        for (rule, alternatives) in rules.iter_mut().zip(alternatives) {
+            if alternatives.len() > 1 {
+                rule.port = FilterPattern::Wildcard(Wildcard::any_of(alternatives));
+            }
+        }
+
+        Self(rules)
+    }
+
+    /// Checks the exact port and channel identifiers of the rules against the
+    /// ICS 24 specification, eg. its length bounds, which identifiers built
+    /// programmatically or deserialized directly may not satisfy.
+    ///
+    /// Returns a description of every violation found.
+    pub fn validate_identifiers(&self) -> Result<(), Vec<String>> {
+        let mut violations = Vec::new();
+
+        for rule in &self.0 {
+            if let FilterPattern::Exact(port_id) = &rule.port {
+                if let Err(e) = validate_port_identifier(port_id.as_str()) {
+                    violations.push(format!("invalid port `{}`: {}", port_id, e));
+                }
+            }
+
+            if let FilterPattern::Exact(channel_id) = &rule.channel {
+                if let Err(e) = validate_channel_identifier(channel_id.as_str()) {
+                    violations.push(format!("invalid channel `{}`: {}", channel_id, e));
+                }
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Returns the number of rules that don't contain wildcards.
+    pub fn exact_count(&self) -> usize {
+        self.iter_exact().count()
+    }
+
+    /// Returns the number of rules in which at least one of the patterns is a wildcard.
+    pub fn wildcard_count(&self) -> usize {
+        self.iter_wildcards().count()
+    }
+
+    /// An iterator over the port and channel patterns of the rules in which
+    /// at least one of the patterns is a wildcard.
+    pub fn iter_wildcards(
+        &self,
+    ) -> impl Iterator<Item = (&FilterPattern<PortId>, &FilterPattern<ChannelId>)> {
+        self.0
+            .iter()
+            .filter(|rule| rule.port.is_wildcard() || rule.channel.is_wildcard())
+            .map(|rule| (&rule.port, &rule.channel))
+    }
+
+    /// Returns the key by which rules are ordered in [`ChannelFilters::sorted`],
+    /// made of the displayed port and channel patterns of the rule.
+    pub fn entry_sort_key(entry: &ChannelFilterRule) -> String {
+        format!("{}/{}", entry.port, entry.channel)
+    }
+
+    /// Sorts the rules of this filter by their [`ChannelFilters::entry_sort_key`],
+    /// keeping the relative order of the rules with the same key.
+    pub fn sort(&mut self) {
+        self.0.sort_by_cached_key(Self::entry_sort_key);
+    }
+
+    /// Returns the rules of this filter sorted as with [`ChannelFilters::sort`],
+    /// for deterministic serialization and diffing.
+    pub fn sorted(&self) -> Self {
+        let mut sorted = self.clone();
+        sorted.sort();
+        sorted
+    }
+}
+
+impl fmt::Display for ChannelFilters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .map(|rule| format!("{}/{}", rule.port, rule.channel))
+                .join(", ")
+        )
+    }
+}
+
+impl Serialize for ChannelFilters {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeSeq;
+
+        // A rule whose port is an alternation is written as one rule per alternative,
+        // as `|` is matched literally by the patterns read back from the config
+        let alternatives = |rule: &ChannelFilterRule| match &rule.port {
+            FilterPattern::Wildcard(wildcard) => wildcard.alternatives.clone(),
+            FilterPattern::Exact(_) => Vec::new(),
+        };
+
+        let len = self
+            .0
+            .iter()
+            .map(|rule| alternatives(rule).len().max(1))
+            .sum();
+
+        let mut outer_seq = serializer.serialize_seq(Some(len))?;
+
+        for rule in &self.0 {
+            let alternatives = alternatives(rule);
+
+            if alternatives.is_empty() {
+                outer_seq.serialize_element(rule)?;
+            }
+
+            for alternative in alternatives {
+                outer_seq.serialize_element(&ChannelFilterRule {
+                    port: FilterPattern::Wildcard(alternative),
+                    ..rule.clone()
+                })?;
+            }
+        }
+
+        outer_seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ChannelFilters {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ChannelFilters, D::Error> {
+        deserializer.deserialize_any(rule::ChannelFiltersVisitor)
+    }
+}
+
+/// The patterns of the clients that the relayer services, specified as a
+/// `clients = [...]` list of exact [`ClientId`]s or wildcards, eg. `07-tendermint-*`.
+///
+/// An empty list does not filter clients, allowing any and all clients.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ClientFilters(Vec<ClientFilterMatch>);
+
+impl ClientFilters {
+    /// Create a new filter from the given list of client patterns.
+    pub fn new(filters: Vec<ClientFilterMatch>) -> Self {
+        Self(filters)
+    }
+
+    /// The number of client patterns in this filter.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if this filter has no client patterns, and thus allows every client.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns true if the client with the given [`ClientId`] may be serviced,
+    /// ie. if no client patterns are specified or if any of them matches.
+    pub fn is_client_allowed(&self, client_id: &ClientId) -> bool {
+        self.is_empty() || self.0.iter().any(|pattern| pattern.matches(client_id))
+    }
+}
+
+impl fmt::Display for ClientFilters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.iter().join(", "))
+    }
+}
+
+/// Represents the ways in which ICS20 transfers can be filtered by the
+/// denomination of their tokens, specified as a `denoms = [...]` list of
+/// exact denoms or wildcards, eg. `ibc/*` for all IBC vouchers.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(
+    rename_all = "lowercase",
+    tag = "policy",
+    content = "denoms",
+    deny_unknown_fields
+)]
+pub enum DenomFilter {
+    /// Allow transfers of the specified denoms.
+    Allow(Vec<DenomFilterMatch>),
+    /// Deny transfers of the specified denoms.
+    Deny(Vec<DenomFilterMatch>),
+    /// Allow transfers of any & all denoms.
+    AllowAll,
+}
+
+impl Default for DenomFilter {
+    /// By default, allows all denoms.
+    fn default() -> Self {
+        Self::AllowAll
+    }
+}
+
+impl DenomFilter {
+    /// Returns true if the tokens with the given denom, as found in the
+    /// data of an ICS20 packet, eg. `uatom` or `transfer/channel-0/uatom`,
+    /// may be relayed. Returns false otherwise.
+    pub fn is_denom_allowed(&self, denom: &str) -> bool {
+        let denom = denom.to_string();
+
+        match self {
+            DenomFilter::Allow(patterns) => patterns.iter().any(|p| p.matches(&denom)),
+            DenomFilter::Deny(patterns) => !patterns.iter().any(|p| p.matches(&denom)),
+            DenomFilter::AllowAll => true,
+        }
+    }
+
+    /// Returns true if this filter allows transfers of all denoms, as is the default.
+    pub fn is_allow_all(&self) -> bool {
+        matches!(self, DenomFilter::AllowAll)
+    }
+}
+
+/// An alternate form of [`ChannelFilters`] with separate lists of port and
+/// channel patterns, matching a [`PortId`]-[`ChannelId`] pair if any of the
+/// ports matches and any of the channels matches.
+///
+/// Specified as a table, eg. `{ ports = ['transfer', 'ica*'], channels = ['channel-0'] }`.
+/// The `channels` list may be omitted to match any channel on the listed ports.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelFilterProduct {
+    pub ports: Vec<PortFilterMatch>,
+    #[serde(default = "any_channel")]
+    pub channels: Vec<ChannelFilterMatch>,
+}
+
+fn any_channel() -> Vec<ChannelFilterMatch> {
+    vec![FilterPattern::Wildcard(Wildcard::any())]
+}
+
+impl ChannelFilterProduct {
+    pub fn new(ports: Vec<PortFilterMatch>, channels: Vec<ChannelFilterMatch>) -> Self {
+        Self { ports, channels }
+    }
+
+    /// Indicates whether any port and any channel pattern matches the given
+    /// [`PortId`]-[`ChannelId`] pair.
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.ports.iter().any(|port| port.matches(port_id))
+            && self
+                .channels
+                .iter()
+                .any(|channel| channel.matches(channel_id))
+    }
+
+    /// Expands the product into one rule per port and channel pattern pair.
+    pub fn into_rules(self) -> Vec<ChannelFilterRule> {
+        self.ports
+            .iter()
+            .cartesian_product(self.channels.iter())
+            .map(|(port, channel)| ChannelFilterRule::new(port.clone(), channel.clone()))
+            .collect()
+    }
+}
+
+/// A single rule of a [`ChannelFilters`] list, matching a port and a channel,
+/// optionally bounding the size of the packet data it applies to, the
+/// height from which it is active and the time at which it expires.
+///
+/// Rules are specified as `[port, channel]` or `[port, channel, max_data_bytes]`,
+/// or as a table with the `port`, `channel` and optional `max_data_bytes`,
+/// `min_height` and `expires_at` keys.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ChannelFilterRule {
+    pub port: PortFilterMatch,
+    pub channel: ChannelFilterMatch,
+    /// In an allow list, only packets with at most this many bytes of data are
+    /// allowed. In a deny list, only packets with more bytes of data are denied.
+    /// Rules without a limit ignore the packet data size.
+    pub max_data_bytes: Option<usize>,
+    /// The revision height of the source chain from which this rule is active.
+    /// Rules without it are always active.
+    pub min_height: Option<u64>,
+    /// The time, in seconds since the Unix epoch, at which this rule expires.
+    /// Rules without it never expire.
+    pub expires_at: Option<u64>,
+}
+
+impl ChannelFilterRule {
+    pub fn new(port: PortFilterMatch, channel: ChannelFilterMatch) -> Self {
+        Self {
+            port,
+            channel,
+            max_data_bytes: None,
+            min_height: None,
+            expires_at: None,
+        }
+    }
+
+    pub fn with_max_data_bytes(self, max_data_bytes: usize) -> Self {
+        Self {
+            max_data_bytes: Some(max_data_bytes),
+            ..self
+        }
+    }
+
+    pub fn with_min_height(self, min_height: u64) -> Self {
+        Self {
+            min_height: Some(min_height),
+            ..self
+        }
+    }
+
+    pub fn with_expires_at(self, expires_at: u64) -> Self {
+        Self {
+            expires_at: Some(expires_at),
+            ..self
+        }
+    }
+
+    /// Indicates whether this rule has expired at the time `now`.
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        is_expired_at(self.expires_at, now)
+    }
+
+    /// Indicates whether this rule matches the given [`PortId`]-[`ChannelId`] pair.
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.port.matches(port_id) && self.channel.matches(channel_id)
+    }
+
+    /// The number of exact patterns in this rule, followed by the total
+    /// length of the literal prefixes of its patterns.
+    fn specificity(&self) -> (usize, usize) {
+        let exact = usize::from(self.port.is_exact()) + usize::from(self.channel.is_exact());
+        let literal = self.port.literal_prefix_len() + self.channel.literal_prefix_len();
+
+        (exact, literal)
+    }
+
+    fn is_within_limit(&self, data_len: usize) -> bool {
+        self.max_data_bytes.map_or(true, |max| data_len <= max)
+    }
+
+    /// Indicates whether a packet with `data_len` bytes of data is denied by
+    /// this rule in a deny list, which is the case of any packet without a limit.
+    fn is_beyond_limit(&self, data_len: usize) -> bool {
+        self.max_data_bytes.map_or(true, |max| data_len > max)
+    }
+
+    fn is_active_at(&self, height: Height) -> bool {
+        self.min_height
+            .map_or(true, |min_height| height.revision_height() >= min_height)
+    }
+}
+
+/// Indicates whether a rule expiring at `expires_at`, in seconds since the
+/// Unix epoch, has expired at the time `now`.
+fn is_expired_at(expires_at: Option<u64>, now: SystemTime) -> bool {
+    expires_at.map_or(false, |expires_at| {
+        now >= UNIX_EPOCH + Duration::from_secs(expires_at)
+    })
+}
+
+impl Serialize for ChannelFilterRule {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::{SerializeMap, SerializeSeq};
+
+        if self.min_height.is_some() || self.expires_at.is_some() {
+            let len = 2
+                + usize::from(self.max_data_bytes.is_some())
+                + usize::from(self.min_height.is_some())
+                + usize::from(self.expires_at.is_some());
+
+            let mut map = serializer.serialize_map(Some(len))?;
+            map.serialize_entry("port", &self.port)?;
+            map.serialize_entry("channel", &self.channel)?;
+            if let Some(max_data_bytes) = self.max_data_bytes {
+                map.serialize_entry("max_data_bytes", &max_data_bytes)?;
+            }
+            if let Some(min_height) = self.min_height {
+                map.serialize_entry("min_height", &min_height)?;
+            }
+            if let Some(expires_at) = self.expires_at {
+                map.serialize_entry("expires_at", &expires_at)?;
+            }
+            return map.end();
+        }
+
+        let len = if self.max_data_bytes.is_some() { 3 } else { 2 };
+
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        seq.serialize_element(&self.port)?;
+        seq.serialize_element(&self.channel)?;
+        if let Some(max_data_bytes) = self.max_data_bytes {
+            seq.serialize_element(&max_data_bytes)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ChannelFilterRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ChannelFilterRule, D::Error> {
+        deserializer.deserialize_any(rule::ChannelFilterRuleVisitor)
+    }
+}
+
+/// Newtype wrapper for expressing wildcard patterns compiled to a [`regex::Regex`].
+#[derive(Clone, Debug)]
+pub struct Wildcard {
+    pattern: String,
+    regex: regex::Regex,
+    case_insensitive: bool,
+    /// A regex-free representation of the pattern, when it is a simple glob.
+    simple: Option<SimpleGlob>,
+    /// The wildcards this wildcard is the alternation of, if built with [`Wildcard::any_of`].
+    alternatives: Vec<Wildcard>,
+}
+
+impl Wildcard {
+    pub fn new(pattern: String) -> Result<Self, regex::Error> {
+        let regex = glob_to_regex(&pattern).parse()?;
+        let simple = SimpleGlob::parse(&pattern);
+        Ok(Self {
+            pattern,
+            regex,
+            case_insensitive: false,
+            simple,
+            alternatives: Vec::new(),
+        })
+    }
+
+    /// Creates a wildcard matching identifiers regardless of their case,
+    /// using Unicode simple case folding, so that eg. `ΣΟΦΙΑ*` matches `σοφια-0`.
+    pub fn new_case_insensitive(pattern: String) -> Result<Self, regex::Error> {
+        let regex = regex::RegexBuilder::new(&glob_to_regex(&pattern))
+            .case_insensitive(true)
+            .unicode(true)
+            .build()?;
+        Ok(Self {
+            pattern,
+            regex,
+            case_insensitive: true,
+            simple: None,
+            alternatives: Vec::new(),
+        })
+    }
+
+    /// Creates a wildcard matching any identifier matched by one of the given
+    /// wildcards, displayed as their patterns separated by `|`.
+    ///
+    /// There is no pattern syntax for an alternation: the `|` of a pattern is
+    /// matched literally, so that eg. parsing `ica*|transfer*` does not give
+    /// back this wildcard.
+    pub fn any_of(alternatives: Vec<Wildcard>) -> Self {
+        let alternatives: Vec<Wildcard> = alternatives
+            .into_iter()
+            .flat_map(|wildcard| {
+                if wildcard.alternatives.is_empty() {
+                    vec![wildcard]
+                } else {
+                    wildcard.alternatives
+                }
+            })
+            .collect();
+
+        let pattern = alternatives.iter().map(|w| w.pattern.as_str()).join("|");
+        let source = alternatives
+            .iter()
+            .map(Wildcard::unanchored_regex_source)
+            .join("|");
+
+        let regex = format!("^(?:{source})$")
+            .parse()
+            .expect("an alternation of valid regexes is a valid regex");
+
+        Self {
+            pattern,
+            regex,
+            case_insensitive: alternatives.iter().all(Wildcard::is_case_insensitive),
+            simple: None,
+            alternatives,
+        }
+    }
+
+    /// Creates the `*` wildcard, matching any identifier.
+    pub fn any() -> Self {
+        Self {
+            pattern: "*".to_string(),
+            regex: regex::Regex::new("^(?:.*)$").expect("a constant valid regex"),
+            case_insensitive: false,
+            simple: Some(SimpleGlob::PrefixSuffix {
+                prefix: String::new(),
+                suffix: String::new(),
+            }),
+            alternatives: Vec::new(),
+        }
+    }
+
+    /// Parses a wildcard matching identifiers regardless of their case, as
+    /// with [`Wildcard::new_case_insensitive`].
+    ///
+    /// Identifiers are case-sensitive as per ICS 24, eg. `Transfer` and `transfer`
+    /// are distinct ports, so case-insensitive matching must be opted into explicitly.
+    pub fn from_str_ci(pattern: &str) -> Result<Self, regex::Error> {
+        Self::new_case_insensitive(pattern.to_string())
+    }
+
+    /// Returns the source of the regex of this wildcard, without its anchors.
+    fn unanchored_regex_source(&self) -> String {
+        let source = self.regex.as_str();
+        let source = source.strip_prefix('^').unwrap_or(source);
+        let source = source.strip_suffix('$').unwrap_or(source);
+
+        // The alternatives of an alternation carry their own case sensitivity
+        if self.case_insensitive && self.alternatives.is_empty() {
+            format!("(?i:{})", source)
+        } else {
+            source.to_string()
+        }
+    }
+
+    /// Indicates whether this wildcard matches regardless of case.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    #[inline]
+    pub fn is_match(&self, text: &str) -> bool {
+        match &self.simple {
+            Some(simple) => simple.is_match(text),
+            None => self.regex.is_match(text),
+        }
+    }
+
+    /// Indicates whether this wildcard matches any text, eg. `*`.
+    fn matches_everything(&self) -> bool {
+        if self.alternatives.is_empty() {
+            !self.pattern.is_empty() && self.pattern.chars().all(|c| c == '*')
+        } else {
+            self.alternatives.iter().any(Wildcard::matches_everything)
+        }
+    }
+
+    /// Matches the given text as in [`Wildcard::is_match`], but regardless of case.
+    fn is_match_ci(&self, text: &str) -> bool {
+        if self.case_insensitive {
+            self.is_match(text)
+        } else if !self.alternatives.is_empty() {
+            self.alternatives.iter().any(|w| w.is_match_ci(text))
+        } else {
+            Wildcard::from_str_ci(&self.pattern).map_or(false, |w| w.is_match(text))
+        }
+    }
+
+    /// Returns the pattern in a normalized glob form, where any regex-style
+    /// `(?:.*)` group is rewritten to `*` and runs of consecutive `*` are
+    /// collapsed into one, so that eg. `ica*` and `ica(?:.*)` compare equal.
+    ///
+    /// This form is only used to compare and hash wildcards: the matcher is
+    /// built from the pattern as given, where `(?:.*)` is matched literally.
+    pub fn canonical_source(&self) -> String {
+        if self.alternatives.is_empty() {
+            canonicalize_glob(&self.pattern)
+        } else {
+            self.alternatives
+                .iter()
+                .map(Wildcard::canonical_source)
+                .join("|")
+        }
+    }
+
+    /// Indicates whether the pattern is a plain glob, where `*` is the only
+    /// special character. A pattern with any other regex metacharacter, eg. the
+    /// `[` of a character class or the `(?:` of a group, is not, even though
+    /// the matcher takes it literally, nor is an alternation of wildcards.
+    ///
+    /// The `.`, `-` and `#` metacharacters are not flagged, as they are part
+    /// of many identifiers, eg. `wasm.abc` or `channel-0`.
+    pub fn is_plain_glob(&self) -> bool {
+        const METACHARACTERS: [char; 12] =
+            ['[', ']', '{', '}', '(', ')', '|', '?', '+', '\\', '^', '$'];
+
+        self.alternatives.is_empty() && !self.pattern.contains(METACHARACTERS)
+    }
+
+    /// Returns the literal part of the pattern preceding the first `*`, or
+    /// for an alternation, the literal part common to all its alternatives.
+    pub fn literal_prefix(&self) -> String {
+        if self.alternatives.is_empty() {
+            return match self.pattern.split_once('*') {
+                Some((prefix, _)) => prefix.to_string(),
+                None => self.pattern.clone(),
+            };
+        }
+
+        let prefixes: Vec<String> = self
+            .alternatives
+            .iter()
+            .map(Wildcard::literal_prefix)
+            .collect();
+
+        let first = prefixes[0].as_str();
+        let len = prefixes[1..].iter().fold(first.len(), |len, prefix| {
+            first
+                .char_indices()
+                .zip(prefix.chars())
+                .take_while(|((i, a), b)| *i < len && a == b)
+                .map(|((i, a), _)| i + a.len_utf8())
+                .last()
+                .unwrap_or(0)
+        });
+
+        first[..len].to_string()
+    }
+}
+
+/// A case-sensitive glob with at most one `*`, matched by comparing bytes
+/// rather than with a regex, as most channel and port patterns are.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SimpleGlob {
+    /// A glob without any `*`, matching only the given text.
+    Exact(String),
+    /// A glob with a single `*`, matching any text with the given prefix and suffix.
+    PrefixSuffix { prefix: String, suffix: String },
+}
+
+impl SimpleGlob {
+    /// Parses the given glob, returning `None` if it has more than one `*`.
+    ///
+    /// As every character other than `*` is matched literally by the regex
+    /// of a [`Wildcard`], any such glob is matched the same way by both.
+    fn parse(pattern: &str) -> Option<Self> {
+        match pattern.split_once('*') {
+            None => Some(SimpleGlob::Exact(pattern.to_string())),
+            Some((_, suffix)) if suffix.contains('*') => None,
+            Some((prefix, suffix)) => Some(SimpleGlob::PrefixSuffix {
+                prefix: prefix.to_string(),
+                suffix: suffix.to_string(),
+            }),
+        }
+    }
+
+    #[inline]
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            SimpleGlob::Exact(exact) => text == exact,
+            SimpleGlob::PrefixSuffix { prefix, suffix } => {
+                text.len() >= prefix.len() + suffix.len()
+                    && text.starts_with(prefix.as_str())
+                    && text.ends_with(suffix.as_str())
+            }
+        }
+    }
+}
+
+fn glob_to_regex(pattern: &str) -> String {
+    let escaped = regex::escape(pattern).replace("\\*", "(?:.*)");
+    format!("^{escaped}$")
+}
+
+fn canonicalize_glob(pattern: &str) -> String {
+    let glob = pattern.replace("(?:.*)", "*");
+
+    let mut canonical = String::with_capacity(glob.len());
+    for c in glob.chars() {
+        if c == '*' && canonical.ends_with('*') {
+            continue;
+        }
+        canonical.push(c);
+    }
+
+    canonical
+}
+
+impl FromStr for Wildcard {
+    type Err = regex::Error;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Self::new(pattern.to_string())
+    }
+}
+
+impl fmt::Display for Wildcard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+impl Serialize for Wildcard {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.pattern)
+    }
+}
+
+impl PartialEq for Wildcard {
+    fn eq(&self, other: &Self) -> bool {
+        self.case_insensitive == other.case_insensitive
+            && self.alternatives == other.alternatives
+            && self.canonical_source() == other.canonical_source()
+    }
+}
+
+impl Eq for Wildcard {}
+
+impl Hash for Wildcard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.case_insensitive.hash(state);
+        self.alternatives.hash(state);
+        self.canonical_source().hash(state);
+    }
+}
+
+/// Represents a single channel to be filtered in a [`ChannelFilters`] list.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FilterPattern<T> {
+    /// A channel specified exactly with its [`PortId`] & [`ChannelId`].
+    Exact(T),
+    /// A glob of channel(s) specified with a wildcard in either or both [`PortId`] & [`ChannelId`].
+    Wildcard(Wildcard),
+}
+
+impl<T> FilterPattern<T> {
+    /// Indicates whether this filter is specified in part with a wildcard.
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self, Self::Wildcard(_))
+    }
+
+    /// Indicates whether this filter is specified as an exact match.
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Exact(_))
+    }
+
+    /// Indicates whether this filter is a wildcard that is not a plain glob.
+    pub fn is_advanced(&self) -> bool {
+        match self {
+            Self::Exact(_) => false,
+            Self::Wildcard(wildcard) => !wildcard.is_plain_glob(),
+        }
+    }
+
+    /// Matches the given value via strict equality if the filter is an `Exact`, or via
+    /// wildcard matching if the filter is a `Pattern`.
+    pub fn matches(&self, value: &T) -> bool
+    where
+        T: PartialEq + ToString,
+    {
+        match self {
+            FilterPattern::Exact(v) => value == v,
+            FilterPattern::Wildcard(regex) => regex.is_match(&value.to_string()),
+        }
+    }
+
+    /// Matches the given value as in [`FilterPattern::matches`], but regardless
+    /// of case. As identifiers are case-sensitive, this is meant for tools which
+    /// explicitly opt into it, eg. to treat `Transfer` and `transfer` the same.
+    ///
+    /// A case-sensitive wildcard is recompiled on each call, so filters meant to
+    /// be matched often regardless of case should rather be created with
+    /// [`FilterPattern::wildcard_ci`].
+    pub fn matches_ci(&self, value: &T) -> bool
+    where
+        T: ToString,
+    {
+        let value = value.to_string();
+
+        match self {
+            FilterPattern::Exact(v) => v.to_string().to_lowercase() == value.to_lowercase(),
+            FilterPattern::Wildcard(wildcard) => wildcard.is_match_ci(&value),
+        }
+    }
+
+    /// Creates a wildcard pattern matching identifiers regardless of their
+    /// case, see [`Wildcard::from_str_ci`].
+    pub fn wildcard_ci(pattern: &str) -> Result<Self, regex::Error> {
+        Wildcard::from_str_ci(pattern).map(Self::Wildcard)
+    }
+
+    /// Indicates whether this pattern is a wildcard matching every value.
+    fn matches_everything(&self) -> bool {
+        match self {
+            FilterPattern::Exact(_) => false,
+            FilterPattern::Wildcard(wildcard) => wildcard.matches_everything(),
+        }
+    }
+
+    /// Returns an unanchored regex matching the same values as this pattern.
+    fn regex_source(&self) -> String
+    where
+        T: ToString,
+    {
+        match self {
+            FilterPattern::Exact(value) => regex::escape(&value.to_string()),
+            FilterPattern::Wildcard(wildcard) => wildcard.unanchored_regex_source(),
+        }
+    }
+
+    /// Returns the length of the literal part of the pattern preceding any
+    /// wildcard, i.e. the whole value for an `Exact` pattern.
+    pub fn literal_prefix_len(&self) -> usize
+    where
+        T: ToString,
+    {
+        match self {
+            FilterPattern::Exact(value) => value.to_string().len(),
+            FilterPattern::Wildcard(wildcard) => wildcard.literal_prefix().len(),
+        }
+    }
+
+    /// Returns the contained value if this filter contains an `Exact` variant, or
+    /// `None` if it contains a `Pattern`.
+    pub fn exact_value(&self) -> Option<&T> {
+        match self {
+            FilterPattern::Exact(value) => Some(value),
+            FilterPattern::Wildcard(_) => None,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for FilterPattern<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterPattern::Exact(value) => write!(f, "{}", value),
+            FilterPattern::Wildcard(regex) => write!(f, "{}", regex),
+        }
+    }
+}
+
+impl<T> Serialize for FilterPattern<T>
+where
+    T: ToString,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            FilterPattern::Exact(e) => serializer.serialize_str(&e.to_string()),
+            FilterPattern::Wildcard(t) => serializer.serialize_str(&t.to_string()),
+        }
+    }
+}
+
+/// Filters restricting relaying to the channels whose connection hops all
+/// match one of the given connection patterns, specified as a list of
+/// exact connection identifiers or wildcards, eg.
+/// `connections = ['connection-0', 'connection-1*']`.
+///
+/// Without any pattern, channels on all connections are allowed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionFilters {
+    #[serde(default)]
+    connections: Vec<ConnectionFilterMatch>,
+}
+
+impl ConnectionFilters {
+    /// Create a new filter from the given list of connection patterns.
+    pub fn new(connections: Vec<ConnectionFilterMatch>) -> Self {
+        Self { connections }
+    }
+
+    /// Returns the number of connection patterns.
+    pub fn len(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Returns true if there are no connection patterns, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.connections.is_empty()
+    }
+
+    /// Returns true if there are no connection patterns or if one of them
+    /// matches the given [`ConnectionId`], false otherwise.
+    pub fn is_connection_allowed(&self, connection_id: &ConnectionId) -> bool {
+        self.is_empty()
+            || self
+                .connections
+                .iter()
+                .any(|pattern| pattern.matches(connection_id))
+    }
+
+    /// Returns true if every connection of the given connection hops of a
+    /// channel is allowed, false otherwise.
+    pub fn are_connection_hops_allowed(&self, connection_hops: &[ConnectionId]) -> bool {
+        connection_hops
+            .iter()
+            .all(|connection_id| self.is_connection_allowed(connection_id))
+    }
+}
+
+/// Type alias for a [`FilterPattern`] containing a [`PortId`].
+pub type PortFilterMatch = FilterPattern<PortId>;
+/// Type alias for a [`FilterPattern`] containing a [`ChannelId`].
+pub type ChannelFilterMatch = FilterPattern<ChannelId>;
+/// Type alias for a [`FilterPattern`] containing a [`ClientId`].
+pub type ClientFilterMatch = FilterPattern<ClientId>;
+/// Type alias for a [`FilterPattern`] containing a token denom.
+pub type DenomFilterMatch = FilterPattern<String>;
+/// Type alias for a [`FilterPattern`] containing a [`ConnectionId`].
+pub type ConnectionFilterMatch = FilterPattern<ConnectionId>;
+
+impl<'de> Deserialize<'de> for PortFilterMatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<PortFilterMatch, D::Error> {
+        deserializer.deserialize_string(port::PortFilterMatchVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChannelFilterMatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ChannelFilterMatch, D::Error> {
+        deserializer.deserialize_string(channel::ChannelFilterMatchVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for ClientFilterMatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ClientFilterMatch, D::Error> {
+        deserializer.deserialize_string(client::ClientFilterMatchVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for DenomFilterMatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DenomFilterMatch, D::Error> {
+        deserializer.deserialize_string(denom::DenomFilterMatchVisitor)
+    }
+}
+
+/// The characters besides alphanumeric ones that ICS 24 identifiers may contain.
+const IDENTIFIER_SPECIAL_CHARS: &str = "._+-#[]<>";
+
+/// Parses a wildcard matching identifiers, such as port or channel identifiers,
+/// failing if the pattern contains characters which are neither valid in an
+/// identifier nor wildcard tokens, eg. for a typo such as `transfer!`,
+/// as such a pattern never matches any identifier.
+fn parse_identifier_wildcard<E: de::Error>(pattern: &str) -> Result<Wildcard, E> {
+    let invalid = pattern.chars().find(|c| {
+        !(c.is_ascii_alphanumeric() || *c == '*' || IDENTIFIER_SPECIAL_CHARS.contains(*c))
+    });
+
+    if let Some(c) = invalid {
+        return Err(E::custom(format!(
+            "invalid character `{c}` in wildcard `{pattern}`, \
+             expected only `*` and characters valid in an identifier"
+        )));
+    }
+
+    pattern.parse().map_err(E::custom)
+}
+
+impl<'de> Deserialize<'de> for ConnectionFilterMatch {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ConnectionFilterMatch, D::Error> {
+        deserializer.deserialize_string(connection::ConnectionFilterMatchVisitor)
+    }
+}
+
+pub(crate) mod port {
+    use super::*;
+    use ibc_relayer_types::core::ics24_host::identifier::PortId;
+
+    pub struct PortFilterMatchVisitor;
+
+    impl<'de> de::Visitor<'de> for PortFilterMatchVisitor {
+        type Value = PortFilterMatch;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("valid PortId or wildcard")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if let Ok(port_id) = PortId::from_str(v) {
+                Ok(PortFilterMatch::Exact(port_id))
+            } else {
+                let wildcard = parse_identifier_wildcard(v)?;
+                Ok(PortFilterMatch::Wildcard(wildcard))
+            }
+        }
+
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+            self.visit_str(&v)
+        }
+    }
+}
+
+pub(crate) mod rule {
+    use super::*;
+
+    pub struct ChannelFiltersVisitor;
+
+    impl<'de> de::Visitor<'de> for ChannelFiltersVisitor {
+        type Value = ChannelFilters;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a list of filter rules or a table with ports and channels")
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut rules = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+
+            while let Some(rule) = seq.next_element()? {
+                rules.push(rule);
+            }
+
+            Ok(ChannelFilters::from_rules(rules))
+        }
+
+        fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+            let product =
+                ChannelFilterProduct::deserialize(de::value::MapAccessDeserializer::new(map))?;
+
+            Ok(ChannelFilters::from_product(product))
+        }
+    }
+
+    pub struct ChannelFilterRuleVisitor;
+
+    impl<'de> de::Visitor<'de> for ChannelFilterRuleVisitor {
+        type Value = ChannelFilterRule;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str(
+                "[port, channel], [port, channel, max_data_bytes] or a table with port and channel",
+            )
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let port = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let channel = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            let max_data_bytes = seq.next_element()?;
+
+            if seq.next_element::<de::IgnoredAny>()?.is_some() {
+                return Err(de::Error::invalid_length(4, &self));
+            }
+
+            Ok(ChannelFilterRule {
+                port,
+                channel,
+                max_data_bytes,
+                min_height: None,
+                expires_at: None,
+            })
+        }
+
+        fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            #[derive(Deserialize)]
+            #[serde(field_identifier, rename_all = "snake_case")]
+            enum Field {
+                Port,
+                Channel,
+                MaxDataBytes,
+                MinHeight,
+                ExpiresAt,
+            }
+
+            let mut port = None;
+            let mut channel = None;
+            let mut max_data_bytes = None;
+            let mut min_height = None;
+            let mut expires_at = None;
+
+            while let Some(key) = map.next_key()? {
+                match key {
+                    Field::Port if port.is_none() => port = Some(map.next_value()?),
+                    Field::Channel if channel.is_none() => channel = Some(map.next_value()?),
+                    Field::MaxDataBytes if max_data_bytes.is_none() => {
+                        max_data_bytes = Some(map.next_value()?)
+                    }
+                    Field::MinHeight if min_height.is_none() => {
+                        min_height = Some(map.next_value()?)
+                    }
+                    Field::ExpiresAt if expires_at.is_none() => {
+                        expires_at = Some(map.next_value()?)
+                    }
+                    _ => return Err(de::Error::custom("duplicate field in filter rule")),
+                }
+            }
+
+            Ok(ChannelFilterRule {
+                port: port.ok_or_else(|| de::Error::missing_field("port"))?,
+                channel: channel.ok_or_else(|| de::Error::missing_field("channel"))?,
+                max_data_bytes,
+                min_height,
+                expires_at,
+            })
+        }
+    }
+}
+
+pub(crate) mod channel {
+    use super::*;
+    use ibc_relayer_types::core::ics24_host::identifier::ChannelId;
+
+    pub struct ChannelFilterMatchVisitor;
+
+    impl<'de> de::Visitor<'de> for ChannelFilterMatchVisitor {
+        type Value = ChannelFilterMatch;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("valid ChannelId or wildcard")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if let Ok(channel_id) = ChannelId::from_str(v) {
+                Ok(ChannelFilterMatch::Exact(channel_id))
+            } else {
+                let wildcard = parse_identifier_wildcard(v)?;
+                Ok(ChannelFilterMatch::Wildcard(wildcard))
+            }
+        }
+
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+            self.visit_str(&v)
+        }
+    }
+}
+
+pub(crate) mod client {
+    use super::*;
+    use ibc_relayer_types::core::ics24_host::identifier::ClientId;
+
+    pub struct ClientFilterMatchVisitor;
+
+    impl<'de> de::Visitor<'de> for ClientFilterMatchVisitor {
+        type Value = ClientFilterMatch;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("valid ClientId or wildcard")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if let Ok(client_id) = ClientId::from_str(v) {
+                Ok(ClientFilterMatch::Exact(client_id))
+            } else {
+                let wildcard = parse_identifier_wildcard(v)?;
+                Ok(ClientFilterMatch::Wildcard(wildcard))
+            }
+        }
+
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+            self.visit_str(&v)
+        }
+    }
+}
+
+pub(crate) mod denom {
+    use super::*;
+
+    pub struct DenomFilterMatchVisitor;
+
+    impl<'de> de::Visitor<'de> for DenomFilterMatchVisitor {
+        type Value = DenomFilterMatch;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("denom or wildcard")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if v.contains('*') {
+                let wildcard = v.parse().map_err(E::custom)?;
+                Ok(DenomFilterMatch::Wildcard(wildcard))
+            } else {
+                Ok(DenomFilterMatch::Exact(v.to_string()))
+            }
+        }
+
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+            self.visit_str(&v)
+        }
+    }
+}
+
+pub(crate) mod connection {
+    use super::*;
+    use ibc_relayer_types::core::ics24_host::identifier::ConnectionId;
+
+    pub struct ConnectionFilterMatchVisitor;
+
+    impl<'de> de::Visitor<'de> for ConnectionFilterMatchVisitor {
+        type Value = ConnectionFilterMatch;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("valid ConnectionId or wildcard")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if let Ok(connection_id) = ConnectionId::from_str(v) {
+                Ok(ConnectionFilterMatch::Exact(connection_id))
+            } else {
+                let wildcard = parse_identifier_wildcard(v)?;
+                Ok(ConnectionFilterMatch::Wildcard(wildcard))
+            }
+        }
+
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+            self.visit_str(&v)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::error::ErrorDetail;
+    use crate::config::PacketFilter;
+    use ibc_relayer_types::core::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use ibc_relayer_types::core::ics04_channel::version::Version;
+
+    #[test]
+    fn deserialize_packet_filter_policy() {
+        let toml_content = r#"
+            policy = 'allow'
+            list = [
+              ['ica*', '*'],
+              ['transfer', 'channel-0'],
+            ]
+            "#;
+
+        let filter_policy: PacketFilter =
+            toml::from_str(toml_content).expect("could not parse filter policy");
+
+        dbg!(filter_policy);
+    }
+
+    #[test]
+    fn packet_filter_rules_first_match_wins() {
+        let toml_content = r#"
+            policy = 'rules'
+            list = [
+              { action = 'deny', port = 'transfer', channel = 'channel-1' },
+              { action = 'allow', port = 'transfer', channel = '*' },
+              { action = 'deny', port = 'ica*', channel = 'channel-0' },
+              { action = 'allow', port = 'ica*', channel = 'channel-0' },
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(toml_content).expect("could not parse filter policy");
+
+        let transfer = PortId::from_str("transfer").unwrap();
+        let ica = PortId::from_str("icahost").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+
+        // An earlier allow shadows a later deny
+        assert!(pf.is_allowed(&transfer, &channel_0));
+        // An earlier deny shadows a later allow
+        assert!(!pf.is_allowed(&transfer, &channel_1));
+        assert!(!pf.is_allowed(&ica, &channel_0));
+        // Channels without a matching rule are denied
+        assert!(!pf.is_allowed(&ica, &channel_1));
+
+        pf.assert_round_trip().unwrap();
+    }
+
+    #[test]
+    fn serialize_packet_filter_policy() {
+        use std::str::FromStr;
+
+        use ibc_relayer_types::core::ics24_host::identifier::{ChannelId, PortId};
+
+        let filter_policy = ChannelFilters::new(vec![
+            (
+                FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
+                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            ),
+            (
+                FilterPattern::Wildcard("ica*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+        ]);
+
+        let fp = PacketFilter::Allow(filter_policy);
+        let toml_str = toml::to_string_pretty(&fp).expect("could not serialize packet filter");
+
+        println!("{}", toml_str);
+    }
+
+    #[test]
+    fn packet_filter_assert_round_trip() {
+        let pf = PacketFilter::Allow(ChannelFilters::new(vec![
+            (
+                FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
+                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            ),
+            (
+                FilterPattern::Wildcard("ica*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+        ]));
+
+        pf.assert_round_trip().unwrap();
+        PacketFilter::AllowAll.assert_round_trip().unwrap();
+
+        // The case insensitivity of a wildcard is not serialized
+        let pf = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Wildcard(Wildcard::new_case_insensitive("ICA*".to_string()).unwrap()),
+            FilterPattern::Wildcard("*".parse().unwrap()),
+        )]));
+
+        let err = pf.assert_round_trip().unwrap_err();
+
+        match err.detail() {
+            ErrorDetail::FilterRoundTrip(e) => {
+                assert_eq!(e.toml, toml::to_string(&pf).unwrap());
+                assert_eq!(e.parsed_toml, e.toml);
+            }
+            _ => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn channel_filter_iter_exact() {
+        let toml_content = r#"
+            policy = 'deny'
+            list = [
+              ['ica', 'channel-*'],
+              ['ica*', '*'],
+              ['transfer', 'channel-0'],
+              ['transfer*', 'channel-1'],
+              ['ft-transfer', 'channel-2'],
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(toml_content).expect("could not parse filter policy");
+
+        if let PacketFilter::Deny(channel_filters) = pf {
+            let exact_matches = channel_filters.iter_exact().collect::<Vec<_>>();
+            assert_eq!(
+                exact_matches,
+                vec![
+                    (
+                        &PortId::from_str("transfer").unwrap(),
+                        &ChannelId::from_str("channel-0").unwrap()
+                    ),
+                    (
+                        &PortId::from_str("ft-transfer").unwrap(),
+                        &ChannelId::from_str("channel-2").unwrap()
+                    )
+                ]
+            );
+        } else {
+            panic!("expected `PacketFilter::Deny` variant");
+        }
+    }
+
+    #[test]
+    fn channel_filter_iter_wildcards() {
+        let toml_content = r#"
+            policy = 'deny'
+            list = [
+              ['ica', 'channel-*'],
+              ['transfer', 'channel-0'],
+              ['transfer*', 'channel-1'],
+              ['ft-transfer', 'channel-2'],
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(toml_content).expect("could not parse filter policy");
+
+        if let PacketFilter::Deny(channel_filters) = pf {
+            let wildcards = channel_filters
+                .iter_wildcards()
+                .map(|(port, channel)| (port.to_string(), channel.to_string()))
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                wildcards,
+                vec![
+                    ("ica".to_string(), "channel-*".to_string()),
+                    ("transfer*".to_string(), "channel-1".to_string()),
+                ]
+            );
+            assert_eq!(channel_filters.exact_count(), 2);
+            assert_eq!(channel_filters.wildcard_count(), 2);
+            assert_eq!(
+                channel_filters.exact_count() + channel_filters.wildcard_count(),
+                channel_filters.len()
+            );
+            assert!(!channel_filters.is_empty());
+        } else {
+            panic!("expected `PacketFilter::Deny` variant");
+        }
+    }
+
+    #[test]
+    fn channel_filters_sorted() {
+        let toml_a = r#"
+            policy = 'allow'
+            list = [
+              ['transfer', 'channel-1'],
+              ['ica*', '*'],
+              ['transfer', 'channel-0'],
+            ]
+            "#;
+
+        let toml_b = r#"
+            policy = 'allow'
+            list = [
+              ['transfer', 'channel-0'],
+              ['transfer', 'channel-1'],
+              ['ica*', '*'],
+            ]
+            "#;
+
+        let sort_keys = |toml_content: &str| {
+            let pf: PacketFilter =
+                toml::from_str(toml_content).expect("could not parse filter policy");
+
+            if let PacketFilter::Allow(channel_filters) = pf {
+                channel_filters
+                    .sorted()
+                    .0
+                    .iter()
+                    .map(ChannelFilters::entry_sort_key)
+                    .collect::<Vec<_>>()
+            } else {
+                panic!("expected `PacketFilter::Allow` variant");
+            }
+        };
+
+        assert_eq!(sort_keys(toml_a), sort_keys(toml_b));
+        assert_eq!(
+            sort_keys(toml_a),
+            vec!["ica*/*", "transfer/channel-0", "transfer/channel-1"]
+        );
+    }
+
+    #[test]
+    fn channel_filters_validate_identifiers() {
+        let valid = ChannelFilters::new(vec![
+            (
+                FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
+                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            ),
+            (
+                FilterPattern::Wildcard("ica*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+        ]);
+
+        assert!(valid.validate_identifiers().is_ok());
+
+        // Deserializing a `PortId` directly does not validate it
+        let long_port: PortId = serde_json::from_value(serde_json::Value::String("p".repeat(129)))
+            .expect("could not deserialize port");
+
+        let invalid = ChannelFilters::new(vec![
+            (
+                FilterPattern::Exact(long_port),
+                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            ),
+            (
+                FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
+                FilterPattern::Exact(ChannelId::from_str("channel-1").unwrap()),
+            ),
+        ]);
+
+        let violations = invalid.validate_identifiers().unwrap_err();
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("invalid port `ppp"));
+    }
+
+    #[test]
+    fn connection_filters() {
+        let toml_content = r#"
+            connections = ['connection-0', 'connection-1*']
+            "#;
+
+        let filters: ConnectionFilters =
+            toml::from_str(toml_content).expect("could not parse connection filter");
+
+        assert_eq!(filters.len(), 2);
+
+        let connection = |id: &str| ConnectionId::from_str(id).unwrap();
+
+        // Exact
+        assert!(filters.is_connection_allowed(&connection("connection-0")));
+        assert!(!filters.is_connection_allowed(&connection("connection-2")));
+        // Wildcard
+        assert!(filters.is_connection_allowed(&connection("connection-1")));
+        assert!(filters.is_connection_allowed(&connection("connection-12")));
+
+        assert!(filters.are_connection_hops_allowed(&[
+            connection("connection-0"),
+            connection("connection-1")
+        ]));
+        assert!(!filters.are_connection_hops_allowed(&[
+            connection("connection-0"),
+            connection("connection-2")
+        ]));
+
+        let empty: ConnectionFilters =
+            toml::from_str("").expect("could not parse connection filter");
+
+        assert!(empty.is_empty());
+        assert!(empty.is_connection_allowed(&connection("connection-2")));
+    }
+
+    #[test]
+    fn packet_filter_deny_policy() {
+        let deny_policy = r#"
+            policy = 'deny'
+            list = [
+              ['ica', 'channel-*'],
+              ['ica*', '*'],
+              ['transfer', 'channel-0'],
+              ['transfer*', 'channel-1'],
+              ['ft-transfer', 'channel-2'],
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(deny_policy).expect("could not parse filter policy");
+
+        assert!(!pf.is_allowed(
+            &PortId::from_str("ft-transfer").unwrap(),
+            &ChannelId::from_str("channel-2").unwrap()
+        ));
+        assert!(pf.is_allowed(
+            &PortId::from_str("ft-transfer").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+        assert!(pf.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-2").unwrap()
+        ));
+        assert!(!pf.is_allowed(
+            &PortId::from_str("ica-1").unwrap(),
+            &ChannelId::from_str("channel-2").unwrap()
+        ));
+    }
+
+    #[test]
+    fn packet_filter_allow_policy() {
+        let allow_policy = r#"
+            policy = 'allow'
+            list = [
+              ['ica', 'channel-*'],
+              ['ica*', '*'],
+              ['transfer', 'channel-0'],
+              ['transfer*', 'channel-1'],
+              ['ft-transfer', 'channel-2'],
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(allow_policy).expect("could not parse filter policy");
+
+        assert!(pf.is_allowed(
+            &PortId::from_str("ft-transfer").unwrap(),
+            &ChannelId::from_str("channel-2").unwrap()
+        ));
+        assert!(!pf.is_allowed(
+            &PortId::from_str("ft-transfer").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+        assert!(!pf.is_allowed(
+            &PortId::from_str("transfer-1").unwrap(),
+            &ChannelId::from_str("channel-2").unwrap()
+        ));
+        assert!(pf.is_allowed(
+            &PortId::from_str("ica-1").unwrap(),
+            &ChannelId::from_str("channel-2").unwrap()
+        ));
+        assert!(pf.is_allowed(
+            &PortId::from_str("ica").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+    }
+
+    #[test]
+    fn packet_filter_regex() {
+        let allow_policy = r#"
+            policy = 'allow'
+            list = [
+              ['transfer*', 'channel-1'],
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(allow_policy).expect("could not parse filter policy");
+
+        assert!(!pf.is_allowed(
+            &PortId::from_str("ft-transfer").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+        assert!(!pf.is_allowed(
+            &PortId::from_str("ft-transfer-port").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+    }
+
+    #[test]
+    fn load_named_packet_filters() {
+        let toml_content = r#"
+            [filters.strict]
+            policy = 'allow'
+            list = [
+              ['transfer', 'channel-0'],
+            ]
+
+            [filters.no-ica]
+            policy = 'deny'
+            list = [
+              ['ica*', '*'],
+            ]
+            "#;
+
+        let filters = PacketFilter::load_named(toml_content).expect("could not parse presets");
+        assert_eq!(filters.len(), 2);
+
+        let strict = &filters["strict"];
+        assert!(strict.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-0").unwrap()
+        ));
+        assert!(!strict.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+
+        let no_ica = &filters["no-ica"];
+        assert!(!no_ica.is_allowed(
+            &PortId::from_str("icahost").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+        assert!(no_ica.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+    }
+
+    #[test]
+    fn packet_filter_covers_all() {
+        let deny_policy = r#"
+            policy = 'deny'
+            list = [
+              ['transfer', 'channel-1'],
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(deny_policy).expect("could not parse filter policy");
+
+        let transfer = PortId::from_str("transfer").unwrap();
+        let mut channels = vec![
+            (transfer.clone(), ChannelId::from_str("channel-0").unwrap()),
+            (transfer.clone(), ChannelId::from_str("channel-2").unwrap()),
+        ];
+
+        assert!(pf.covers_all(&channels));
+        assert!(pf.covers_all(&[]));
+
+        channels.push((transfer, ChannelId::from_str("channel-1").unwrap()));
+
+        assert!(!pf.covers_all(&channels));
+    }
+
+    #[test]
+    fn channel_filters_minimal_cover() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let ica = PortId::from_str("icahost").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+        let channel_2 = ChannelId::from_str("channel-2").unwrap();
+
+        let universe = vec![
+            (transfer.clone(), channel_0.clone()),
+            (transfer.clone(), channel_1.clone()),
+            (ica.clone(), channel_0.clone()),
+            (ica.clone(), channel_2.clone()),
+        ];
+
+        let channels = vec![
+            (transfer.clone(), channel_1.clone()),
+            (ica.clone(), channel_2.clone()),
+            (transfer.clone(), channel_0.clone()),
+        ];
+
+        let filters = ChannelFilters::minimal_cover(&channels, &universe);
+
+        assert_eq!(filters.len(), 2);
+        assert!(!filters.is_exact());
+        assert_eq!(
+            filters.iter_exact().collect::<Vec<_>>(),
+            vec![(&ica, &channel_2)]
+        );
+
+        for (port_id, channel_id) in &universe {
+            assert_eq!(
+                filters.matches((port_id, channel_id)),
+                channels.contains(&(port_id.clone(), channel_id.clone()))
+            );
+        }
+    }
+
+    #[cfg(feature = "remote-filter")]
+    fn serve_once(response: String) -> String {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        format!("http://{}/filter.toml", addr)
+    }
+
+    #[test]
+    #[cfg(feature = "remote-filter")]
+    fn packet_filter_from_url() {
+        let body = "policy = 'allow'\nlist = [['transfer', 'channel-0']]\n";
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+
+        let pf = PacketFilter::from_url(&serve_once(response)).unwrap();
+
+        assert!(pf.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-0").unwrap()
+        ));
+        assert!(!pf.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+
+        let garbage = "HTTP/1.1 200 OK\r\nContent-Length: 9\r\nConnection: close\r\n\r\n{garbage}";
+
+        assert!(PacketFilter::from_url(&serve_once(garbage.to_string())).is_err());
+
+        let not_found = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+        assert!(PacketFilter::from_url(&serve_once(not_found.to_string())).is_err());
+    }
+
+    #[test]
+    fn packet_filter_connection_coverage() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channels = vec![
+            (transfer.clone(), ChannelId::from_str("channel-0").unwrap()),
+            (transfer.clone(), ChannelId::from_str("channel-1").unwrap()),
+        ];
+
+        assert_eq!(
+            PacketFilter::AllowAll.connection_coverage(&channels),
+            ConnectionCoverage::FullyAllowed
+        );
+
+        let deny_transfer = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Exact(transfer.clone()),
+            FilterPattern::Wildcard("*".parse().unwrap()),
+        )]));
+
+        assert_eq!(
+            deny_transfer.connection_coverage(&channels),
+            ConnectionCoverage::FullyDenied
+        );
+
+        let allow_channel_0 = PacketFilter::Allow(ChannelFilters::new(vec![(
+            FilterPattern::Exact(transfer),
+            FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+        )]));
+
+        assert_eq!(
+            allow_channel_0.connection_coverage(&channels),
+            ConnectionCoverage::PartiallyAllowed
+        );
+    }
+
+    #[test]
+    fn packet_filter_reconcile() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let rule = |channel: &str| {
+            ChannelFilterRule::new(
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(ChannelId::from_str(channel).unwrap()),
+            )
+        };
+
+        let old = PacketFilter::Allow(ChannelFilters::from_rules(vec![
+            rule("channel-0"),
+            rule("channel-1"),
+        ]));
+        let new = PacketFilter::Allow(ChannelFilters::from_rules(vec![
+            rule("channel-1"),
+            rule("channel-2"),
+        ]));
+
+        let plan = old.reconcile(&new);
+
+        assert!(!plan.policy_changed);
+        assert_eq!(plan.added, vec![PolicyRule::Channel(rule("channel-2"))]);
+        assert_eq!(plan.removed, vec![PolicyRule::Channel(rule("channel-0"))]);
+
+        assert!(old.reconcile(&old).is_empty());
+
+        let deny = PacketFilter::Deny(ChannelFilters::from_rules(vec![
+            rule("channel-0"),
+            rule("channel-1"),
+        ]));
+
+        let plan = old.reconcile(&deny);
+
+        assert!(plan.policy_changed);
+        assert!(plan.added.is_empty());
+        assert!(plan.removed.is_empty());
+    }
+
+    #[test]
+    fn packet_filter_merge() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+        let channel_2 = ChannelId::from_str("channel-2").unwrap();
+
+        let filters = |channels: &[&ChannelId]| {
+            ChannelFilters::new(
+                channels
+                    .iter()
+                    .map(|channel_id| {
+                        (
+                            FilterPattern::Exact(transfer.clone()),
+                            FilterPattern::Exact((*channel_id).clone()),
+                        )
+                    })
+                    .collect(),
+            )
+        };
+
+        let allow = PacketFilter::Allow(filters(&[&channel_0, &channel_1]));
+        let deny = PacketFilter::Deny(filters(&[&channel_1]));
+
+        // AllowAll yields the other side
+        assert_eq!(
+            PacketFilter::AllowAll.merge(PacketFilter::AllowAll),
+            PacketFilter::AllowAll
+        );
+        assert_eq!(PacketFilter::AllowAll.merge(allow.clone()), allow);
+        assert_eq!(allow.clone().merge(PacketFilter::AllowAll), allow);
+        assert_eq!(PacketFilter::AllowAll.merge(deny.clone()), deny);
+        assert_eq!(deny.clone().merge(PacketFilter::AllowAll), deny);
+
+        // Lists with the same policy are concatenated
+        assert_eq!(
+            allow
+                .clone()
+                .merge(PacketFilter::Allow(filters(&[&channel_1, &channel_2]))),
+            PacketFilter::Allow(filters(&[&channel_0, &channel_1, &channel_2]))
+        );
+        assert_eq!(
+            deny.clone()
+                .merge(PacketFilter::Deny(filters(&[&channel_2]))),
+            PacketFilter::Deny(filters(&[&channel_1, &channel_2]))
+        );
+
+        // A deny list on top of an allow list only allows what both allow
+        let merged = allow.clone().merge(deny.clone());
+        assert!(merged.is_allowed(&transfer, &channel_0));
+        assert!(!merged.is_allowed(&transfer, &channel_1));
+        assert!(!merged.is_allowed(&transfer, &channel_2));
+
+        // An allow list on top of a deny list allows the channels it lists
+        let merged = deny.merge(allow);
+        assert!(merged.is_allowed(&transfer, &channel_0));
+        assert!(merged.is_allowed(&transfer, &channel_1));
+        assert!(!merged.is_allowed(&transfer, &channel_2));
+    }
+
+    #[test]
+    fn packet_filter_merge_precedence() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channels = (0..5).map(ChannelId::new).collect::<Vec<_>>();
+
+        let rule = |i: usize| {
+            (
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channels[i].clone()),
+            )
+        };
+
+        let filters = [
+            PacketFilter::AllowAll,
+            PacketFilter::Allow(ChannelFilters::new(vec![rule(0), rule(1)])),
+            PacketFilter::Deny(ChannelFilters::new(vec![rule(1), rule(2)])),
+            PacketFilter::Rules(vec![
+                FilterRule::new(FilterAction::Deny, rule(0).0, rule(0).1),
+                FilterRule::new(FilterAction::Allow, rule(2).0, rule(2).1),
+                FilterRule::new(FilterAction::Allow, rule(3).0, rule(3).1),
+            ]),
+        ];
+
+        // Whether the rules of the filter allow or deny the channel, if any matches it
+        let verdict = |pf: &PacketFilter, channel_id: &ChannelId| match pf {
+            PacketFilter::Allow(filters) => filters.matches((&transfer, channel_id)).then(|| true),
+            PacketFilter::Deny(filters) => filters.matches((&transfer, channel_id)).then(|| false),
+            PacketFilter::Rules(rules) => rules
+                .iter()
+                .find(|rule| rule.matches(&transfer, channel_id))
+                .map(|rule| rule.action == FilterAction::Allow),
+            PacketFilter::AllowAll => None,
+        };
+
+        let allows_unmatched =
+            |pf: &PacketFilter| matches!(pf, PacketFilter::Deny(_) | PacketFilter::AllowAll);
+
+        for (base, other) in filters.iter().cartesian_product(filters.iter()) {
+            let merged = base.clone().merge(other.clone());
+
+            for channel_id in &channels {
+                let expected = verdict(other, channel_id)
+                    .or_else(|| verdict(base, channel_id))
+                    .unwrap_or_else(|| allows_unmatched(base) && allows_unmatched(other));
+
+                assert_eq!(
+                    merged.is_allowed(&transfer, channel_id),
+                    expected,
+                    "{other:?} on top of {base:?} on {channel_id}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn packet_filter_merge_limits() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+
+        let rule = || {
+            ChannelFilterRule::new(
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channel_0.clone()),
+            )
+        };
+
+        // The limits of the rule on top take precedence, the others are kept
+        let merged = PacketFilter::Allow(ChannelFilters::from_rules(vec![rule()
+            .with_max_data_bytes(100)
+            .with_min_height(10)]))
+        .merge(PacketFilter::Allow(ChannelFilters::from_rules(vec![rule(
+        )
+        .with_min_height(20)])));
+
+        assert_eq!(
+            merged,
+            PacketFilter::Allow(ChannelFilters::from_rules(vec![rule()
+                .with_max_data_bytes(100)
+                .with_min_height(20)]))
+        );
+
+        // The limits are kept by the rules of a `rules` filter
+        let merged = PacketFilter::Allow(ChannelFilters::from_rules(vec![rule()])).merge(
+            PacketFilter::Deny(ChannelFilters::from_rules(vec![rule()
+                .with_max_data_bytes(100)
+                .with_min_height(10)])),
+        );
+
+        assert_eq!(
+            merged,
+            PacketFilter::Rules(vec![
+                FilterRule::new(FilterAction::Deny, rule().port, rule().channel)
+                    .with_max_data_bytes(100)
+                    .with_min_height(10),
+                FilterRule::new(FilterAction::Allow, rule().port, rule().channel),
+            ])
+        );
+
+        let height = |h| Height::new(0, h).unwrap();
+
+        assert!(merged.is_packet_allowed(&transfer, &channel_0, height(5), &[0; 200]));
+        assert!(merged.is_packet_allowed(&transfer, &channel_0, height(15), &[0; 50]));
+        assert!(!merged.is_packet_allowed(&transfer, &channel_0, height(15), &[0; 200]));
+    }
+
+    #[test]
+    fn packet_filter_to_combined_regex() {
+        let pf = PacketFilter::Allow(ChannelFilters::new(vec![
+            (
+                FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
+                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            ),
+            (
+                FilterPattern::Wildcard("ica*".parse().unwrap()),
+                FilterPattern::Wildcard("channel-1*".parse().unwrap()),
+            ),
+        ]));
+
+        let regex = pf.to_combined_regex().unwrap();
+
+        let sample = [
+            ("transfer", "channel-0"),
+            ("transfer", "channel-00"),
+            ("transfer", "channel-1"),
+            ("icahost", "channel-1"),
+            ("icahost", "channel-12"),
+            ("icahost", "channel-2"),
+            ("xicahost", "channel-1"),
+        ];
+
+        for (port, channel) in sample {
+            let port_id = PortId::from_str(port).unwrap();
+            let channel_id = ChannelId::from_str(channel).unwrap();
+
+            assert_eq!(
+                regex.is_match(&format!("{}/{}", port, channel)),
+                pf.is_allowed(&port_id, &channel_id),
+                "{}/{}",
+                port,
+                channel
+            );
+        }
+
+        let empty = PacketFilter::Allow(ChannelFilters::empty())
+            .to_combined_regex()
+            .unwrap();
+        assert!(!empty.is_match("transfer/channel-0"));
+        assert!(!empty.is_match(""));
+
+        assert!(PacketFilter::AllowAll.to_combined_regex().is_none());
+        assert!(PacketFilter::Deny(ChannelFilters::empty())
+            .to_combined_regex()
+            .is_none());
+    }
+
+    #[test]
+    fn packet_filter_semantics() {
+        assert_eq!(
+            PacketFilter::Allow(ChannelFilters::empty()).semantics(),
+            FilterSemantics::AllowList
+        );
+        assert_eq!(
+            PacketFilter::Rules(Vec::new()).semantics(),
+            FilterSemantics::AllowList
+        );
+        assert_eq!(
+            PacketFilter::Deny(ChannelFilters::empty()).semantics(),
+            FilterSemantics::DenyList
+        );
+        assert_eq!(
+            PacketFilter::AllowAll.semantics(),
+            FilterSemantics::Permissive
+        );
+    }
+
+    #[test]
+    fn packet_filter_blocks_transfer() {
+        let deny_transfer = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Exact(PortId::transfer()),
+            FilterPattern::Wildcard("*".parse().unwrap()),
+        )]));
+        assert!(deny_transfer.blocks_transfer());
+
+        let deny_all = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Wildcard("*".parse().unwrap()),
+            FilterPattern::Wildcard("*".parse().unwrap()),
+        )]));
+        assert!(deny_all.blocks_transfer());
+
+        let narrow_deny = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Exact(PortId::transfer()),
+            FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+        )]));
+        assert!(!narrow_deny.blocks_transfer());
+
+        let allow_other = PacketFilter::Allow(ChannelFilters::new(vec![(
+            FilterPattern::Wildcard("ica*".parse().unwrap()),
+            FilterPattern::Wildcard("*".parse().unwrap()),
+        )]));
+        assert!(allow_other.blocks_transfer());
+
+        let rules = PacketFilter::Rules(vec![
+            FilterRule::new(
+                FilterAction::Deny,
+                FilterPattern::Exact(PortId::transfer()),
+                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            ),
+            FilterRule::new(
+                FilterAction::Allow,
+                FilterPattern::Wildcard("*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+        ]);
+        assert!(!rules.blocks_transfer());
 
-    pub struct ChannelFilterMatchVisitor;
+        assert!(!PacketFilter::AllowAll.blocks_transfer());
+    }
 
-    impl<'de> de::Visitor<'de> for ChannelFilterMatchVisitor {
-        type Value = ChannelFilterMatch;
+    #[test]
+    fn packet_filter_to_port_index() {
+        let transfer = PortId::transfer();
+        let ica = PortId::from_str("icahost").unwrap();
+        let ica_controller = PortId::from_str("icacontroller-1").unwrap();
+        let oracle = PortId::from_str("oracle").unwrap();
 
-        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-            formatter.write_str("valid ChannelId or wildcard")
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+        let channel_12 = ChannelId::from_str("channel-12").unwrap();
+
+        let filters = ChannelFilters::new(vec![
+            (
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channel_0.clone()),
+            ),
+            (
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Wildcard("channel-1*".parse().unwrap()),
+            ),
+            (
+                FilterPattern::Exact(ica.clone()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+            (
+                FilterPattern::Wildcard("icacontroller-*".parse().unwrap()),
+                FilterPattern::Exact(channel_1.clone()),
+            ),
+        ]);
+
+        let universe = [&transfer, &ica, &ica_controller, &oracle]
+            .into_iter()
+            .cartesian_product([&channel_0, &channel_1, &channel_12])
+            .map(|(port_id, channel_id)| (port_id.clone(), channel_id.clone()))
+            .collect::<Vec<_>>();
+
+        for pf in [
+            PacketFilter::Allow(filters.clone()),
+            PacketFilter::Deny(filters),
+            PacketFilter::AllowAll,
+        ] {
+            let index = pf.to_port_index();
+
+            for (port_id, channel_id) in &universe {
+                assert_eq!(
+                    index.is_allowed(port_id, channel_id),
+                    pf.is_allowed(port_id, channel_id),
+                    "{pf:?} on {port_id}/{channel_id}"
+                );
+            }
         }
 
-        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
-            if let Ok(channel_id) = ChannelId::from_str(v) {
-                Ok(ChannelFilterMatch::Exact(channel_id))
-            } else {
-                let wildcard = v.parse().map_err(E::custom)?;
-                Ok(ChannelFilterMatch::Wildcard(wildcard))
+        let index = PacketFilter::Allow(ChannelFilters::new(vec![(
+            FilterPattern::Exact(ica.clone()),
+            FilterPattern::Wildcard("*".parse().unwrap()),
+        )]))
+        .to_port_index();
+
+        assert_eq!(index.get(&ica), Some(&ChannelAllowSet::All));
+        assert_eq!(index.get(&transfer), None);
+
+        // Rules with an expiry only match until they expire
+        let expiring = ChannelFilters::from_rules(vec![
+            ChannelFilterRule {
+                expires_at: Some(1_000),
+                ..ChannelFilterRule::new(
+                    FilterPattern::Exact(transfer.clone()),
+                    FilterPattern::Exact(channel_0.clone()),
+                )
+            },
+            ChannelFilterRule {
+                expires_at: Some(2_000),
+                ..ChannelFilterRule::new(
+                    FilterPattern::Wildcard("ica*".parse().unwrap()),
+                    FilterPattern::Wildcard("*".parse().unwrap()),
+                )
+            },
+            ChannelFilterRule::new(
+                FilterPattern::Exact(oracle.clone()),
+                FilterPattern::Exact(channel_1.clone()),
+            ),
+        ]);
+
+        for pf in [
+            PacketFilter::Allow(expiring.clone()),
+            PacketFilter::Deny(expiring),
+        ] {
+            let index = pf.to_port_index();
+
+            for secs in [0, 1_000, 1_500, 2_000] {
+                let now = UNIX_EPOCH + Duration::from_secs(secs);
+
+                for (port_id, channel_id) in &universe {
+                    assert_eq!(
+                        index.is_allowed_at_time(port_id, channel_id, now),
+                        pf.is_allowed_at_time(port_id, channel_id, now),
+                        "{pf:?} on {port_id}/{channel_id} at {secs}"
+                    );
+                }
             }
+
+            let now = UNIX_EPOCH + Duration::from_secs(1_500);
+            assert_eq!(
+                index.is_allowed_at_time(&transfer, &channel_0, now),
+                index.is_allowed_at_time(&transfer, &channel_12, now)
+            );
+            assert_ne!(
+                index.is_allowed_at_time(&ica, &channel_0, now),
+                index.is_allowed_at_time(&transfer, &channel_0, now)
+            );
         }
+    }
 
-        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
-            self.visit_str(&v)
+    #[test]
+    fn simple_glob_matches_like_regex() {
+        let patterns = ["channel-*", "*-7", "chan*-1", "*", "transfer", "ica(?:.*)"];
+        let texts = [
+            "channel-0",
+            "channel-17",
+            "chan-1",
+            "ch-1",
+            "transfer",
+            "transfers",
+            "icahost",
+            "",
+        ];
+
+        for pattern in patterns {
+            let wildcard = Wildcard::new(pattern.to_string()).unwrap();
+            assert!(wildcard.simple.is_some(), "{pattern}");
+
+            for text in texts {
+                assert_eq!(
+                    wildcard.is_match(text),
+                    wildcard.regex.is_match(text),
+                    "{pattern} on {text}"
+                );
+            }
         }
+
+        let complex = Wildcard::new("ica*-*".to_string()).unwrap();
+        assert!(complex.simple.is_none());
+        assert!(complex.is_match("icacontroller-1"));
+        assert!(!complex.is_match("icahost"));
+
+        let alternation =
+            Wildcard::any_of(vec!["ica*".parse().unwrap(), "transfer".parse().unwrap()]);
+        assert!(alternation.simple.is_none());
+        assert!(alternation.is_match("transfer"));
+        assert!(alternation.is_match("icahost"));
+
+        let pipe = Wildcard::new("ica*|transfer".to_string()).unwrap();
+        assert!(pipe.simple.is_some());
+        assert!(pipe.is_match("ica-a|transfer"));
+        assert!(!pipe.is_match("transfer"));
+
+        let case_insensitive = Wildcard::from_str_ci("Channel-*").unwrap();
+        assert!(case_insensitive.simple.is_none());
+        assert!(case_insensitive.is_match("channel-0"));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::PacketFilter;
+    /// Checks that a 500-entry filter of simple globs gives the same results
+    /// when matched without and with a regex.
+    #[test]
+    fn large_filter_simple_globs_match_regex() {
+        let filters = ChannelFilters::new(
+            (0..500)
+                .map(|i| {
+                    (
+                        FilterPattern::Wildcard(format!("port-{i}-*").parse().unwrap()),
+                        FilterPattern::Wildcard(format!("*-{i}").parse().unwrap()),
+                    )
+                })
+                .collect(),
+        );
+
+        let without_simple = ChannelFilters::from_rules(
+            filters
+                .0
+                .iter()
+                .cloned()
+                .map(|mut rule| {
+                    if let FilterPattern::Wildcard(wildcard) = &mut rule.port {
+                        wildcard.simple = None;
+                    }
+                    if let FilterPattern::Wildcard(wildcard) = &mut rule.channel {
+                        wildcard.simple = None;
+                    }
+                    rule
+                })
+                .collect(),
+        );
+
+        let simple = PacketFilter::Allow(filters);
+        let regex = PacketFilter::Allow(without_simple);
+
+        for (port, channel, expected) in [
+            ("port-499-transfer", "channel-499", true),
+            ("port-10-transfer", "channel-10", true),
+            ("port-0-transfer", "channel-10", false),
+            ("port-12-icahost", "channel-12", true),
+            ("port-12-icahost", "channel-13", false),
+            ("transfer", "channel-499", false),
+        ] {
+            let port_id = PortId::from_str(port).unwrap();
+            let channel_id = ChannelId::from_str(channel).unwrap();
+
+            assert_eq!(simple.is_allowed(&port_id, &channel_id), expected);
+            assert_eq!(regex.is_allowed(&port_id, &channel_id), expected);
+        }
+    }
 
     #[test]
-    fn deserialize_packet_filter_policy() {
+    fn packet_filter_decision_table() {
+        let pf = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Wildcard("ica*".parse().unwrap()),
+            FilterPattern::Wildcard("*".parse().unwrap()),
+        )]));
+
+        let transfer = PortId::from_str("transfer").unwrap();
+        let ica = PortId::from_str("icahost").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+
+        let universe = vec![
+            (transfer.clone(), channel_0.clone()),
+            (ica.clone(), channel_1.clone()),
+            (transfer.clone(), channel_1.clone()),
+        ];
+
+        assert_eq!(
+            pf.decision_table(&universe),
+            vec![
+                (transfer.clone(), channel_0, true),
+                (ica, channel_1.clone(), false),
+                (transfer, channel_1, true),
+            ]
+        );
+        assert!(pf.decision_table(&[]).is_empty());
+    }
+
+    #[test]
+    fn packet_filter_is_allowed_qualified() {
+        let pf = PacketFilter::Allow(ChannelFilters::new(vec![(
+            FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
+            FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+        )]));
+
+        assert!(pf.is_allowed_qualified("transfer/channel-0").unwrap());
+        assert!(!pf.is_allowed_qualified("transfer/channel-1").unwrap());
+
+        assert!(pf.is_allowed_qualified("transfer-channel-0").is_err());
+        assert!(pf.is_allowed_qualified("transfer/channel/0").is_err());
+    }
+
+    #[test]
+    fn packet_filter_product_deserialize() {
         let toml_content = r#"
             policy = 'allow'
+            list = { ports = ['transfer', 'ica*'], channels = ['channel-0', 'channel-1'] }
+            "#;
+
+        let pf: PacketFilter = toml::from_str(toml_content).expect("could not parse filter policy");
+
+        let ports = ["transfer", "icahost", "icacontroller-1", "other"];
+        let channels = ["channel-0", "channel-1", "channel-2"];
+
+        let port_patterns = vec![
+            FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
+            FilterPattern::Wildcard("ica*".parse().unwrap()),
+        ];
+        let channel_patterns = vec![
+            FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            FilterPattern::Exact(ChannelId::from_str("channel-1").unwrap()),
+        ];
+
+        let cross = PacketFilter::Allow(ChannelFilters::new(
+            port_patterns
+                .iter()
+                .cartesian_product(channel_patterns.iter())
+                .map(|(port, channel)| (port.clone(), channel.clone()))
+                .collect(),
+        ));
+
+        for (port, channel) in ports.iter().cartesian_product(channels.iter()) {
+            let port_id = PortId::from_str(port).unwrap();
+            let channel_id = ChannelId::from_str(channel).unwrap();
+
+            assert_eq!(
+                pf.is_allowed(&port_id, &channel_id),
+                cross.is_allowed(&port_id, &channel_id),
+                "mismatch for {port}/{channel}"
+            );
+        }
+
+        assert!(pf.is_allowed(
+            &PortId::from_str("icahost").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+        assert!(!pf.is_allowed(
+            &PortId::from_str("other").unwrap(),
+            &ChannelId::from_str("channel-0").unwrap()
+        ));
+        assert!(!pf.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-2").unwrap()
+        ));
+
+        let product = ChannelFilterProduct::new(
+            vec![FilterPattern::Exact(PortId::from_str("transfer").unwrap())],
+            vec![FilterPattern::Wildcard("channel-*".parse().unwrap())],
+        );
+
+        assert!(product.matches(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-7").unwrap()
+        ));
+    }
+
+    #[test]
+    fn packet_filter_rules_expires_at() {
+        let toml_content = r#"
+            policy = 'rules'
             list = [
-              ['ica*', '*'],
-              ['transfer', 'channel-0'],
+              { action = 'deny', port = 'transfer', channel = 'channel-5', expires_at = 1700000000 },
+              { action = 'allow', port = 'transfer', channel = '*' },
+            ]
+            "#;
+
+        let mut pf: PacketFilter =
+            toml::from_str(toml_content).expect("could not parse filter policy");
+
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_5 = ChannelId::from_str("channel-5").unwrap();
+
+        let before = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let after = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+
+        assert!(!pf.is_allowed_at_time(&transfer, &channel_5, before));
+        assert!(pf.is_allowed_at_time(&transfer, &channel_5, after));
+        assert!(pf.is_allowed(&transfer, &channel_5));
+
+        pf.prune_expired(after);
+
+        match &pf {
+            PacketFilter::Rules(rules) => assert_eq!(rules.len(), 1),
+            _ => panic!("expected rules filter, got {pf:?}"),
+        }
+    }
+
+    #[test]
+    fn packet_filter_expires_at() {
+        let toml_content = r#"
+            policy = 'deny'
+            list = [
+              { port = 'transfer', channel = 'channel-5', expires_at = 1700000000 },
+              ['transfer', 'channel-9'],
             ]
             "#;
 
-        let filter_policy: PacketFilter =
-            toml::from_str(toml_content).expect("could not parse filter policy");
+        let mut pf: PacketFilter =
+            toml::from_str(toml_content).expect("could not parse filter policy");
+
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_5 = ChannelId::from_str("channel-5").unwrap();
+        let channel_9 = ChannelId::from_str("channel-9").unwrap();
+
+        let before = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let after = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+
+        assert!(!pf.is_allowed_at_time(&transfer, &channel_5, before));
+        assert!(pf.is_allowed_at_time(&transfer, &channel_5, after));
+        assert!(!pf.is_allowed_at_time(&transfer, &channel_9, after));
+
+        // The deny rule for `channel-5` expired in 2023
+        assert!(pf.is_allowed(&transfer, &channel_5));
+        assert!(!pf.is_allowed(&transfer, &channel_9));
+
+        pf.prune_expired(before);
+
+        match &pf {
+            PacketFilter::Deny(filters) => assert_eq!(filters.len(), 2),
+            _ => panic!("expected deny filter, got {pf:?}"),
+        }
+
+        pf.prune_expired(after);
+
+        match &pf {
+            PacketFilter::Deny(filters) => assert_eq!(filters.len(), 1),
+            _ => panic!("expected deny filter, got {pf:?}"),
+        }
+
+        assert!(pf.is_allowed(&transfer, &channel_5));
+        assert!(!pf.is_allowed(&transfer, &channel_9));
+    }
+
+    #[test]
+    fn channel_filters_union() {
+        let rule = |port: &str, channel: &str| {
+            ChannelFilterRule::new(
+                FilterPattern::Exact(PortId::from_str(port).unwrap()),
+                FilterPattern::Exact(ChannelId::from_str(channel).unwrap()),
+            )
+        };
+
+        let team_a = ChannelFilters::from_rules(vec![
+            rule("transfer", "channel-0"),
+            rule("transfer", "channel-1"),
+            ChannelFilterRule::new(
+                FilterPattern::Wildcard("ica*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+        ]);
+
+        let team_b = ChannelFilters::from_rules(vec![
+            rule("transfer", "channel-1"),
+            rule("transfer", "channel-2"),
+            ChannelFilterRule::new(
+                FilterPattern::Wildcard("ica**".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+            rule("transfer", "channel-0").with_max_data_bytes(1024),
+        ]);
+
+        let union = team_a.union(team_b);
+
+        assert_eq!(union.len(), 5);
+        assert_eq!(
+            union.to_string(),
+            "transfer/channel-0, transfer/channel-1, ica*/*, transfer/channel-2, transfer/channel-0"
+        );
+
+        for (i, rule) in union.0.iter().enumerate() {
+            assert!(!union.0[i + 1..].contains(rule), "duplicate rule {rule:?}");
+        }
+    }
+
+    #[test]
+    fn wildcard_case_insensitive_unicode() {
+        let wildcard = Wildcard::new_case_insensitive("ΣΟΦΙΑ-*".to_string()).unwrap();
+
+        assert!(wildcard.is_case_insensitive());
+        assert!(wildcard.is_match("σοφια-0"));
+        assert!(wildcard.is_match("Σοφια-1"));
+        assert!(wildcard.is_match("ςοφια-2"));
+        assert!(!wildcard.is_match("sofia-0"));
+
+        let sensitive = Wildcard::new("ΣΟΦΙΑ-*".to_string()).unwrap();
+
+        assert!(!sensitive.is_case_insensitive());
+        assert!(sensitive.is_match("ΣΟΦΙΑ-0"));
+        assert!(!sensitive.is_match("σοφια-0"));
+        assert_ne!(wildcard, sensitive);
+    }
+
+    #[test]
+    fn filter_pattern_matches_ci() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let upper = PortId::from_str("Transfer").unwrap();
+
+        let exact = PortFilterMatch::Exact(transfer.clone());
+        assert!(exact.matches(&transfer));
+        assert!(!exact.matches(&upper));
+        assert!(exact.matches_ci(&upper));
+
+        let sensitive = PortFilterMatch::Wildcard("trans*".parse().unwrap());
+        assert!(sensitive.matches(&transfer));
+        assert!(!sensitive.matches(&upper));
+        assert!(sensitive.matches_ci(&upper));
+
+        let insensitive = PortFilterMatch::wildcard_ci("TRANS*").unwrap();
+        assert!(insensitive.matches(&transfer));
+        assert!(insensitive.matches(&upper));
+        assert!(insensitive.matches_ci(&upper));
+        assert!(!insensitive.matches(&PortId::from_str("icahost").unwrap()));
+    }
+
+    #[test]
+    fn packet_filter_allow_empty_denies_all() {
+        let filters = ChannelFilters::empty();
+        assert!(filters.is_empty());
+        assert_eq!(filters.exact_count(), 0);
+        assert_eq!(filters.wildcard_count(), 0);
+
+        let pf = PacketFilter::Allow(filters);
+
+        for port in ["transfer", "icahost", "ft-transfer"] {
+            for channel in ["channel-0", "channel-1", "channel-42"] {
+                assert!(!pf.is_allowed(
+                    &PortId::from_str(port).unwrap(),
+                    &ChannelId::from_str(channel).unwrap()
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn packet_filter_ports_only_deserialize() {
+        let toml_content = r#"
+            policy = 'allow'
+            list = { ports = ['transfer', 'ica*'] }
+            "#;
+
+        let pf: PacketFilter = toml::from_str(toml_content).expect("could not parse filter policy");
+
+        match &pf {
+            PacketFilter::Allow(filters) => assert_eq!(filters.len(), 2),
+            _ => panic!("expected allow filter, got {pf:?}"),
+        }
+
+        for channel in ["channel-0", "channel-7", "channel-123"] {
+            let channel_id = ChannelId::from_str(channel).unwrap();
+
+            assert!(pf.is_allowed(&PortId::from_str("transfer").unwrap(), &channel_id));
+            assert!(pf.is_allowed(&PortId::from_str("icahost").unwrap(), &channel_id));
+            assert!(!pf.is_allowed(&PortId::from_str("other").unwrap(), &channel_id));
+        }
+    }
+
+    #[test]
+    fn packet_filter_is_subset_of() {
+        let universe = ["transfer", "icahost"]
+            .iter()
+            .cartesian_product(["channel-0", "channel-1", "channel-2"].iter())
+            .map(|(port, channel)| {
+                (
+                    PortId::from_str(port).unwrap(),
+                    ChannelId::from_str(channel).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let old = PacketFilter::from_cli_arg("allow:transfer/*,icahost/channel-0").unwrap();
+        let tightened = PacketFilter::from_cli_arg("allow:transfer/channel-1").unwrap();
+        let loosened = PacketFilter::from_cli_arg("allow:transfer/*,icahost/*").unwrap();
+
+        assert!(old.is_subset_of(&old, &universe));
+        assert!(tightened.is_subset_of(&old, &universe));
+        assert!(!loosened.is_subset_of(&old, &universe));
+        assert!(old.is_subset_of(&loosened, &universe));
+        assert!(old.is_subset_of(&PacketFilter::AllowAll, &universe));
+        assert!(!PacketFilter::AllowAll.is_subset_of(&old, &universe));
+    }
+
+    #[test]
+    fn packet_filter_from_connection_channels() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let icahost = PortId::from_str("icahost").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_3 = ChannelId::from_str("channel-3").unwrap();
+
+        let connection_0 = ConnectionId::from_str("connection-0").unwrap();
+        let connection_1 = ConnectionId::from_str("connection-1").unwrap();
+
+        let channel = |port_id: &PortId, channel_id: &ChannelId, hops: &[&ConnectionId]| {
+            IdentifiedChannelEnd::new(
+                port_id.clone(),
+                channel_id.clone(),
+                ChannelEnd::new(
+                    State::Open,
+                    Order::Unordered,
+                    Counterparty::new(port_id.clone(), Some(channel_id.clone())),
+                    hops.iter().map(|&hop| hop.clone()).collect(),
+                    Version::empty(),
+                ),
+            )
+        };
+
+        let channels = vec![
+            channel(&transfer, &channel_0, &[&connection_0]),
+            channel(&icahost, &channel_3, &[&connection_1, &connection_0]),
+            channel(&transfer, &channel_0, &[&connection_0]),
+            channel(&icahost, &channel_0, &[&connection_1]),
+        ];
+
+        let pf = PacketFilter::from_connection_channels(&connection_0, &channels);
+
+        match &pf {
+            PacketFilter::Allow(filters) => assert_eq!(filters.len(), 2),
+            _ => panic!("expected allow filter, got {pf:?}"),
+        }
+
+        assert!(pf.is_allowed(&transfer, &channel_0));
+        assert!(pf.is_allowed(&icahost, &channel_3));
+        assert!(!pf.is_allowed(&transfer, &channel_3));
+        assert!(!pf.is_allowed(&icahost, &channel_0));
+        assert!(!pf.is_allowed(&transfer, &ChannelId::from_str("channel-1").unwrap()));
+    }
+
+    #[test]
+    fn packet_filter_from_chain_channels() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let icahost = PortId::from_str("icahost").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+        let channel_2 = ChannelId::from_str("channel-2").unwrap();
+
+        let channels = vec![
+            (transfer.clone(), channel_0.clone()),
+            (transfer.clone(), channel_1.clone()),
+            (icahost.clone(), channel_2.clone()),
+        ];
+
+        let pf = PacketFilter::from_chain_channels(&channels);
+
+        assert!(pf.covers_all(&channels));
+
+        let others = [
+            (transfer.clone(), channel_2.clone()),
+            (icahost.clone(), channel_0.clone()),
+            (icahost, channel_1),
+            (transfer, ChannelId::from_str("channel-3").unwrap()),
+        ];
+
+        for (port_id, channel_id) in &others {
+            assert!(!pf.is_allowed(port_id, channel_id));
+        }
+
+        assert!(!PacketFilter::from_chain_channels(&[]).is_allowed(&others[0].0, &channel_2));
+    }
+
+    #[test]
+    fn channel_filters_advanced_rules() {
+        let filters = ChannelFilters::from_rules(vec![
+            ChannelFilterRule::new(
+                FilterPattern::Wildcard("ica*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+            ChannelFilterRule::new(
+                FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
+                FilterPattern::Wildcard("channel-[0-9]*".parse().unwrap()),
+            ),
+            ChannelFilterRule::new(
+                FilterPattern::Wildcard("ica(?:.*)".parse().unwrap()),
+                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            ),
+            ChannelFilterRule::new(
+                FilterPattern::Wildcard("transfer*|ica*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+        ]);
+
+        assert_eq!(filters.advanced_rules(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn wildcard_is_plain_glob() {
+        let is_plain_glob = |pattern: &str| pattern.parse::<Wildcard>().unwrap().is_plain_glob();
+
+        // Plain globs
+        assert!(is_plain_glob("ica*"));
+        assert!(is_plain_glob("*"));
+        assert!(is_plain_glob("channel-*"));
+        assert!(is_plain_glob("wasm.abc*"));
+
+        // Character classes
+        assert!(!is_plain_glob("channel-[0-9]*"));
+        assert!(!is_plain_glob("channel-]"));
+
+        // Repetitions
+        assert!(!is_plain_glob("channel-{1,2}"));
+        assert!(!is_plain_glob("ica?"));
+        assert!(!is_plain_glob("ica+"));
+
+        // Groups
+        assert!(!is_plain_glob("ica(?:.*)"));
+        assert!(!is_plain_glob("(ica)*"));
+
+        // Alternations
+        assert!(!is_plain_glob("transfer*|ica*"));
+        assert!(
+            !Wildcard::any_of(vec!["transfer*".parse().unwrap(), "ica*".parse().unwrap()])
+                .is_plain_glob()
+        );
+
+        // Escapes and anchors
+        assert!(!is_plain_glob("ica\\d"));
+        assert!(!is_plain_glob("^ica"));
+        assert!(!is_plain_glob("ica$"));
+    }
+
+    #[test]
+    fn packet_filter_filter_stream() {
+        let pf = PacketFilter::from_cli_arg("deny:transfer/channel-1,ica*/*").unwrap();
+
+        let stream = || {
+            ["transfer", "icahost", "ft-transfer"]
+                .into_iter()
+                .cartesian_product(0..100u64)
+                .map(|(port, i)| (PortId::from_str(port).unwrap(), ChannelId::new(i)))
+        };
+
+        let batch = stream()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .filter(|(port_id, channel_id)| pf.is_allowed(port_id, channel_id))
+            .collect::<Vec<_>>();
+
+        let lazy = pf.filter_stream(stream()).collect::<Vec<_>>();
+
+        assert_eq!(lazy, batch);
+        assert_eq!(lazy.len(), 199);
+    }
+
+    #[test]
+    fn packet_filter_from_cli_arg() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_5 = ChannelId::from_str("channel-5").unwrap();
+        let channel_9 = ChannelId::from_str("channel-9").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+
+        let pf = PacketFilter::from_cli_arg("deny:transfer/channel-5,transfer/channel-9").unwrap();
+
+        match &pf {
+            PacketFilter::Deny(filters) => assert_eq!(filters.len(), 2),
+            _ => panic!("expected deny filter, got {pf:?}"),
+        }
+
+        assert!(!pf.is_allowed(&transfer, &channel_5));
+        assert!(!pf.is_allowed(&transfer, &channel_9));
+        assert!(pf.is_allowed(&transfer, &channel_1));
+
+        let pf = PacketFilter::from_cli_arg("allow:transfer/channel-5,ica*/*").unwrap();
+
+        match &pf {
+            PacketFilter::Allow(filters) => assert_eq!(filters.len(), 2),
+            _ => panic!("expected allow filter, got {pf:?}"),
+        }
+
+        assert!(pf.is_allowed(&transfer, &channel_5));
+        assert!(!pf.is_allowed(&transfer, &channel_9));
+        assert!(pf.is_allowed(&PortId::from_str("icahost").unwrap(), &channel_9));
 
-        dbg!(filter_policy);
+        assert!(PacketFilter::from_cli_arg("block:transfer/channel-5").is_err());
+        assert!(PacketFilter::from_cli_arg("transfer/channel-5").is_err());
+        assert!(PacketFilter::from_cli_arg("allow:transfer-channel-5").is_err());
     }
 
     #[test]
-    fn serialize_packet_filter_policy() {
-        use std::str::FromStr;
-
-        use ibc_relayer_types::core::ics24_host::identifier::{ChannelId, PortId};
+    fn channel_filters_best_match() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
 
-        let filter_policy = ChannelFilters(vec![
+        let filters = ChannelFilters::new(vec![
             (
-                FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
-                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
             ),
             (
-                FilterPattern::Wildcard("ica*".parse().unwrap()),
-                FilterPattern::Wildcard("*".parse().unwrap()),
+                FilterPattern::Wildcard("trans*".parse().unwrap()),
+                FilterPattern::Wildcard("channel-*".parse().unwrap()),
+            ),
+            (
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channel_0.clone()),
+            ),
+            (
+                FilterPattern::Wildcard("tr*".parse().unwrap()),
+                FilterPattern::Exact(channel_0.clone()),
             ),
         ]);
 
-        let fp = PacketFilter::Allow(filter_policy);
-        let toml_str = toml::to_string_pretty(&fp).expect("could not serialize packet filter");
+        let best = filters.best_match(&transfer, &channel_0).unwrap();
+        assert_eq!(best.port, FilterPattern::Exact(transfer.clone()));
+        assert_eq!(best.channel, FilterPattern::Exact(channel_0));
 
-        println!("{}", toml_str);
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+        let best = filters.best_match(&transfer, &channel_1).unwrap();
+        assert_eq!(best.port.to_string(), "trans*");
+
+        let ica = PortId::from_str("icahost").unwrap();
+        let best = filters.best_match(&ica, &channel_1).unwrap();
+        assert_eq!(best.port.to_string(), "*");
+
+        assert!(ChannelFilters::default()
+            .best_match(&transfer, &channel_1)
+            .is_none());
     }
 
     #[test]
-    fn channel_filter_iter_exact() {
+    fn packet_filter_exact_only() {
         let toml_content = r#"
             policy = 'deny'
             list = [
@@ -392,115 +4257,423 @@ mod tests {
 
         let pf: PacketFilter = toml::from_str(toml_content).expect("could not parse filter policy");
 
-        if let PacketFilter::Deny(channel_filters) = pf {
-            let exact_matches = channel_filters.iter_exact().collect::<Vec<_>>();
+        let exact = pf.exact_only();
+
+        if let PacketFilter::Deny(channel_filters) = &exact {
+            assert_eq!(channel_filters.len(), 2);
+            assert!(channel_filters.is_exact());
             assert_eq!(
-                exact_matches,
-                vec![
-                    (
-                        &PortId::from_str("transfer").unwrap(),
-                        &ChannelId::from_str("channel-0").unwrap()
-                    ),
-                    (
-                        &PortId::from_str("ft-transfer").unwrap(),
-                        &ChannelId::from_str("channel-2").unwrap()
-                    )
-                ]
+                channel_filters.to_string(),
+                "transfer/channel-0, ft-transfer/channel-2"
             );
         } else {
             panic!("expected `PacketFilter::Deny` variant");
         }
+
+        assert!(matches!(
+            PacketFilter::AllowAll.exact_only(),
+            PacketFilter::AllowAll
+        ));
     }
 
     #[test]
-    fn packet_filter_deny_policy() {
-        let deny_policy = r#"
+    fn packet_filter_min_height() {
+        let toml_content = r#"
             policy = 'deny'
             list = [
-              ['ica', 'channel-*'],
-              ['ica*', '*'],
-              ['transfer', 'channel-0'],
-              ['transfer*', 'channel-1'],
-              ['ft-transfer', 'channel-2'],
+              { port = 'transfer', channel = 'channel-0', min_height = 1000 },
+              ['transfer', 'channel-1'],
             ]
             "#;
 
-        let pf: PacketFilter = toml::from_str(deny_policy).expect("could not parse filter policy");
+        let pf: PacketFilter = toml::from_str(toml_content).expect("could not parse filter policy");
 
-        assert!(!pf.is_allowed(
-            &PortId::from_str("ft-transfer").unwrap(),
-            &ChannelId::from_str("channel-2").unwrap()
-        ));
-        assert!(pf.is_allowed(
-            &PortId::from_str("ft-transfer").unwrap(),
-            &ChannelId::from_str("channel-1").unwrap()
-        ));
-        assert!(pf.is_allowed(
-            &PortId::from_str("transfer").unwrap(),
-            &ChannelId::from_str("channel-2").unwrap()
-        ));
-        assert!(!pf.is_allowed(
-            &PortId::from_str("ica-1").unwrap(),
-            &ChannelId::from_str("channel-2").unwrap()
-        ));
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+
+        let before = Height::new(0, 999).unwrap();
+        let at = Height::new(0, 1000).unwrap();
+        let after = Height::new(0, 5000).unwrap();
+
+        assert!(pf.is_allowed_at_height(&transfer, &channel_0, before));
+        assert!(!pf.is_allowed_at_height(&transfer, &channel_0, at));
+        assert!(!pf.is_allowed_at_height(&transfer, &channel_0, after));
+
+        assert!(!pf.is_allowed_at_height(&transfer, &channel_1, before));
+        assert!(!pf.is_allowed_at_height(&transfer, &channel_1, after));
+
+        assert!(pf.is_allowed(&transfer, &channel_0));
+        assert!(!pf.is_allowed(&transfer, &channel_1));
+
+        // An allow rule with a minimum height allows the channel, whose
+        // packets are then only relayed from that height on.
+        let pf = PacketFilter::Allow(ChannelFilters::from_rules(vec![ChannelFilterRule::new(
+            FilterPattern::Exact(transfer.clone()),
+            FilterPattern::Exact(channel_0.clone()),
+        )
+        .with_min_height(1000)]));
+
+        assert!(pf.is_allowed(&transfer, &channel_0));
+        assert!(!pf.is_allowed_at_height(&transfer, &channel_0, before));
+        assert!(pf.is_allowed_at_height(&transfer, &channel_0, at));
+        assert!(!pf.is_allowed(&transfer, &channel_1));
     }
 
     #[test]
-    fn packet_filter_allow_policy() {
-        let allow_policy = r#"
-            policy = 'allow'
-            list = [
-              ['ica', 'channel-*'],
-              ['ica*', '*'],
-              ['transfer', 'channel-0'],
-              ['transfer*', 'channel-1'],
-              ['ft-transfer', 'channel-2'],
-            ]
+    fn packet_filter_max_data_bytes() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+
+        let pf = PacketFilter::Deny(ChannelFilters::from_rules(vec![ChannelFilterRule::new(
+            FilterPattern::Exact(transfer.clone()),
+            FilterPattern::Exact(channel_0.clone()),
+        )
+        .with_max_data_bytes(16)]));
+
+        let small = [0u8; 16];
+        let large = [0u8; 17];
+
+        assert!(pf.is_allowed(&transfer, &channel_0));
+        assert!(pf.is_allowed_with_data(&transfer, &channel_0, &small));
+        assert!(!pf.is_allowed_with_data(&transfer, &channel_0, &large));
+        assert!(pf.is_allowed_with_data(&transfer, &channel_1, &large));
+
+        let pf = PacketFilter::Allow(ChannelFilters::from_rules(vec![
+            ChannelFilterRule::new(
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channel_0.clone()),
+            )
+            .with_max_data_bytes(16),
+            ChannelFilterRule::new(
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channel_1.clone()),
+            ),
+        ]));
+
+        assert!(pf.is_allowed_with_data(&transfer, &channel_0, &small));
+        assert!(!pf.is_allowed_with_data(&transfer, &channel_0, &large));
+        assert!(pf.is_allowed_with_data(&transfer, &channel_1, &large));
+
+        // A deny rule without a limit denies packets of any size.
+        let pf = PacketFilter::Deny(ChannelFilters::from_rules(vec![ChannelFilterRule::new(
+            FilterPattern::Exact(transfer.clone()),
+            FilterPattern::Exact(channel_0.clone()),
+        )]));
+
+        assert!(!pf.is_allowed_with_data(&transfer, &channel_0, &small));
+        assert!(pf.is_allowed_with_data(&transfer, &channel_1, &large));
+
+        let pf = PacketFilter::Rules(vec![
+            FilterRule::new(
+                FilterAction::Deny,
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channel_0.clone()),
+            )
+            .with_max_data_bytes(16),
+            FilterRule::new(
+                FilterAction::Allow,
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+        ]);
+
+        assert!(pf.is_allowed(&transfer, &channel_0));
+        assert!(pf.is_allowed_with_data(&transfer, &channel_0, &small));
+        assert!(!pf.is_allowed_with_data(&transfer, &channel_0, &large));
+        assert!(pf.is_allowed_with_data(&transfer, &channel_1, &large));
+    }
+
+    #[test]
+    fn packet_filter_packet_allowed() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+
+        let before = Height::new(0, 999).unwrap();
+        let at = Height::new(0, 1000).unwrap();
+
+        let small = [0u8; 16];
+        let large = [0u8; 17];
+
+        let pf = PacketFilter::Allow(ChannelFilters::from_rules(vec![
+            ChannelFilterRule::new(
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channel_0.clone()),
+            )
+            .with_min_height(1000),
+            ChannelFilterRule::new(
+                FilterPattern::Exact(transfer.clone()),
+                FilterPattern::Exact(channel_0.clone()),
+            )
+            .with_max_data_bytes(16),
+        ]));
+
+        assert!(pf.is_allowed(&transfer, &channel_0));
+        assert!(pf.is_packet_allowed(&transfer, &channel_0, before, &small));
+        assert!(!pf.is_packet_allowed(&transfer, &channel_0, before, &large));
+        assert!(pf.is_packet_allowed(&transfer, &channel_0, at, &large));
+
+        let pf = PacketFilter::Deny(ChannelFilters::from_rules(vec![ChannelFilterRule::new(
+            FilterPattern::Exact(transfer.clone()),
+            FilterPattern::Exact(channel_0.clone()),
+        )
+        .with_min_height(1000)
+        .with_max_data_bytes(16)]));
+
+        assert!(pf.is_allowed(&transfer, &channel_0));
+        assert!(pf.is_packet_allowed(&transfer, &channel_0, before, &large));
+        assert!(pf.is_packet_allowed(&transfer, &channel_0, at, &small));
+        assert!(!pf.is_packet_allowed(&transfer, &channel_0, at, &large));
+    }
+
+    #[test]
+    fn packet_filter_allowing_unassigned_channel() {
+        let transfer = PortId::from_str("transfer").unwrap();
+        let channel_0 = ChannelId::from_str("channel-0").unwrap();
+        let channel_1 = ChannelId::from_str("channel-1").unwrap();
+
+        let pf = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Exact(transfer.clone()),
+            FilterPattern::Wildcard("*".parse().unwrap()),
+        )]));
+
+        assert!(pf.is_allowed_or_unassigned(&transfer, None));
+        assert!(!pf.is_allowed_or_unassigned(&transfer, Some(&channel_0)));
+        assert!(!pf.is_allowed_or_unassigned(&transfer, Some(&channel_1)));
+    }
+
+    #[test]
+    fn client_filters_wildcard() {
+        #[derive(Deserialize)]
+        struct Section {
+            clients: ClientFilters,
+        }
+
+        let section: Section = toml::from_str("clients = ['07-tendermint-*']")
+            .expect("could not parse client filters");
+        let filters = section.clients;
+
+        assert_eq!(filters.len(), 1);
+        assert!(filters.is_client_allowed(&ClientId::from_str("07-tendermint-0").unwrap()));
+        assert!(filters.is_client_allowed(&ClientId::from_str("07-tendermint-12").unwrap()));
+        assert!(!filters.is_client_allowed(&ClientId::from_str("06-solomachine-0").unwrap()));
+    }
+
+    #[test]
+    fn client_filters_exact() {
+        #[derive(Deserialize)]
+        struct Section {
+            clients: ClientFilters,
+        }
+
+        let section: Section = toml::from_str("clients = ['07-tendermint-3']")
+            .expect("could not parse client filters");
+        let filters = section.clients;
+
+        assert!(matches!(filters.0[0], FilterPattern::Exact(_)));
+        assert!(filters.is_client_allowed(&ClientId::from_str("07-tendermint-3").unwrap()));
+        assert!(!filters.is_client_allowed(&ClientId::from_str("07-tendermint-30").unwrap()));
+        assert!(!filters.is_client_allowed(&ClientId::from_str("07-tendermint-4").unwrap()));
+
+        assert!(ClientFilters::default()
+            .is_client_allowed(&ClientId::from_str("07-tendermint-4").unwrap()));
+    }
+
+    #[test]
+    fn wildcard_matching_is_anchored() {
+        let exact = "transfer".parse::<Wildcard>().unwrap();
+        assert!(exact.is_match("transfer"));
+        assert!(!exact.is_match("transfer-extra"));
+        assert!(!exact.is_match("xtransfer"));
+        assert!(!exact.is_match(""));
+
+        let prefix = "ica*".parse::<Wildcard>().unwrap();
+        assert!(prefix.is_match("icahost"));
+        assert!(prefix.is_match("ica"));
+        assert!(!prefix.is_match("xicahost"));
+
+        let suffix = "*host".parse::<Wildcard>().unwrap();
+        assert!(suffix.is_match("icahost"));
+        assert!(!suffix.is_match("icahostx"));
+
+        let middle = "ica*host".parse::<Wildcard>().unwrap();
+        assert!(middle.is_match("icahost"));
+        assert!(middle.is_match("ica-controller-host"));
+        assert!(!middle.is_match("xica-host"));
+        assert!(!middle.is_match("ica-hostx"));
+
+        let any = "*".parse::<Wildcard>().unwrap();
+        assert!(any.is_match(""));
+        assert!(any.is_match("transfer"));
+
+        assert_eq!(Wildcard::any(), any);
+        assert_eq!(Wildcard::any().regex.as_str(), any.regex.as_str());
+        assert_eq!(Wildcard::any().simple, any.simple);
+        assert!(Wildcard::any().is_match(""));
+    }
+
+    #[test]
+    fn denom_filter_deny_ibc_denoms() {
+        let deny_policy = r#"
+            policy = 'deny'
+            denoms = ['ibc/*', 'transfer/channel-1/*']
             "#;
 
-        let pf: PacketFilter = toml::from_str(allow_policy).expect("could not parse filter policy");
+        let df: DenomFilter = toml::from_str(deny_policy).expect("could not parse denom filter");
 
-        assert!(pf.is_allowed(
-            &PortId::from_str("ft-transfer").unwrap(),
-            &ChannelId::from_str("channel-2").unwrap()
-        ));
-        assert!(!pf.is_allowed(
-            &PortId::from_str("ft-transfer").unwrap(),
-            &ChannelId::from_str("channel-1").unwrap()
-        ));
-        assert!(!pf.is_allowed(
-            &PortId::from_str("transfer-1").unwrap(),
-            &ChannelId::from_str("channel-2").unwrap()
-        ));
-        assert!(pf.is_allowed(
-            &PortId::from_str("ica-1").unwrap(),
-            &ChannelId::from_str("channel-2").unwrap()
-        ));
-        assert!(pf.is_allowed(
-            &PortId::from_str("ica").unwrap(),
-            &ChannelId::from_str("channel-1").unwrap()
+        assert!(!df.is_denom_allowed(
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
         ));
+        assert!(!df.is_denom_allowed("transfer/channel-1/uatom"));
+        assert!(df.is_denom_allowed("transfer/channel-0/uatom"));
+        assert!(df.is_denom_allowed("uatom"));
     }
 
     #[test]
-    fn packet_filter_regex() {
+    fn denom_filter_allow_base_denoms() {
         let allow_policy = r#"
             policy = 'allow'
-            list = [
-              ['transfer*', 'channel-1'],
-            ]
+            denoms = ['uatom', 'stake*']
             "#;
 
-        let pf: PacketFilter = toml::from_str(allow_policy).expect("could not parse filter policy");
+        let df: DenomFilter = toml::from_str(allow_policy).expect("could not parse denom filter");
 
-        assert!(!pf.is_allowed(
-            &PortId::from_str("ft-transfer").unwrap(),
-            &ChannelId::from_str("channel-1").unwrap()
-        ));
-        assert!(!pf.is_allowed(
-            &PortId::from_str("ft-transfer-port").unwrap(),
-            &ChannelId::from_str("channel-1").unwrap()
+        assert!(matches!(&df, DenomFilter::Allow(denoms) if denoms[0].is_exact()));
+        assert!(df.is_denom_allowed("uatom"));
+        assert!(df.is_denom_allowed("stake"));
+        assert!(df.is_denom_allowed("stake2"));
+        assert!(!df.is_denom_allowed("uatom2"));
+        assert!(!df.is_denom_allowed("ibc/uatom"));
+
+        assert!(DenomFilter::default().is_denom_allowed("ibc/ABCD"));
+    }
+
+    #[test]
+    fn channel_filters_coalesce_wildcards() {
+        let filters = ChannelFilters::new(vec![
+            (
+                FilterPattern::Wildcard("ica*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+            (
+                FilterPattern::Exact(PortId::from_str("oracle").unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+            (
+                FilterPattern::Wildcard("transfer*".parse().unwrap()),
+                FilterPattern::Wildcard("*".parse().unwrap()),
+            ),
+            (
+                FilterPattern::Wildcard("wasm*".parse().unwrap()),
+                FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
+            ),
+        ]);
+
+        let coalesced = filters.clone().coalesce_wildcards();
+
+        assert_eq!(coalesced.len(), 3);
+        assert_eq!(coalesced.0[0].port.to_string(), "ica*|transfer*");
+        assert!(coalesced.0[1].port.is_exact());
+        assert_eq!(coalesced.0[2].port.to_string(), "wasm*");
+
+        let ports = [
+            "icahost",
+            "transfer",
+            "transfer-2",
+            "oracle",
+            "wasm.abc",
+            "other",
+        ];
+        let channels = ["channel-0", "channel-1"];
+
+        for port in ports {
+            for channel in channels {
+                let port_id = PortId::from_str(port).unwrap();
+                let channel_id = ChannelId::from_str(channel).unwrap();
+
+                assert_eq!(
+                    coalesced.matches((&port_id, &channel_id)),
+                    filters.matches((&port_id, &channel_id)),
+                    "{port}/{channel}"
+                );
+            }
+        }
+
+        // The merged rule is written back as one rule per alternative
+        let toml = toml::to_string(&PacketFilter::Allow(coalesced)).unwrap();
+        let parsed = match toml::from_str::<PacketFilter>(&toml).unwrap() {
+            PacketFilter::Allow(parsed) => parsed,
+            policy => panic!("unexpected policy: {policy:?}"),
+        };
+
+        assert_eq!(parsed.len(), 4);
+
+        for port in ports {
+            for channel in channels {
+                let port_id = PortId::from_str(port).unwrap();
+                let channel_id = ChannelId::from_str(channel).unwrap();
+
+                assert_eq!(
+                    parsed.matches((&port_id, &channel_id)),
+                    filters.matches((&port_id, &channel_id)),
+                    "{port}/{channel}"
+                );
+            }
+        }
+
+        let any_of = |patterns: &[&str]| {
+            Wildcard::any_of(patterns.iter().map(|p| p.parse().unwrap()).collect())
+        };
+
+        assert_eq!(any_of(&["ica*", "transfer*"]).literal_prefix(), "");
+        assert_eq!(any_of(&["icahost", "ica*"]).literal_prefix(), "ica");
+    }
+
+    #[test]
+    fn wildcard_pipe_is_literal() {
+        let wildcard = "ica*|transfer*".parse::<Wildcard>().unwrap();
+
+        assert!(wildcard.is_match("ica-a|transfer-b"));
+        assert!(!wildcard.is_match("icahost"));
+        assert!(!wildcard.is_match("transfer"));
+
+        let alternation =
+            Wildcard::any_of(vec!["ica*".parse().unwrap(), "transfer*".parse().unwrap()]);
+
+        assert_eq!(alternation.to_string(), wildcard.to_string());
+        assert_ne!(alternation, wildcard);
+        assert!(alternation.is_match("icahost"));
+        assert!(alternation.is_match("transfer"));
+        assert!(!alternation.is_match("ica-a|transfer-b"));
+    }
+
+    #[test]
+    fn deserialize_wildcard_identifier_characters() {
+        let valid = "policy = 'allow'\nlist = [['ica*', 'channel-*']]\n";
+        let pf: PacketFilter = toml::from_str(valid).expect("could not parse filter policy");
+
+        assert!(pf.is_allowed(
+            &PortId::from_str("icahost").unwrap(),
+            &ChannelId::from_str("channel-3").unwrap()
         ));
+
+        for invalid in [
+            "bad port name",
+            "transfer!",
+            "ica*$",
+            "transfér*",
+            "ica*|transfer*",
+        ] {
+            let policy = format!("policy = 'allow'\nlist = [['{invalid}', '*']]\n");
+            let err = toml::from_str::<PacketFilter>(&policy)
+                .expect_err("invalid wildcard should fail to parse");
+
+            assert!(err.to_string().contains("invalid character"), "{err}");
+        }
+
+        let invalid_channel = "policy = 'deny'\nlist = [['transfer', 'channel-0;*']]\n";
+        assert!(toml::from_str::<PacketFilter>(invalid_channel).is_err());
     }
 
     #[test]
@@ -508,4 +4681,118 @@ mod tests {
         let wildcard = "ica*".parse::<Wildcard>().unwrap();
         assert_eq!(wildcard.to_string(), "ica*".to_string());
     }
+
+    #[test]
+    fn to_string_multiple_wildcards() {
+        let wildcard = "a*b*c".parse::<Wildcard>().unwrap();
+        assert_eq!(wildcard.to_string(), "a*b*c");
+        assert!(wildcard.is_match("abc"));
+        assert!(wildcard.is_match("a-b-c"));
+        assert!(!wildcard.is_match("a-c"));
+
+        let reparsed = wildcard.to_string().parse::<Wildcard>().unwrap();
+        assert_eq!(reparsed, wildcard);
+        assert_eq!(reparsed.to_string(), "a*b*c");
+    }
+
+    #[test]
+    fn to_string_wildcards_with_metacharacters() {
+        let wildcard = "ica.host+*".parse::<Wildcard>().unwrap();
+        assert_eq!(wildcard.to_string(), "ica.host+*");
+
+        let reparsed = wildcard.to_string().parse::<Wildcard>().unwrap();
+        for wildcard in [&wildcard, &reparsed] {
+            assert!(wildcard.is_match("ica.host+"));
+            assert!(wildcard.is_match("ica.host+-0"));
+            assert!(!wildcard.is_match("icaXhost+"));
+            assert!(!wildcard.is_match("ica.hosttt"));
+        }
+    }
+
+    #[test]
+    fn canonical_source_wildcards() {
+        let glob = "ica*".parse::<Wildcard>().unwrap();
+        let regexish = "ica(?:.*)".parse::<Wildcard>().unwrap();
+        let doubled = "ica**".parse::<Wildcard>().unwrap();
+
+        assert_eq!(glob.canonical_source(), "ica*");
+        assert_eq!(regexish.canonical_source(), glob.canonical_source());
+        assert_eq!(doubled.canonical_source(), glob.canonical_source());
+        assert_eq!(glob, regexish);
+
+        // Only the comparison is canonical, the regex-style group is matched literally
+        assert!(!regexish.is_match("icahost"));
+        assert!(regexish.is_match("ica(?:.host)"));
+        assert!(doubled.is_match("icahost"));
+
+        let other = "transfer*".parse::<Wildcard>().unwrap();
+        assert_ne!(glob, other);
+    }
+
+    #[test]
+    fn cached_packet_filter_reload() {
+        let transfer = PortId::transfer();
+        let channel_0 = ChannelId::new(0);
+        let channel_1 = ChannelId::new(1);
+
+        let deny_channel_0 = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Exact(transfer.clone()),
+            FilterPattern::Wildcard("channel-0".parse().unwrap()),
+        )]));
+
+        let mut cached = CachedPacketFilter::from(deny_channel_0);
+
+        for _ in 0..2 {
+            assert!(!cached.is_allowed(&transfer, &channel_0));
+            assert!(cached.is_allowed(&transfer, &channel_1));
+        }
+
+        let deny_channel_1 = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Exact(transfer.clone()),
+            FilterPattern::Wildcard("channel-1".parse().unwrap()),
+        )]));
+
+        let previous = cached.clone();
+        cached.reload(deny_channel_1);
+
+        assert!(cached.is_allowed(&transfer, &channel_0));
+        assert!(!cached.is_allowed(&transfer, &channel_1));
+
+        assert!(!previous.is_allowed(&transfer, &channel_0));
+    }
+
+    /// Checks that the results of a 500-entry deny list of wildcards are
+    /// the same with and without caching, including on repeated lookups.
+    #[test]
+    fn large_cached_packet_filter_matches_uncached() {
+        let filter = PacketFilter::Deny(ChannelFilters::new(
+            (0..500)
+                .map(|i| {
+                    (
+                        FilterPattern::Wildcard(format!("port-{i}-*-*").parse().unwrap()),
+                        FilterPattern::Wildcard(format!("*-{i}").parse().unwrap()),
+                    )
+                })
+                .collect(),
+        ));
+
+        let cached = CachedPacketFilter::from(filter.clone());
+
+        let cases = [
+            ("transfer", "channel-7", true),
+            ("port-7-ica-host", "channel-7", false),
+            ("port-7-ica-host", "channel-8", true),
+            ("port-499-a-b", "channel-499", false),
+        ];
+
+        for _ in 0..2 {
+            for (port, channel, expected) in cases {
+                let port_id = PortId::from_str(port).unwrap();
+                let channel_id = ChannelId::from_str(channel).unwrap();
+
+                assert_eq!(filter.is_allowed(&port_id, &channel_id), expected);
+                assert_eq!(cached.is_allowed(&port_id, &channel_id), expected);
+            }
+        }
+    }
 }
@@ -1,6 +1,7 @@
 use alloc::collections::BTreeMap as HashMap;
 use alloc::collections::VecDeque;
 use std::ops::Sub;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
 use ibc_proto::google::protobuf::Any;
@@ -25,6 +26,7 @@ use crate::chain::tracking::TrackedMsgs;
 use crate::chain::tracking::TrackingId;
 use crate::channel::error::ChannelError;
 use crate::channel::Channel;
+use crate::config::{ChainConfig, Config};
 use crate::event::monitor::EventBatch;
 use crate::event::IbcEventWithHeight;
 use crate::foreign_client::{ForeignClient, ForeignClientError};
@@ -41,9 +43,11 @@ use crate::link::relay_summary::RelaySummary;
 use crate::link::{pending, relay_sender};
 use crate::path::PathIdentifiers;
 use crate::telemetry;
+use crate::util::lock::LockExt;
 use crate::util::pretty::PrettyEvents;
 use crate::util::queue::Queue;
 use ibc_relayer_types::{
+    applications::transfer::packet::PacketData as TransferPacketData,
     core::{
         ics02_client::events::ClientMisbehaviour as ClientMisbehaviourEvent,
         ics04_channel::{
@@ -111,6 +115,12 @@ pub struct RelayPath<ChainA: ChainHandle, ChainB: ChainHandle> {
     // transactions if [`confirm_txes`] is true.
     pending_txs_src: PendingTxs<ChainA>,
     pending_txs_dst: PendingTxs<ChainB>,
+
+    // The config shared with the supervisor, from which the filters of the
+    // source chain are checked against each packet to relay, if set.
+    // Only the packet workers set it, so that the packets cleared from
+    // the CLI are relayed regardless of the filters.
+    packet_filter_config: Option<Arc<RwLock<Config>>>,
 }
 
 impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
@@ -155,9 +165,18 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             confirm_txes: with_tx_confirmation,
             pending_txs_src: PendingTxs::new(src_chain, src_channel_id, src_port_id, dst_chain_id),
             pending_txs_dst: PendingTxs::new(dst_chain, dst_channel_id, dst_port_id, src_chain_id),
+
+            packet_filter_config: None,
         })
     }
 
+    /// Checks each packet to relay against the packet and denom filters of
+    /// the source chain in the given config, which may be updated at runtime,
+    /// skipping the packets they do not allow.
+    pub fn set_packet_filter_config(&mut self, config: Arc<RwLock<Config>>) {
+        self.packet_filter_config = Some(config);
+    }
+
     pub fn src_chain(&self) -> &ChainA {
         self.channel.src_chain()
     }
@@ -514,6 +533,16 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
         let dst_latest_height = dst_latest_info.height;
 
+        // The supervisor only checks the packet filter per channel, so the
+        // rules of the filters that only apply to some of the packets of a
+        // channel are checked here, against the filters of the source chain.
+        let src_config = self.packet_filter_config.as_ref().and_then(|config| {
+            config
+                .acquire_read()
+                .find_chain(&self.src_chain().id())
+                .cloned()
+        });
+
         // Operational data targeting the source chain (e.g., Timeout packets)
         let mut src_od = OperationalData::new(
             dst_latest_height,
@@ -566,6 +595,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                         self.build_recv_or_timeout_from_send_packet_event(
                             event,
                             &dst_latest_info,
+                            src_config.as_ref(),
                             event_with_height.height,
                         )?
                     }
@@ -1330,18 +1360,35 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         }
     }
 
+    /// Builds the message relaying the packet sent in the given event, either
+    /// a timeout if the packet has timed out, or otherwise the receive message,
+    /// unless the packet is not allowed by the packet or denom filter of the
+    /// given config of the source chain, in which case no message is built.
     fn build_recv_or_timeout_from_send_packet_event(
         &self,
         event: &SendPacket,
         dst_info: &ChainStatus,
+        src_config: Option<&ChainConfig>,
         height: Height,
     ) -> Result<(Option<Any>, Option<Any>), LinkError> {
         let timeout = self.build_timeout_from_send_packet_event(event, dst_info)?;
         if timeout.is_some() {
-            Ok((None, timeout))
-        } else {
-            Ok((self.build_recv_packet(&event.packet, height)?, None))
+            return Ok((None, timeout));
+        }
+
+        if let Some(reason) =
+            src_config.and_then(|config| packet_filtered_reason(config, &event.packet, height))
+        {
+            info!(
+                sequence = %event.packet.sequence,
+                "not relaying packet, as it is not allowed by {}",
+                reason
+            );
+
+            return Ok((None, None));
         }
+
+        Ok((self.build_recv_packet(&event.packet, height)?, None))
     }
 
     /// Drives the relaying of elapsed operational data items meant for
@@ -1841,3 +1888,39 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         }
     }
 }
+
+/// Returns why the given packet, sent at `height` on the source chain, may not
+/// be relayed according to the `min_height` and `max_data_bytes` of the rules
+/// of the packet filter of the source chain, and, for ICS20 transfers,
+/// according to its denom filter, or `None` if it may be relayed.
+fn packet_filtered_reason(
+    src_config: &ChainConfig,
+    packet: &Packet,
+    height: Height,
+) -> Option<String> {
+    if !src_config.packet_filter.is_packet_allowed(
+        &packet.source_port,
+        &packet.source_channel,
+        height,
+        &packet.data,
+    ) {
+        return Some(format!(
+            "the packet filter of the source chain at height {} for {} bytes of data",
+            height,
+            packet.data.len()
+        ));
+    }
+
+    // Packets whose data is not that of an ICS20 transfer are not subject to the denom filter
+    let data = serde_json::from_slice::<TransferPacketData>(&packet.data).ok()?;
+    let denom = data.token.denom.to_string();
+
+    if !src_config.denom_filter.is_denom_allowed(&denom) {
+        return Some(format!(
+            "the denom filter of the source chain for denom {}",
+            denom
+        ));
+    }
+
+    None
+}
@@ -17,7 +17,7 @@ use ibc_relayer_types::{
 
 use crate::{
     chain::{endpoint::HealthCheck, handle::ChainHandle, tracking::TrackingId},
-    config::Config,
+    config::{Config, PacketFilter},
     event::{
         monitor::{self, Error as EventError, ErrorDetail as EventErrorDetail, EventBatch},
         IbcEventWithHeight,
@@ -131,7 +131,13 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
         health_check(&config, &mut registry.write());
     }
 
-    let workers = Arc::new(RwLock::new(WorkerMap::new()));
+    // The config is shared with the command worker, which may update it at
+    // runtime, and with the packet workers, which check the packets against it
+    let shared_config = Arc::new(RwLock::new(config.clone()));
+
+    let workers = Arc::new(RwLock::new(WorkerMap::with_shared_config(
+        shared_config.clone(),
+    )));
     let client_state_filter = Arc::new(RwLock::new(FilterPolicy::default()));
 
     let scan = chain_scanner(
@@ -154,14 +160,14 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
     let subscriptions = init_subscriptions(&config, &mut registry.write())?;
 
     let batch_tasks = spawn_batch_workers(
-        &config,
+        shared_config.clone(),
         registry.clone(),
         client_state_filter,
         workers.clone(),
         subscriptions,
     );
 
-    let cmd_task = spawn_cmd_worker(registry.clone(), workers.clone(), cmd_rx);
+    let cmd_task = spawn_cmd_worker(shared_config, registry.clone(), workers.clone(), cmd_rx);
 
     let mut tasks = vec![cmd_task];
     tasks.extend(batch_tasks);
@@ -175,7 +181,7 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
 }
 
 fn spawn_batch_workers<Chain: ChainHandle>(
-    config: &Config,
+    config: Arc<RwLock<Config>>,
     registry: SharedRegistry<Chain>,
     client_state_filter: Arc<RwLock<FilterPolicy>>,
     workers: Arc<RwLock<WorkerMap>>,
@@ -195,7 +201,7 @@ fn spawn_batch_workers<Chain: ChainHandle>(
             move || -> Result<Next, TaskError<Infallible>> {
                 if let Ok(batch) = subscription.try_recv() {
                     handle_batch(
-                        &config,
+                        &config.acquire_read(),
                         &mut registry.write(),
                         &mut client_state_filter.acquire_write(),
                         &mut workers.acquire_write(),
@@ -215,6 +221,7 @@ fn spawn_batch_workers<Chain: ChainHandle>(
 }
 
 pub fn spawn_cmd_worker<Chain: ChainHandle>(
+    config: Arc<RwLock<Config>>,
     registry: SharedRegistry<Chain>,
     workers: Arc<RwLock<WorkerMap>>,
     cmd_rx: Receiver<SupervisorCmd>,
@@ -228,6 +235,10 @@ pub fn spawn_cmd_worker<Chain: ChainHandle>(
                     SupervisorCmd::DumpState(reply_to) => {
                         dump_state(&registry.read(), &workers.acquire_read(), reply_to);
                     }
+                    SupervisorCmd::SetPacketFilter(chain_id, packet_filter, reply_to) => {
+                        set_packet_filter(&config, &workers, &chain_id, packet_filter);
+                        let _ = reply_to.try_send(());
+                    }
                 }
             }
 
@@ -284,6 +295,43 @@ fn is_channel_allowed(
     config.packets_on_channel_allowed(chain_id, port_id, channel_id)
 }
 
+/// Whether or not the connection hops of the given channel are allowed by the
+/// connection filter of the chain, if any.
+///
+/// The connection hops of the channel are cached by the [`FilterPolicy`], so
+/// that they are only queried once per channel. If the query keeps failing
+/// after a few retries, the channel is denied until the next event on it,
+/// which retries the query.
+fn are_channel_connection_hops_allowed<Chain: ChainHandle>(
+    config: &Config,
+    registry: &mut Registry<Chain>,
+    client_state_filter: &mut FilterPolicy,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> bool {
+    // If the chain does not filter connections, bail fast without querying the channel
+    let filters_connections = config.find_chain(chain_id).map_or(false, |chain_config| {
+        !chain_config.connection_filter.is_empty()
+    });
+
+    if !channel_filter_enabled(config) || !filters_connections {
+        return true;
+    }
+
+    match client_state_filter.channel_connection_hops(registry, chain_id, port_id, channel_id) {
+        Ok(connection_hops) => config.connection_hops_allowed(chain_id, &connection_hops),
+        Err(e) => {
+            error!(
+                "skipping the events on channel {}/{} for now, failed to query its connection hops: {}",
+                port_id, channel_id, e
+            );
+
+            false
+        }
+    }
+}
+
 /// Whether or not the relayer should relay packets
 /// or complete handshakes for the given [`Object`].
 fn relay_on_object<Chain: ChainHandle>(
@@ -298,20 +346,50 @@ fn relay_on_object<Chain: ChainHandle>(
         return true;
     }
 
-    // First, apply the channel filter on packets and channel workers
+    // First, apply the channel and connection filters on packets and channel workers
     match object {
         Object::Packet(p) => {
-            if !is_channel_allowed(config, chain_id, &p.src_port_id, &p.src_channel_id) {
+            if !is_channel_allowed(config, chain_id, &p.src_port_id, &p.src_channel_id)
+                || !are_channel_connection_hops_allowed(
+                    config,
+                    registry,
+                    client_state_filter,
+                    chain_id,
+                    &p.src_port_id,
+                    &p.src_channel_id,
+                )
+            {
                 // Forbid relaying packets on that channel
                 return false;
             }
         }
         Object::Channel(c) => {
-            if !is_channel_allowed(config, chain_id, &c.src_port_id, &c.src_channel_id) {
+            if !is_channel_allowed(config, chain_id, &c.src_port_id, &c.src_channel_id)
+                || !are_channel_connection_hops_allowed(
+                    config,
+                    registry,
+                    client_state_filter,
+                    chain_id,
+                    &c.src_port_id,
+                    &c.src_channel_id,
+                )
+            {
                 // Forbid completing handshake for that channel
                 return false;
             }
         }
+        Object::Connection(c) => {
+            if !config.connection_hops_allowed(chain_id, &[c.src_connection_id.clone()]) {
+                // Forbid completing handshake for that connection
+                return false;
+            }
+        }
+        Object::Client(c) => {
+            if !config.client_allowed(&c.dst_chain_id, &c.dst_client_id) {
+                // Forbid updating a client the relayer does not service
+                return false;
+            }
+        }
         _ => (),
     };
 
@@ -605,6 +683,53 @@ fn dump_state<Chain: ChainHandle>(
     let _ = reply_to.try_send(state);
 }
 
+/// Replaces the packet filter of the chain with the given [`ChainId`] in the
+/// config shared by the supervisor and its packet workers, so that the events
+/// on the channels it no longer allows are dropped from then on and the packet
+/// workers check their packets against it, and stops the workers of these channels.
+fn set_packet_filter(
+    shared_config: &RwLock<Config>,
+    workers: &RwLock<WorkerMap>,
+    chain_id: &ChainId,
+    packet_filter: PacketFilter,
+) {
+    // The write lock on the config is released before locking the workers, which
+    // the batch workers lock while reading the config, and before stopping the
+    // workers, as the packet workers read the config while relaying.
+    match shared_config.acquire_write().find_chain_mut(chain_id) {
+        Some(chain_config) => chain_config.packet_filter = packet_filter,
+        None => {
+            warn!(chain = %chain_id, "cannot set the packet filter of a chain not in the config");
+            return;
+        }
+    }
+
+    let config = shared_config.acquire_read().clone();
+
+    info!(chain = %chain_id, "packet filter updated");
+
+    let mut workers = workers.acquire_write();
+
+    for object in workers.objects_for_chain(chain_id) {
+        let channel = match &object {
+            Object::Packet(p) if &p.src_chain_id == chain_id => (&p.src_port_id, &p.src_channel_id),
+            Object::Channel(c) if &c.src_chain_id == chain_id => {
+                (&c.src_port_id, &c.src_channel_id)
+            }
+            _ => continue,
+        };
+
+        if !is_channel_allowed(&config, chain_id, channel.0, channel.1) {
+            info!(
+                "stopping worker for object {}, which the packet filter no longer allows",
+                object.short_name()
+            );
+
+            workers.shutdown_worker(&object);
+        }
+    }
+}
+
 /// Returns a representation of the supervisor's internal state
 /// as a [`SupervisorState`].
 fn state<Chain: ChainHandle>(registry: &Registry<Chain>, workers: &WorkerMap) -> SupervisorState {
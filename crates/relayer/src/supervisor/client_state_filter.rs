@@ -1,4 +1,5 @@
 use alloc::collections::BTreeMap as HashMap;
+use core::time::Duration;
 
 use flex_error::define_error;
 use tracing::{debug, trace};
@@ -20,6 +21,14 @@ use crate::error::Error as RelayerError;
 use crate::object;
 use crate::registry::Registry;
 use crate::spawn::SpawnError;
+use crate::util::retry::{retry_with_index, Fixed, RetryResult};
+
+/// The number of times a failed query of the connection hops of a channel
+/// is retried before giving up.
+const CONNECTION_HOPS_QUERY_RETRIES: usize = 3;
+
+/// The delay between two queries of the connection hops of a channel.
+const CONNECTION_HOPS_QUERY_DELAY: Duration = Duration::from_millis(200);
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Permission {
@@ -75,9 +84,87 @@ pub struct FilterPolicy {
     /// client id, channel id, or connection id, with an
     /// [`Allow`](Permission::Allow) status.
     permission_cache: HashMap<CacheKey, Permission>,
+
+    /// A cache associating a channel with its connection hops, which
+    /// never change over the lifetime of the channel.
+    connection_hops_cache: HashMap<CacheKey, Vec<ConnectionId>>,
 }
 
 impl FilterPolicy {
+    /// Caches the given connection hops of a channel, eg. as found when
+    /// scanning the chain, so that they need not be queried later on.
+    pub fn cache_connection_hops(
+        &mut self,
+        chain_id: &ChainId,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        connection_hops: &[ConnectionId],
+    ) {
+        let identifier = CacheKey::Channel(chain_id.clone(), port_id.clone(), channel_id.clone());
+
+        self.connection_hops_cache
+            .entry(identifier)
+            .or_insert_with(|| connection_hops.to_vec());
+    }
+
+    /// Returns the connection hops of the given channel, querying and caching
+    /// them on a cache miss.
+    ///
+    /// A failed query is retried a few times. If it still fails, the error is
+    /// returned and nothing is cached, so that the query is retried next time.
+    pub fn channel_connection_hops<Chain: ChainHandle>(
+        &mut self,
+        registry: &mut Registry<Chain>,
+        chain_id: &ChainId,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<Vec<ConnectionId>, FilterError> {
+        let identifier = CacheKey::Channel(chain_id.clone(), port_id.clone(), channel_id.clone());
+
+        if let Some(connection_hops) = self.connection_hops_cache.get(&identifier) {
+            trace!(?connection_hops, "cache hit");
+
+            return Ok(connection_hops.clone());
+        }
+
+        let strategy = Fixed::from(CONNECTION_HOPS_QUERY_DELAY).take(CONNECTION_HOPS_QUERY_RETRIES);
+
+        let connection_hops = retry_with_index(strategy, |_| {
+            let result = registry
+                .get_or_spawn(chain_id)
+                .map_err(FilterError::spawn)
+                .and_then(|chain| {
+                    chain
+                        .query_channel(
+                            QueryChannelRequest {
+                                port_id: port_id.clone(),
+                                channel_id: channel_id.clone(),
+                                height: QueryHeight::Latest,
+                            },
+                            IncludeProof::No,
+                        )
+                        .map_err(FilterError::relayer)
+                });
+
+            match result {
+                Ok((channel_end, _)) => RetryResult::Ok(channel_end.connection_hops),
+                Err(e) => {
+                    debug!(
+                        "failed to query the connection hops of the channel, retrying: {}",
+                        e
+                    );
+                    RetryResult::Retry(e)
+                }
+            }
+        })
+        .map_err(|e| e.error)?;
+
+        self.connection_hops_cache
+            .insert(identifier, connection_hops.clone());
+
+        Ok(connection_hops)
+    }
+
     /// Given a connection end and the underlying client for that
     /// connection, controls both the client as well as the
     /// client on the counterparty chain.
@@ -1,8 +1,15 @@
 use crossbeam_channel::Sender;
 
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+
 use super::dump_state::SupervisorState;
+use crate::config::PacketFilter;
 
 #[derive(Clone, Debug)]
 pub enum SupervisorCmd {
     DumpState(Sender<SupervisorState>),
+    /// Replaces the packet filter of the chain with the given [`ChainId`],
+    /// and stops the workers of the channels it no longer allows, replying
+    /// once the new filter is in effect.
+    SetPacketFilter(ChainId, PacketFilter, Sender<()>),
 }
@@ -348,6 +348,14 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
             let result = scan_allowed_channel(self.registry, chain, port_id, channel_id);
 
             match result {
+                Ok(ScannedChannel { client, .. })
+                    if !self.config.client_allowed(&chain.id(), &client.client_id) =>
+                {
+                    warn!(
+                        channel = %channel_id, client = %client.client_id,
+                        "skipping channel, reason: its client is not in the clients of the chain",
+                    );
+                }
                 Ok(ScannedChannel {
                     channel,
                     counterparty_channel,
@@ -431,6 +439,12 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
 
         info!("scanning client...");
 
+        if !self.config.client_allowed(&chain.id(), &client.client_id) {
+            warn!("skipping client, reason: client is not in the clients of the chain");
+
+            return Ok(None);
+        }
+
         if !self.client_allowed(chain, &client) {
             warn!(
                 trust_threshold = ?client.client_state.trust_threshold(),
@@ -640,8 +654,20 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
     }
 
     fn channel_allowed(&mut self, chain: &Chain, channel: &IdentifiedChannelEnd) -> bool {
+        // Cache the connection hops of the channel, so that the connection filter
+        // can be applied to the events of the channel without querying it again
+        self.client_state_filter.cache_connection_hops(
+            &chain.id(),
+            &channel.port_id,
+            &channel.channel_id,
+            channel.channel_end.connection_hops(),
+        );
+
         self.config
             .packets_on_channel_allowed(&chain.id(), &channel.port_id, &channel.channel_id)
+            && self
+                .config
+                .connection_hops_allowed(&chain.id(), channel.channel_end.connection_hops())
     }
 }
 
@@ -877,7 +903,7 @@ fn init_telemetry(
     let mut tx_worker_enabled = false;
 
     let clear_packets = config.mode.packets.enabled
-        && (config.mode.packets.clear_on_start || config.mode.packets.clear_interval > 0);
+        && (config.clear_on_start_for(chain_id) || config.mode.packets.clear_interval > 0);
 
     if config.mode.packets.enabled {
         tx_worker_enabled = true;
@@ -2,7 +2,7 @@ use alloc::sync::Arc;
 use core::fmt::{Display, Error as FmtError, Formatter};
 use ibc_relayer_types::core::ics04_channel::channel::Order;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::{Mutex, RwLock};
 use tracing::error;
 
 use crate::foreign_client::ForeignClient;
@@ -58,6 +58,7 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
     id: WorkerId,
     object: Object,
     config: &Config,
+    shared_config: Option<Arc<RwLock<Config>>>,
 ) -> WorkerHandle {
     let mut task_handles = Vec::new();
 
@@ -110,6 +111,7 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
         }
         Object::Packet(path) => {
             let packets_config = config.mode.packets;
+            let clear_on_start = config.clear_on_start_for(&chains.a.id());
             let link_res = Link::new_from_opts(
                 chains.a.clone(),
                 chains.b,
@@ -121,10 +123,14 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
             );
 
             match link_res {
-                Ok(link) => {
+                Ok(mut link) => {
+                    if let Some(shared_config) = shared_config {
+                        link.a_to_b.set_packet_filter_config(shared_config);
+                    }
+
                     let channel_ordering = link.a_to_b.channel().ordering;
                     let should_clear_on_start =
-                        packets_config.clear_on_start || channel_ordering == Order::Ordered;
+                        clear_on_start || channel_ordering == Order::Ordered;
 
                     let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
                     let link = Arc::new(Mutex::new(link));
@@ -1,5 +1,6 @@
 use alloc::collections::btree_map::BTreeMap as HashMap;
 use core::mem;
+use std::sync::{Arc, RwLock};
 
 use ibc_relayer_types::core::ics02_client::events::NewBlock;
 use ibc_relayer_types::core::ics24_host::identifier::ChainId;
@@ -20,6 +21,7 @@ use super::{spawn_worker_tasks, WorkerHandle, WorkerId};
 pub struct WorkerMap {
     workers: HashMap<Object, WorkerHandle>,
     latest_worker_id: WorkerId,
+    shared_config: Option<Arc<RwLock<Config>>>,
 }
 
 impl Default for WorkerMap {
@@ -27,6 +29,7 @@ impl Default for WorkerMap {
         Self {
             workers: HashMap::new(),
             latest_worker_id: WorkerId::new(0),
+            shared_config: None,
         }
     }
 }
@@ -39,6 +42,16 @@ impl WorkerMap {
         Self::default()
     }
 
+    /// Create a new worker map, whose packet workers check the packets they
+    /// relay against the filters in the given config shared with the
+    /// supervisor, which may be updated at runtime.
+    pub fn with_shared_config(config: Arc<RwLock<Config>>) -> Self {
+        Self {
+            shared_config: Some(config),
+            ..Self::default()
+        }
+    }
+
     /// Returns `true` if there is a spawned [`WorkerHandle`] associated with the given [`Object`].
     pub fn contains(&self, object: &Object) -> bool {
         self.workers.contains_key(object)
@@ -166,6 +179,7 @@ impl WorkerMap {
             self.next_worker_id(),
             object.clone(),
             config,
+            self.shared_config.clone(),
         )
     }
 
@@ -0,0 +1,91 @@
+//! Tests that the adjacency matrices of N-ary connections reflect the
+//! topology the chains are connected in.
+
+use ibc_test_framework::bootstrap::nary::topology::bootstrap_nary_from_spec;
+use ibc_test_framework::chain::builder::ChainBuilder;
+use ibc_test_framework::framework::base::{run_basic_test, BasicTest};
+use ibc_test_framework::prelude::*;
+
+#[test]
+fn test_nary_mesh_adjacency() -> Result<(), Error> {
+    run_nary_connection_test(&NaryMeshAdjacencyTest)
+}
+
+#[test]
+fn test_nary_star_adjacency() -> Result<(), Error> {
+    run_basic_test(&NaryStarAdjacencyTest)
+}
+
+pub struct NaryMeshAdjacencyTest;
+
+pub struct NaryStarAdjacencyTest;
+
+impl TestOverrides for NaryMeshAdjacencyTest {}
+
+impl TestOverrides for NaryStarAdjacencyTest {}
+
+fn is_symmetric(matrix: &[Vec<bool>]) -> bool {
+    matrix.iter().enumerate().all(|(i, row)| {
+        row.iter()
+            .enumerate()
+            .all(|(j, entry)| *entry == matrix[j][i])
+    })
+}
+
+impl NaryConnectionTest<3> for NaryMeshAdjacencyTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        _chains: NaryConnectedChains<Handle, 3>,
+        connections: NaryConnectedConnections<Handle, 3>,
+    ) -> Result<(), Error> {
+        let matrix = connections.adjacency_matrix();
+
+        assert_eq(
+            "expect the mesh adjacency matrix to be symmetric",
+            &is_symmetric(&matrix),
+            &true,
+        )?;
+
+        assert_eq(
+            "expect all chains of the mesh to be connected",
+            &matrix,
+            &vec![vec![true; 3]; 3],
+        )?;
+
+        Ok(())
+    }
+}
+
+impl BasicTest for NaryStarAdjacencyTest {
+    fn run(&self, config: &TestConfig, builder: &ChainBuilder) -> Result<(), Error> {
+        let spec_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/nary-star-topology.toml"
+        );
+
+        let (_relayer, topology) = bootstrap_nary_from_spec(spec_path, config, builder)?;
+
+        let matrix = topology.adjacency_matrix();
+
+        assert_eq(
+            "expect the star adjacency matrix to be symmetric",
+            &is_symmetric(&matrix),
+            &true,
+        )?;
+
+        assert_eq(
+            "expect only the hub to be connected to the spokes",
+            &matrix,
+            &vec![
+                vec![false, true, true, true],
+                vec![true, false, false, false],
+                vec![true, false, false, false],
+                vec![true, false, false, false],
+            ],
+        )?;
+
+        Ok(())
+    }
+}
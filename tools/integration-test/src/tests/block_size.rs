@@ -0,0 +1,93 @@
+//! Tests that the block consensus params of each chain in an N-ary test can
+//! be overridden, with the chain at position 0 limited to small blocks.
+
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::transfer::build_transfer_message;
+use ibc_test_framework::types::wallet::WalletAddress;
+
+/// Small enough for an oversized transaction to be rejected, while still
+/// fitting the handshake transactions submitted by the relayer.
+const SMALL_MAX_BLOCK_BYTES: i64 = 32_000;
+
+/// The length of the recipient address of the oversized transfers, which
+/// is not validated by the sending chain.
+const OVERSIZED_RECIPIENT_LEN: usize = 40_000;
+
+#[test]
+fn test_nary_block_size() -> Result<(), Error> {
+    run_nary_channel_test(&NaryBlockSizeTest)
+}
+
+pub struct NaryBlockSizeTest;
+
+impl TestOverrides for NaryBlockSizeTest {
+    fn max_block_bytes(&self, chain_index: usize) -> Option<i64> {
+        if chain_index == 0 {
+            Some(SMALL_MAX_BLOCK_BYTES)
+        } else {
+            None
+        }
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl PortsOverride<2> for NaryBlockSizeTest {}
+
+impl NaryChannelTest<2> for NaryBlockSizeTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+        channels: NaryConnectedChannels<Handle, 2>,
+    ) -> Result<(), Error> {
+        let chains = chains.connected_chains_at::<0, 1>()?;
+        let channel = channels.channel_at::<0, 1>()?;
+
+        let oversized_recipient = WalletAddress("a".repeat(OVERSIZED_RECIPIENT_LEN));
+
+        info!("sending an oversized transfer on chain 0 with small blocks");
+
+        let message_a = build_transfer_message(
+            &channel.port_a.as_ref(),
+            &channel.channel_id_a.as_ref(),
+            &chains.node_a.wallets().user1(),
+            &MonoTagged::new(&oversized_recipient),
+            &chains.node_a.denom(),
+            1,
+        )?;
+
+        let result_a = chains
+            .node_a
+            .chain_driver()
+            .send_tx(&chains.node_a.wallets().user1(), vec![message_a]);
+
+        if result_a.is_ok() {
+            return Err(Error::generic(eyre!(
+                "expect oversized transaction to be rejected by chain 0 with max block bytes {}",
+                SMALL_MAX_BLOCK_BYTES
+            )));
+        }
+
+        info!("sending an oversized transfer on chain 1 with default blocks");
+
+        let message_b = build_transfer_message(
+            &channel.port_b.as_ref(),
+            &channel.channel_id_b.as_ref(),
+            &chains.node_b.wallets().user1(),
+            &MonoTagged::new(&oversized_recipient),
+            &chains.node_b.denom(),
+            1,
+        )?;
+
+        chains
+            .node_b
+            .chain_driver()
+            .send_tx(&chains.node_b.wallets().user1(), vec![message_b])?;
+
+        Ok(())
+    }
+}
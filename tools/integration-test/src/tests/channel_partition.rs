@@ -0,0 +1,53 @@
+//! Tests the partition of the channels of a live chain by a packet filter.
+
+use ibc_relayer::config::filter::{ChannelFilters, FilterPattern, PacketFilter};
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::channel::partition_chain_channels;
+
+#[test]
+fn test_partition_chain_channels() -> Result<(), Error> {
+    run_binary_channel_test(&PartitionChainChannelsTest)
+}
+
+pub struct PartitionChainChannelsTest;
+
+impl TestOverrides for PartitionChainChannelsTest {}
+
+impl BinaryChannelTest for PartitionChainChannelsTest {
+    fn run<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: ConnectedChains<ChainA, ChainB>,
+        channel: ConnectedChannel<ChainA, ChainB>,
+    ) -> Result<(), Error> {
+        let port_a = channel.port_a.value().clone();
+        let channel_id_a = channel.channel_id_a.value().clone();
+
+        let (allowed, denied) =
+            partition_chain_channels(chains.handle_a(), &PacketFilter::AllowAll)?;
+
+        assert_eq(
+            "expect the channel to be allowed by the default filter",
+            &allowed,
+            &vec![(port_a.clone(), channel_id_a.clone())],
+        )?;
+        assert!(denied.is_empty());
+
+        let deny = PacketFilter::Deny(ChannelFilters::new(vec![(
+            FilterPattern::Exact(port_a.clone()),
+            FilterPattern::Exact(channel_id_a.clone()),
+        )]));
+
+        let (allowed, denied) = partition_chain_channels(chains.handle_a(), &deny)?;
+
+        assert!(allowed.is_empty());
+        assert_eq(
+            "expect the channel to be denied by the deny filter",
+            &denied,
+            &vec![(port_a, channel_id_a)],
+        )?;
+
+        Ok(())
+    }
+}
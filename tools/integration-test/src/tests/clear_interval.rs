@@ -0,0 +1,81 @@
+//! Tests that the packet clearing interval can be overridden in an N-ary
+//! test, and that a slow interval delays the clearing of pending packets.
+
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::packet::assert_no_relay;
+use ibc_test_framework::util::random::random_u64_range;
+
+const SLOW_CLEAR_INTERVAL: u64 = 10_000;
+
+#[test]
+fn test_nary_slow_clear_interval() -> Result<(), Error> {
+    run_nary_channel_test(&NarySlowClearIntervalTest)
+}
+
+pub struct NarySlowClearIntervalTest;
+
+impl TestOverrides for NarySlowClearIntervalTest {
+    fn modify_relayer_config(&self, config: &mut Config) {
+        config.mode.packets.clear_on_start = false;
+    }
+
+    fn clear_interval(&self) -> u64 {
+        SLOW_CLEAR_INTERVAL
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl PortsOverride<2> for NarySlowClearIntervalTest {}
+
+impl NaryChannelTest<2> for NarySlowClearIntervalTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+        channels: NaryConnectedChannels<Handle, 2>,
+    ) -> Result<(), Error> {
+        assert_eq(
+            "expect the relayer config to use the overridden clear interval",
+            &relayer.config.mode.packets.clear_interval,
+            &SLOW_CLEAR_INTERVAL,
+        )?;
+
+        let chains = chains.connected_chains_at::<0, 1>()?;
+        let channel = channels.channel_at::<0, 1>()?;
+
+        let denom_a = chains.node_a.denom();
+        let wallet_a = chains.node_a.wallets().user1().cloned();
+        let wallet_b = chains.node_b.wallets().user1().cloned();
+
+        let amount = random_u64_range(1000, 5000);
+
+        info!(
+            "Performing IBC transfer with amount {} before the relayer starts",
+            amount
+        );
+
+        chains.node_a.chain_driver().ibc_transfer_token(
+            &channel.port_a.as_ref(),
+            &channel.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount,
+        )?;
+
+        relayer.with_supervisor(|| {
+            info!("Expecting the pending packet to not be cleared before the next clear interval");
+
+            assert_no_relay(
+                &chains,
+                &channel.port_a.as_ref(),
+                &channel.channel_id_a.as_ref(),
+                Duration::from_secs(10),
+            )
+        })
+    }
+}
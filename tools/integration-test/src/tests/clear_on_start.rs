@@ -0,0 +1,108 @@
+//! Tests that the clear-on-start behavior can be set per chain in an
+//! N-ary test, so that only the backlog of the chain configured to clear
+//! pending packets on start is drained.
+
+use ibc_test_framework::ibc::denom::derive_ibc_denom;
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::packet::assert_no_relay;
+use ibc_test_framework::util::random::random_u64_range;
+
+#[test]
+fn test_nary_clear_on_start_per_chain() -> Result<(), Error> {
+    run_nary_channel_test(&NaryClearOnStartTest)
+}
+
+pub struct NaryClearOnStartTest;
+
+impl TestOverrides for NaryClearOnStartTest {
+    fn modify_relayer_config(&self, config: &mut Config) {
+        config.mode.packets.clear_on_start = false;
+        config.mode.packets.clear_interval = 0;
+    }
+
+    fn clear_on_start(&self, chain_index: usize) -> Option<bool> {
+        Some(chain_index == 0)
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl PortsOverride<2> for NaryClearOnStartTest {}
+
+impl NaryChannelTest<2> for NaryClearOnStartTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+        channels: NaryConnectedChannels<Handle, 2>,
+    ) -> Result<(), Error> {
+        let chains_a_to_b = chains.connected_chains_at::<0, 1>()?;
+        let channel_a_to_b = channels.channel_at::<0, 1>()?;
+
+        let chains_b_to_a = chains.connected_chains_at::<1, 0>()?;
+        let channel_b_to_a = channels.channel_at::<1, 0>()?;
+
+        let denom_a = chains_a_to_b.node_a.denom();
+        let denom_b = chains_a_to_b.node_b.denom();
+
+        let wallet_a = chains_a_to_b.node_a.wallets().user1().cloned();
+        let wallet_b = chains_a_to_b.node_b.wallets().user1().cloned();
+
+        let amount_a = random_u64_range(1000, 5000);
+        let amount_b = random_u64_range(1000, 5000);
+
+        info!(
+            "Performing IBC transfers with amount {} from chain 0 and {} from chain 1 before the relayer starts",
+            amount_a, amount_b
+        );
+
+        chains_a_to_b.node_a.chain_driver().ibc_transfer_token(
+            &channel_a_to_b.port_a.as_ref(),
+            &channel_a_to_b.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount_a,
+        )?;
+
+        chains_b_to_a.node_a.chain_driver().ibc_transfer_token(
+            &channel_b_to_a.port_a.as_ref(),
+            &channel_b_to_a.channel_id_a.as_ref(),
+            &wallet_b.as_ref(),
+            &wallet_a.address(),
+            &denom_b,
+            amount_b,
+        )?;
+
+        let ibc_denom_b = derive_ibc_denom(
+            &channel_a_to_b.port_b.as_ref(),
+            &channel_a_to_b.channel_id_b.as_ref(),
+            &denom_a,
+        )?;
+
+        relayer.with_supervisor(|| {
+            info!("Expecting the backlog of chain 0 to be cleared on start");
+
+            chains_a_to_b
+                .node_b
+                .chain_driver()
+                .assert_eventual_wallet_amount(
+                    &wallet_b.address(),
+                    amount_a,
+                    &ibc_denom_b.as_ref(),
+                )?;
+
+            info!("Expecting the backlog of chain 1 to be left unrelayed");
+
+            assert_no_relay(
+                &chains_b_to_a,
+                &channel_b_to_a.port_a.as_ref(),
+                &channel_b_to_a.channel_id_a.as_ref(),
+                Duration::from_secs(10),
+            )
+        })
+    }
+}
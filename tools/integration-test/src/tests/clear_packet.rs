@@ -1,3 +1,6 @@
+use ibc_relayer::config::filter::FilterPattern;
+use ibc_relayer::config::DenomFilter;
+use ibc_relayer::link::{Link, LinkParameters};
 use ibc_test_framework::ibc::denom::derive_ibc_denom;
 use ibc_test_framework::prelude::*;
 use ibc_test_framework::util::random::random_u64_range;
@@ -12,8 +15,14 @@ fn test_clear_packet_recovery() -> Result<(), Error> {
     run_binary_channel_test(&ClearPacketRecoveryTest)
 }
 
+#[test]
+fn test_clear_filtered_packet() -> Result<(), Error> {
+    run_binary_channel_test(&ClearFilteredPacketTest)
+}
+
 pub struct ClearPacketTest;
 pub struct ClearPacketRecoveryTest;
+pub struct ClearFilteredPacketTest;
 
 impl TestOverrides for ClearPacketTest {
     fn modify_relayer_config(&self, config: &mut Config) {
@@ -179,3 +188,73 @@ impl BinaryChannelTest for ClearPacketRecoveryTest {
         })
     }
 }
+
+impl TestOverrides for ClearFilteredPacketTest {
+    fn modify_relayer_config(&self, config: &mut Config) {
+        // Deny the transfers of all denoms from chain A, which only the
+        // packet workers of the supervisor are expected to enforce.
+        config.chains[0].denom_filter =
+            DenomFilter::Deny(vec![FilterPattern::Wildcard("*".parse().unwrap())]);
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl BinaryChannelTest for ClearFilteredPacketTest {
+    fn run<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: ConnectedChains<ChainA, ChainB>,
+        channel: ConnectedChannel<ChainA, ChainB>,
+    ) -> Result<(), Error> {
+        let denom_a = chains.node_a.denom();
+
+        let wallet_a = chains.node_a.wallets().user1().cloned();
+        let wallet_b = chains.node_b.wallets().user1().cloned();
+
+        let amount = random_u64_range(1000, 5000);
+
+        chains.node_a.chain_driver().ibc_transfer_token(
+            &channel.port_a.as_ref(),
+            &channel.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount,
+        )?;
+
+        info!(
+            "Clearing the packet denied by the denom filter, which should be relayed nonetheless"
+        );
+
+        let opts = LinkParameters {
+            src_port_id: channel.port_a.clone().into_value(),
+            src_channel_id: channel.channel_id_a.clone().into_value(),
+        };
+        let link = Link::new_from_opts(
+            chains.handle_a().clone(),
+            chains.handle_b().clone(),
+            opts,
+            false,
+        )?;
+
+        link.relay_recv_packet_and_timeout_messages()?;
+
+        let denom_b = derive_ibc_denom(
+            &channel.port_b.as_ref(),
+            &channel.channel_id_b.as_ref(),
+            &denom_a,
+        )?;
+
+        chains.node_b.chain_driver().assert_eventual_wallet_amount(
+            &wallet_b.address(),
+            amount,
+            &denom_b.as_ref(),
+        )?;
+
+        Ok(())
+    }
+}
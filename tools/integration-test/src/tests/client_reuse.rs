@@ -0,0 +1,83 @@
+//! Tests that N-ary connections can be bootstrapped repeatedly by reusing
+//! the clients already existing between each pair of chains, without
+//! creating any duplicate client.
+
+use ibc_relayer::chain::requests::{PageRequest, QueryClientStatesRequest};
+use ibc_test_framework::bootstrap::nary::connection::bootstrap_connections_with_client_reuse;
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::types::nary::topology::Topology;
+
+#[test]
+fn test_nary_client_reuse() -> Result<(), Error> {
+    run_nary_connection_test(&NaryClientReuseTest)
+}
+
+pub struct NaryClientReuseTest;
+
+impl TestOverrides for NaryClientReuseTest {
+    fn reuse_existing_clients(&self) -> bool {
+        true
+    }
+}
+
+impl NaryConnectionTest<3> for NaryClientReuseTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+        connections: NaryConnectedConnections<Handle, 3>,
+    ) -> Result<(), Error> {
+        let clients_before = count_clients(chains.chain_handles())?;
+
+        info!("bootstrapping a second set of connections over the existing clients");
+
+        let second_connections = bootstrap_connections_with_client_reuse(
+            chains.foreign_clients().clone(),
+            &Topology::Complete,
+            |_, _| self.connection_delay(),
+            config.bootstrap_with_random_ids,
+            |chain_a, chain_b| chain_a.min(chain_b),
+        )?;
+
+        let clients_after = count_clients(chains.chain_handles())?;
+
+        assert_eq(
+            "expect no client to be created by the second connections",
+            &clients_after,
+            &clients_before,
+        )?;
+
+        let first = connections.connection_at::<0, 1>()?;
+        let second = second_connections.connection_at::<0, 1>()?;
+
+        assert_eq(
+            "expect the second connection to be bound to the same client",
+            second.client_ids.client_id_a.value(),
+            first.client_ids.client_id_a.value(),
+        )?;
+
+        assert_not_eq(
+            "expect the second connection to be a new connection",
+            second.connection_id_a.value(),
+            first.connection_id_a.value(),
+        )?;
+
+        Ok(())
+    }
+}
+
+fn count_clients<Handle: ChainHandle>(handles: &[Handle]) -> Result<Vec<usize>, Error> {
+    handles
+        .iter()
+        .map(|handle| {
+            let clients = handle
+                .query_clients(QueryClientStatesRequest {
+                    pagination: Some(PageRequest::all()),
+                })
+                .map_err(handle_generic_error)?;
+
+            Ok(clients.len())
+        })
+        .collect()
+}
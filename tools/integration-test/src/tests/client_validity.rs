@@ -0,0 +1,34 @@
+//! Tests that [`NaryConnectedChains::assert_clients_valid`] accepts freshly
+//! created clients and rejects a threshold beyond their trusting period.
+
+use ibc_test_framework::prelude::*;
+
+#[test]
+fn test_nary_clients_valid() -> Result<(), Error> {
+    run_nary_chain_test(&NaryClientsValidTest)
+}
+
+pub struct NaryClientsValidTest;
+
+impl TestOverrides for NaryClientsValidTest {}
+
+impl NaryChainTest<2> for NaryClientsValidTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+    ) -> Result<(), Error> {
+        chains.assert_clients_valid(Duration::from_secs(60))?;
+
+        let res = chains.assert_clients_valid(Duration::from_secs(10 * 365 * 24 * 60 * 60));
+
+        assert_eq(
+            "expect clients to fail validation with a threshold beyond their trusting period",
+            &res.is_err(),
+            &true,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//! Tests that client updates succeed when the clock of the chain hosting
+//! the client is skewed within the `max_clock_drift` of the client, and are
+//! rejected as being in the future when it is skewed beyond it.
+//!
+//! The skew is simulated by the chain handles, which report block timestamps
+//! behind the actual ones for the chain at position 0.
+
+use ibc_relayer::chain::requests::{IncludeProof, QueryClientStateRequest, QueryHeight};
+use ibc_relayer::foreign_client::ForeignClientErrorDetail;
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::clock_skew::ClockSkew;
+
+#[test]
+fn test_small_clock_skew() -> Result<(), Error> {
+    run_nary_chain_test(&ClockSkewTest {
+        skew: Duration::from_secs(10),
+        expect_rejection: false,
+    })
+}
+
+#[test]
+fn test_large_clock_skew() -> Result<(), Error> {
+    run_nary_chain_test(&ClockSkewTest {
+        skew: Duration::from_secs(3600),
+        expect_rejection: true,
+    })
+}
+
+pub struct ClockSkewTest {
+    skew: Duration,
+    expect_rejection: bool,
+}
+
+impl TestOverrides for ClockSkewTest {
+    fn clock_skew(&self, chain_index: usize) -> ClockSkew {
+        if chain_index == 0 {
+            ClockSkew::Behind(self.skew)
+        } else {
+            ClockSkew::Synced
+        }
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl NaryChainTest<2> for ClockSkewTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+    ) -> Result<(), Error> {
+        // The client hosted on the skewed chain 0, tracking chain 1
+        let client = chains.foreign_client_at::<1, 0>()?;
+
+        let (client_state, _) = client.dst_chain().query_client_state(
+            QueryClientStateRequest {
+                client_id: client.id().clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )?;
+
+        let max_clock_drift = client_state.max_clock_drift();
+
+        assert_eq(
+            "expect the skew to be beyond the max clock drift of the client only if rejected",
+            &(self.skew > max_clock_drift),
+            &self.expect_rejection,
+        )?;
+
+        info!(
+            "updating client with chain clock behind by {:?}, with max clock drift {:?}",
+            self.skew, max_clock_drift
+        );
+
+        let result = client.build_update_client_and_send(QueryHeight::Latest, None);
+
+        match result {
+            Ok(_) if !self.expect_rejection => Ok(()),
+            Err(e) if self.expect_rejection => match e.detail() {
+                ForeignClientErrorDetail::HeaderInTheFuture(_) => Ok(()),
+                _ => Err(handle_generic_error(e)),
+            },
+            Ok(_) => Err(Error::assertion(format!(
+                "expect the client update to be rejected with the clock skewed by {:?}",
+                self.skew
+            ))),
+            Err(e) => Err(handle_generic_error(e)),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use ibc_relayer_types::core::ics23_commitment::commitment::CommitmentPrefix;
+use ibc_test_framework::prelude::*;
+
+/// A test to exercise per-chain commitment prefix overrides in N-ary tests.
+#[test]
+fn test_nary_commitment_prefix_override() -> Result<(), Error> {
+    run_nary_chain_test(&CommitmentPrefixTest)
+}
+
+struct CommitmentPrefixTest;
+
+fn custom_prefix() -> CommitmentPrefix {
+    CommitmentPrefix::try_from(b"custom".to_vec()).unwrap()
+}
+
+fn default_prefix() -> CommitmentPrefix {
+    CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap()
+}
+
+impl TestOverrides for CommitmentPrefixTest {
+    fn commitment_prefix(&self, chain_index: usize) -> CommitmentPrefix {
+        if chain_index == 1 {
+            custom_prefix()
+        } else {
+            default_prefix()
+        }
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl NaryChainTest<2> for CommitmentPrefixTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+    ) -> Result<(), Error> {
+        assert_eq!(relayer.config.chains[0].store_prefix, "ibc");
+        assert_eq!(relayer.config.chains[1].store_prefix, "custom");
+
+        let handles = chains.chain_handles();
+
+        // The relayer builds the counterparty prefix of connections, against
+        // which the proofs of the chain are verified, from the chain's prefix.
+        assert_eq!(handles[0].query_commitment_prefix()?, default_prefix());
+        assert_eq!(handles[1].query_commitment_prefix()?, custom_prefix());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+//! Tests that the delay period read back from the connection ends matches
+//! the connection delay override.
+
+use ibc_test_framework::prelude::*;
+
+const CONNECTION_DELAY: Duration = Duration::from_secs(7);
+
+#[test]
+fn test_connection_delay_readback() -> Result<(), Error> {
+    run_nary_connection_test(&ConnectionDelayReadbackTest)
+}
+
+pub struct ConnectionDelayReadbackTest;
+
+impl TestOverrides for ConnectionDelayReadbackTest {
+    fn connection_delay(&self) -> Duration {
+        CONNECTION_DELAY
+    }
+}
+
+impl NaryConnectionTest<2> for ConnectionDelayReadbackTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        _chains: NaryConnectedChains<Handle, 2>,
+        connections: NaryConnectedConnections<Handle, 2>,
+    ) -> Result<(), Error> {
+        assert_eq(
+            "expect the connection delay from chain 0 to chain 1 to match the override",
+            &connections.delay_at::<0, 1>()?,
+            &CONNECTION_DELAY,
+        )?;
+
+        assert_eq(
+            "expect the connection delay from chain 1 to chain 0 to match the override",
+            &connections.delay_at::<1, 0>()?,
+            &CONNECTION_DELAY,
+        )?;
+
+        Ok(())
+    }
+}
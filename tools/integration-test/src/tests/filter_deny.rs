@@ -0,0 +1,73 @@
+//! Tests that packets sent over a channel denied by the packet filter
+//! are left unrelayed in an N-ary test.
+
+use ibc_relayer::config::filter::{ChannelFilters, FilterPattern, PacketFilter};
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::packet::assert_no_relay;
+use ibc_test_framework::util::random::random_u64_range;
+
+#[test]
+fn test_nary_filter_deny_no_relay() -> Result<(), Error> {
+    run_nary_channel_test(&NaryFilterDenyTest)
+}
+
+pub struct NaryFilterDenyTest;
+
+impl TestOverrides for NaryFilterDenyTest {
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl PortsOverride<2> for NaryFilterDenyTest {}
+
+impl NaryChannelTest<2> for NaryFilterDenyTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        mut relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+        channels: NaryConnectedChannels<Handle, 2>,
+    ) -> Result<(), Error> {
+        let chains = chains.connected_chains_at::<0, 1>()?;
+        let channel = channels.channel_at::<0, 1>()?;
+
+        let denom_a = chains.node_a.denom();
+
+        let wallet_a = chains.node_a.wallets().user1().cloned();
+        let wallet_b = chains.node_b.wallets().user1().cloned();
+
+        relayer.set_filter(
+            &chains.handle_a().id(),
+            PacketFilter::Deny(ChannelFilters::new(vec![(
+                FilterPattern::Exact(channel.port_a.value().clone()),
+                FilterPattern::Exact(channel.channel_id_a.value().clone()),
+            )])),
+        )?;
+
+        let amount = random_u64_range(1000, 5000);
+
+        info!(
+            "Performing IBC transfer with amount {}, which should *not* be relayed",
+            amount
+        );
+
+        chains.node_a.chain_driver().ibc_transfer_token(
+            &channel.port_a.as_ref(),
+            &channel.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount,
+        )?;
+
+        relayer.with_supervisor(|| {
+            assert_no_relay(
+                &chains,
+                &channel.port_a.as_ref(),
+                &channel.channel_id_a.as_ref(),
+                Duration::from_secs(10),
+            )
+        })
+    }
+}
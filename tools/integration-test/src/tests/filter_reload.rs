@@ -0,0 +1,163 @@
+//! Tests that a packet filter swapped on the [`RelayerDriver`] in the middle
+//! of an N-ary test is hot-loaded by the running supervisor.
+
+use ibc_relayer::chain::counterparty::pending_packet_summary;
+use ibc_relayer::config::filter::{ChannelFilters, FilterPattern, PacketFilter};
+use ibc_test_framework::ibc::denom::derive_ibc_denom;
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::channel::query_identified_channel_end;
+use ibc_test_framework::util::random::random_u64_range;
+
+#[test]
+fn test_nary_filter_hot_reload() -> Result<(), Error> {
+    run_nary_channel_test(&NaryFilterReloadTest)
+}
+
+pub struct NaryFilterReloadTest;
+
+impl TestOverrides for NaryFilterReloadTest {
+    fn modify_test_config(&self, config: &mut TestConfig) {
+        config.bootstrap_with_random_ids = false;
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl PortsOverride<3> for NaryFilterReloadTest {}
+
+impl NaryChannelTest<3> for NaryFilterReloadTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        mut relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+        channels: NaryConnectedChannels<Handle, 3>,
+    ) -> Result<(), Error> {
+        let chains_ab = chains.connected_chains_at::<0, 1>()?;
+        let chains_ac = chains.connected_chains_at::<0, 2>()?;
+
+        let channel_ab = channels.channel_at::<0, 1>()?;
+        let channel_ac = channels.channel_at::<0, 2>()?;
+
+        let denom_a = chains_ab.node_a.denom();
+
+        let wallet_a = chains_ab.node_a.wallets().user1().cloned();
+        let wallet_b = chains_ab.node_b.wallets().user1().cloned();
+        let wallet_c = chains_ac.node_b.wallets().user1().cloned();
+
+        let denom_b = derive_ibc_denom(
+            &channel_ab.port_b.as_ref(),
+            &channel_ab.channel_id_b.as_ref(),
+            &denom_a,
+        )?;
+
+        let denom_c = derive_ibc_denom(
+            &channel_ac.port_b.as_ref(),
+            &channel_ac.channel_id_b.as_ref(),
+            &denom_a,
+        )?;
+
+        let amount1 = random_u64_range(1000, 5000);
+
+        let _supervisor = relayer.spawn_supervisor()?;
+
+        info!(
+            "Performing IBC transfer with amount {}, which should be relayed",
+            amount1
+        );
+
+        chains_ab.node_a.chain_driver().ibc_transfer_token(
+            &channel_ab.port_a.as_ref(),
+            &channel_ab.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount1,
+        )?;
+
+        chains_ab
+            .node_b
+            .chain_driver()
+            .assert_eventual_wallet_amount(&wallet_b.address(), amount1, &denom_b.as_ref())?;
+
+        info!("Hot-loading a deny filter for the channel from the first to the second chain");
+
+        // Returns once the running supervisor has applied the filter
+        relayer.set_filter(
+            &chains_ab.handle_a().id(),
+            PacketFilter::Deny(ChannelFilters::new(vec![(
+                FilterPattern::Exact(channel_ab.port_a.value().clone()),
+                FilterPattern::Exact(channel_ab.channel_id_a.value().clone()),
+            )])),
+        )?;
+
+        let amount2 = random_u64_range(1000, 5000);
+        let amount3 = random_u64_range(1000, 5000);
+
+        info!(
+            "Performing IBC transfer with amount {} on the denied channel, which should *not* be relayed",
+            amount2
+        );
+
+        chains_ab.node_a.chain_driver().ibc_transfer_token(
+            &channel_ab.port_a.as_ref(),
+            &channel_ab.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount2,
+        )?;
+
+        info!(
+            "Performing IBC transfer with amount {} on the still allowed channel, which should be relayed",
+            amount3
+        );
+
+        chains_ac.node_a.chain_driver().ibc_transfer_token(
+            &channel_ac.port_a.as_ref(),
+            &channel_ac.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_c.address(),
+            &denom_a,
+            amount3,
+        )?;
+
+        // The supervisor is still relaying the packets of the first chain, and by
+        // the time it relays this one it has handled the event of the denied packet
+        chains_ac
+            .node_b
+            .chain_driver()
+            .assert_eventual_wallet_amount(&wallet_c.address(), amount3, &denom_c.as_ref())?;
+
+        let channel_end = query_identified_channel_end(
+            chains_ab.handle_a(),
+            channel_ab.channel_id_a.as_ref(),
+            channel_ab.port_a.as_ref(),
+        )?;
+
+        let summary = pending_packet_summary(
+            chains_ab.handle_a(),
+            chains_ab.handle_b(),
+            channel_end.value(),
+        )?;
+
+        assert_eq(
+            "the packet on the denied channel should still be pending",
+            &summary.unreceived_packets.len(),
+            &1,
+        )?;
+
+        let balance_b = chains_ab
+            .node_b
+            .chain_driver()
+            .query_balance(&wallet_b.address(), &denom_b.as_ref())?;
+
+        assert_eq(
+            "balance on chain B should not change after the deny filter is loaded",
+            &balance_b,
+            &amount1,
+        )
+    }
+}
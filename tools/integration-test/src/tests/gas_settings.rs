@@ -0,0 +1,39 @@
+use ibc_relayer::config::GasPrice;
+use ibc_test_framework::prelude::*;
+
+/// A test to exercise per-chain gas settings overrides in N-ary tests.
+#[test]
+fn test_nary_gas_settings_override() -> Result<(), Error> {
+    run_nary_chain_test(&GasSettingsTest)
+}
+
+struct GasSettingsTest;
+
+fn overridden_gas_price() -> GasPrice {
+    GasPrice::new(0.05, "stake".to_string())
+}
+
+impl TestOverrides for GasSettingsTest {
+    fn gas_price(&self, chain_index: usize) -> Option<GasPrice> {
+        if chain_index == 0 {
+            Some(overridden_gas_price())
+        } else {
+            None
+        }
+    }
+}
+
+impl NaryChainTest<2> for GasSettingsTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        relayer: RelayerDriver,
+        _chains: NaryConnectedChains<Handle, 2>,
+    ) -> Result<(), Error> {
+        let chain_config = &relayer.config.chains[0];
+
+        assert_eq!(chain_config.gas_price, overridden_gas_price());
+
+        Ok(())
+    }
+}
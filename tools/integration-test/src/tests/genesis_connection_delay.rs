@@ -0,0 +1,57 @@
+//! Tests that the connection delay of N-ary connections can be derived
+//! from the `max_expected_time_per_block` parameter in the genesis of the
+//! chains.
+
+use ibc_test_framework::prelude::*;
+
+const MAX_EXPECTED_TIME_PER_BLOCK: Duration = Duration::from_secs(12);
+
+#[test]
+fn test_nary_genesis_connection_delay() -> Result<(), Error> {
+    run_nary_connection_test(&NaryGenesisConnectionDelayTest)
+}
+
+pub struct NaryGenesisConnectionDelayTest;
+
+impl TestOverrides for NaryGenesisConnectionDelayTest {
+    fn modify_genesis_file(&self, genesis: &mut serde_json::Value) -> Result<(), Error> {
+        use serde_json::Value;
+
+        let params = genesis
+            .get_mut("app_state")
+            .and_then(|app_state| app_state.get_mut("ibc"))
+            .and_then(|ibc| ibc.get_mut("connection_genesis"))
+            .and_then(|connection| connection.get_mut("params"))
+            .and_then(|params| params.as_object_mut())
+            .ok_or_else(|| eyre!("failed to find the IBC connection params in genesis"))?;
+
+        params.insert(
+            "max_expected_time_per_block".to_string(),
+            Value::String(MAX_EXPECTED_TIME_PER_BLOCK.as_nanos().to_string()),
+        );
+
+        Ok(())
+    }
+
+    fn connection_delay_from_genesis(&self) -> bool {
+        true
+    }
+}
+
+impl NaryConnectionTest<2> for NaryGenesisConnectionDelayTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        _chains: NaryConnectedChains<Handle, 2>,
+        connections: NaryConnectedConnections<Handle, 2>,
+    ) -> Result<(), Error> {
+        assert_eq(
+            "expect the connection delay to match the genesis of the chains",
+            &connections.delay_at::<0, 1>()?,
+            &MAX_EXPECTED_TIME_PER_BLOCK,
+        )?;
+
+        Ok(())
+    }
+}
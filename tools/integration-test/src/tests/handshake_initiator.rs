@@ -0,0 +1,99 @@
+//! Tests that the chain initiating the handshake of each N-ary connection
+//! can be chosen by the test case.
+
+use ibc_test_framework::prelude::*;
+
+#[test]
+fn test_nary_handshake_initiator() -> Result<(), Error> {
+    run_nary_connection_test(&NaryHandshakeInitiatorTest)
+}
+
+pub struct NaryHandshakeInitiatorTest;
+
+impl TestOverrides for NaryHandshakeInitiatorTest {
+    fn handshake_initiator(&self, chain_a: usize, chain_b: usize) -> usize {
+        if (chain_a, chain_b) == (1, 2) {
+            2
+        } else {
+            chain_a.min(chain_b)
+        }
+    }
+}
+
+fn count_transactions(response: &serde_json::Value) -> usize {
+    response
+        .get("txs")
+        .and_then(|txs| txs.as_array())
+        .map_or(0, |txs| txs.len())
+}
+
+impl NaryConnectionTest<3> for NaryHandshakeInitiatorTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+        connections: NaryConnectedConnections<Handle, 3>,
+    ) -> Result<(), Error> {
+        let node_0 = chains.full_node_at::<0>()?;
+        let node_1 = chains.full_node_at::<1>()?;
+        let node_2 = chains.full_node_at::<2>()?;
+
+        let connection_0_1 = connections.connection_at::<0, 1>()?;
+        let connection_1_2 = connections.connection_at::<1, 2>()?;
+
+        info!("expect chain 0 to initiate the connection handshake with chain 1 by default");
+
+        assert_eq(
+            "expect the ConnOpenInit of connection 0/1 to be submitted to chain 0",
+            &count_transactions(
+                &node_0
+                    .chain_driver()
+                    .query_connection_open_init_transactions(
+                        &connection_0_1.connection_id_a.as_ref(),
+                    )?,
+            ),
+            &1,
+        )?;
+
+        assert_eq(
+            "expect no ConnOpenInit of connection 0/1 to be submitted to chain 1",
+            &count_transactions(
+                &node_1
+                    .chain_driver()
+                    .query_connection_open_init_transactions(
+                        &connection_0_1.connection_id_b.as_ref(),
+                    )?,
+            ),
+            &0,
+        )?;
+
+        info!("expect chain 2 to initiate the connection handshake with chain 1 when overridden");
+
+        assert_eq(
+            "expect the ConnOpenInit of connection 1/2 to be submitted to chain 2",
+            &count_transactions(
+                &node_2
+                    .chain_driver()
+                    .query_connection_open_init_transactions(
+                        &connection_1_2.connection_id_b.as_ref(),
+                    )?,
+            ),
+            &1,
+        )?;
+
+        assert_eq(
+            "expect no ConnOpenInit of connection 1/2 to be submitted to chain 1",
+            &count_transactions(
+                &node_1
+                    .chain_driver()
+                    .query_connection_open_init_transactions(
+                        &connection_1_2.connection_id_a.as_ref(),
+                    )?,
+            ),
+            &0,
+        )?;
+
+        Ok(())
+    }
+}
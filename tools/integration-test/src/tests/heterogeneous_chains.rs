@@ -0,0 +1,90 @@
+//! Tests that each chain of an N-ary test can be bootstrapped with its own
+//! genesis, here with the chain at position 1 using a different staking denom.
+
+use ibc_relayer::config::GasPrice;
+use ibc_test_framework::ibc::denom::Denom;
+use ibc_test_framework::prelude::*;
+
+const CUSTOM_STAKE_DENOM: &str = "ustake";
+
+#[test]
+fn test_nary_heterogeneous_chains() -> Result<(), Error> {
+    run_nary_chain_test(&HeterogeneousChainsTest)
+}
+
+pub struct HeterogeneousChainsTest;
+
+fn stake_denom(chain_index: usize) -> &'static str {
+    if chain_index == 1 {
+        CUSTOM_STAKE_DENOM
+    } else {
+        "stake"
+    }
+}
+
+impl TestOverrides for HeterogeneousChainsTest {
+    fn modify_genesis_file_at(
+        &self,
+        chain_index: usize,
+        genesis: &mut serde_json::Value,
+    ) -> Result<(), Error> {
+        use serde_json::Value;
+
+        if chain_index != 1 {
+            return Ok(());
+        }
+
+        let params = genesis
+            .get_mut("app_state")
+            .and_then(|app_state| app_state.get_mut("staking"))
+            .and_then(|staking| staking.get_mut("params"))
+            .and_then(|params| params.as_object_mut())
+            .ok_or_else(|| eyre!("failed to find the staking params in genesis"))?;
+
+        params.insert(
+            "bond_denom".to_string(),
+            Value::String(CUSTOM_STAKE_DENOM.to_string()),
+        );
+
+        Ok(())
+    }
+
+    // The relayer pays the fees on each chain in the chain's staking denom.
+    fn gas_price(&self, chain_index: usize) -> Option<GasPrice> {
+        Some(GasPrice::new(0.001, stake_denom(chain_index).to_string()))
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl NaryChainTest<3> for HeterogeneousChainsTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+    ) -> Result<(), Error> {
+        for (i, node) in chains.full_nodes().iter().enumerate() {
+            let denom = Denom::base(stake_denom(i));
+
+            let balance = node
+                .chain_driver
+                .query_balance(&node.wallets.relayer.address, &denom)?;
+
+            info!(
+                "relayer on chain {} has balance {} {}",
+                node.chain_driver.chain_id, balance, denom
+            );
+
+            assert_gt(
+                &format!("relayer on chain {} should hold {}", i, denom),
+                &balance,
+                &0,
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! Tests that packets keep being relayed after the key that the relayer
+//! signs transactions with on a chain is rotated in the middle of an N-ary test.
+
+use ibc_relayer::chain::counterparty::pending_packet_summary;
+use ibc_test_framework::ibc::denom::{derive_ibc_denom, Denom};
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::channel::query_identified_channel_end;
+use ibc_test_framework::util::random::random_u64_range;
+use ibc_test_framework::util::retry::assert_eventually_succeed;
+
+#[test]
+fn test_nary_key_rotation() -> Result<(), Error> {
+    run_nary_channel_test(&NaryKeyRotationTest)
+}
+
+pub struct NaryKeyRotationTest;
+
+impl TestOverrides for NaryKeyRotationTest {
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl PortsOverride<2> for NaryKeyRotationTest {}
+
+impl NaryChannelTest<2> for NaryKeyRotationTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        mut relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+        channels: NaryConnectedChannels<Handle, 2>,
+    ) -> Result<(), Error> {
+        let chains = chains.connected_chains_at::<0, 1>()?;
+        let channel = channels.channel_at::<0, 1>()?;
+
+        let denom_a = chains.node_a.denom();
+        let stake = MonoTagged::new(Denom::base("stake"));
+
+        let wallet_a = chains.node_a.wallets().user1().cloned();
+        let wallet_b = chains.node_b.wallets().user1().cloned();
+        let new_relayer_wallet = chains.node_a.wallets().user2().cloned();
+
+        let denom_b = derive_ibc_denom(
+            &channel.port_b.as_ref(),
+            &channel.channel_id_b.as_ref(),
+            &denom_a,
+        )?;
+
+        let channel_end = query_identified_channel_end(
+            chains.handle_a(),
+            channel.channel_id_a.as_ref(),
+            channel.port_a.as_ref(),
+        )?;
+
+        let assert_pending = |unreceived: usize| -> Result<(), Error> {
+            let summary =
+                pending_packet_summary(chains.handle_a(), chains.handle_b(), channel_end.value())?;
+
+            assert_eq(
+                "number of packets pending on chain A",
+                &summary.unreceived_packets.len(),
+                &unreceived,
+            )
+        };
+
+        let assert_eventually_cleared = || {
+            assert_eventually_succeed(
+                "all packets sent from chain A to be cleared",
+                20,
+                Duration::from_secs(1),
+                || {
+                    let summary = pending_packet_summary(
+                        chains.handle_a(),
+                        chains.handle_b(),
+                        channel_end.value(),
+                    )?;
+
+                    if summary.unreceived_packets.is_empty() && summary.unreceived_acks.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(Error::generic(eyre!(
+                            "pending packets on chain A: {:?}",
+                            summary
+                        )))
+                    }
+                },
+            )
+        };
+
+        let amount1 = random_u64_range(1000, 5000);
+
+        info!(
+            "Sending IBC transfer with amount {} before key rotation",
+            amount1
+        );
+
+        chains.node_a.chain_driver().ibc_transfer_token(
+            &channel.port_a.as_ref(),
+            &channel.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount1,
+        )?;
+
+        sleep(Duration::from_secs(2));
+
+        assert_pending(1)?;
+
+        relayer.with_supervisor(|| {
+            chains.node_b.chain_driver().assert_eventual_wallet_amount(
+                &wallet_b.address(),
+                amount1,
+                &denom_b.as_ref(),
+            )?;
+
+            assert_eventually_cleared()
+        })?;
+
+        info!(
+            "Rotating the relayer key on chain A to {}",
+            new_relayer_wallet.value().id.0
+        );
+
+        relayer.set_key(
+            &chains.handle_a().id(),
+            new_relayer_wallet.value().id.0.clone(),
+        )?;
+
+        let stake_before = chains
+            .node_a
+            .chain_driver()
+            .query_balance(&new_relayer_wallet.address(), &stake.as_ref())?;
+
+        let amount2 = random_u64_range(1000, 5000);
+
+        info!(
+            "Sending IBC transfer with amount {} after key rotation",
+            amount2
+        );
+
+        chains.node_a.chain_driver().ibc_transfer_token(
+            &channel.port_a.as_ref(),
+            &channel.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount2,
+        )?;
+
+        sleep(Duration::from_secs(2));
+
+        assert_pending(1)?;
+
+        relayer.with_supervisor(|| {
+            chains.node_b.chain_driver().assert_eventual_wallet_amount(
+                &wallet_b.address(),
+                amount1 + amount2,
+                &denom_b.as_ref(),
+            )?;
+
+            assert_eventually_cleared()
+        })?;
+
+        let stake_after = chains
+            .node_a
+            .chain_driver()
+            .query_balance(&new_relayer_wallet.address(), &stake.as_ref())?;
+
+        // The acknowledgement is relayed to chain A with the new key,
+        // which thus pays the fees of the transaction.
+        assert_gt(
+            "the new relayer key should have paid fees on chain A",
+            &stake_before,
+            &stake_after,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use ibc_relayer::chain::counterparty::pending_packet_summary;
+use ibc_relayer::link::{Link, LinkParameters};
+
+use ibc_test_framework::ibc::denom::derive_ibc_denom;
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::channel::query_identified_channel_end;
+use ibc_test_framework::relayer::malformed_proof::MalformedProofChainHandle;
+use ibc_test_framework::util::random::random_u64_range;
+
+#[test]
+fn test_malformed_proof_resilience() -> Result<(), Error> {
+    run_binary_channel_test(&MalformedProofTest)
+}
+
+pub struct MalformedProofTest;
+
+impl TestOverrides for MalformedProofTest {
+    fn modify_relayer_config(&self, config: &mut Config) {
+        config.mode.packets.clear_on_start = false;
+        config.mode.packets.clear_interval = 0;
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl BinaryChannelTest for MalformedProofTest {
+    fn run<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: ConnectedChains<ChainA, ChainB>,
+        channel: ConnectedChannel<ChainA, ChainB>,
+    ) -> Result<(), Error> {
+        let denom_a = chains.node_a.denom();
+
+        let wallet_a = chains.node_a.wallets().user1().cloned();
+        let wallet_b = chains.node_b.wallets().user1().cloned();
+
+        let amount = random_u64_range(1000, 5000);
+
+        chains.node_a.chain_driver().ibc_transfer_token(
+            &channel.port_a.as_ref(),
+            &channel.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            amount,
+        )?;
+
+        sleep(Duration::from_secs(2));
+
+        let handle_a = MalformedProofChainHandle::new(chains.handle_a().clone());
+
+        let opts = LinkParameters {
+            src_port_id: channel.port_a.clone().into_value(),
+            src_channel_id: channel.channel_id_a.clone().into_value(),
+        };
+        let link = Link::new_from_opts(handle_a.clone(), chains.handle_b().clone(), opts, false)?;
+
+        let channel_end = query_identified_channel_end(
+            chains.handle_a(),
+            channel.channel_id_a.as_ref(),
+            channel.port_a.as_ref(),
+        )?;
+
+        info!("Relaying packet with malformed proofs, which should *not* be received");
+
+        handle_a.inject_malformed_proofs(true);
+
+        if let Err(e) = link.relay_recv_packet_and_timeout_messages() {
+            info!("relaying with malformed proofs failed as expected: {}", e);
+        }
+
+        let summary =
+            pending_packet_summary(chains.handle_a(), chains.handle_b(), channel_end.value())?;
+
+        assert_eq!(summary.unreceived_packets, [1.into()]);
+
+        info!("Relaying packet with valid proofs, which should be received");
+
+        handle_a.inject_malformed_proofs(false);
+
+        link.relay_recv_packet_and_timeout_messages()?;
+
+        let summary =
+            pending_packet_summary(chains.handle_a(), chains.handle_b(), channel_end.value())?;
+
+        assert!(summary.unreceived_packets.is_empty());
+        assert_eq!(summary.unreceived_acks, [1.into()]);
+
+        let denom_b = derive_ibc_denom(
+            &channel.port_b.as_ref(),
+            &channel.channel_id_b.as_ref(),
+            &denom_a,
+        )?;
+
+        chains.node_b.chain_driver().assert_eventual_wallet_amount(
+            &wallet_b.address(),
+            amount,
+            &denom_b.as_ref(),
+        )?;
+
+        Ok(())
+    }
+}
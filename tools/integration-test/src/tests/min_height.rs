@@ -0,0 +1,53 @@
+//! Tests that [`NaryConnectedChains::wait_for_min_height`] returns once all
+//! chains have reached the given height, and fails on timeout otherwise.
+
+use ibc_relayer_types::Height;
+use ibc_test_framework::prelude::*;
+
+#[test]
+fn test_nary_wait_for_min_height() -> Result<(), Error> {
+    run_nary_chain_test(&NaryMinHeightTest)
+}
+
+pub struct NaryMinHeightTest;
+
+impl TestOverrides for NaryMinHeightTest {}
+
+impl NaryChainTest<2> for NaryMinHeightTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+    ) -> Result<(), Error> {
+        let latest_height = chains.chain_handles()[0].query_latest_height()?;
+        let target_height = latest_height.add(3);
+
+        info!("Waiting for all chains to reach height {}", target_height);
+
+        chains.wait_for_min_height(target_height, Duration::from_secs(60))?;
+
+        for handle in chains.chain_handles() {
+            let height = handle.query_latest_height()?;
+
+            assert_eq(
+                "expect every chain to have reached the target height",
+                &(height >= target_height),
+                &true,
+            )?;
+        }
+
+        let unreachable_height = Height::new(latest_height.revision_number(), 1_000_000)
+            .map_err(handle_generic_error)?;
+
+        let res = chains.wait_for_min_height(unreachable_height, Duration::from_secs(3));
+
+        assert_eq(
+            "expect waiting for an unreachable height to time out",
+            &res.is_err(),
+            &true,
+        )?;
+
+        Ok(())
+    }
+}
@@ -5,21 +5,48 @@
    will pick up the definition by default.
 */
 
+pub mod adjacency;
+pub mod block_size;
+pub mod channel_partition;
+pub mod clear_interval;
+pub mod clear_on_start;
 pub mod clear_packet;
 pub mod client_expiration;
 mod client_refresh;
+pub mod client_reuse;
 mod client_settings;
+pub mod client_validity;
+pub mod clock_skew;
+pub mod commitment_prefix;
 pub mod connection_delay;
+pub mod connection_delay_readback;
 pub mod denom_trace;
 pub mod error_events;
 pub mod execute_schedule;
+pub mod filter_deny;
+pub mod filter_reload;
+mod gas_settings;
+pub mod genesis_connection_delay;
+pub mod handshake_initiator;
+pub mod heterogeneous_chains;
+pub mod key_rotation;
+pub mod malformed_proof;
 pub mod memo;
+pub mod min_height;
+pub mod nary_channel_open;
+pub mod nary_pairs;
+pub mod pair_connection_delay;
+pub mod prespawned_chains;
 pub mod python;
 mod query_packet;
+pub mod star_topology;
 pub mod supervisor;
 pub mod tendermint;
 pub mod ternary_transfer;
+pub mod test_timeout;
+pub mod topology;
 pub mod transfer;
+pub mod unique_ids;
 
 #[cfg(any(doc, feature = "ordered"))]
 pub mod ordered_channel;
@@ -27,8 +54,14 @@ pub mod ordered_channel;
 #[cfg(any(doc, feature = "ica"))]
 pub mod ica;
 
+#[cfg(any(doc, feature = "custom-port"))]
+pub mod port_binding;
+
 #[cfg(any(doc, feature = "manual"))]
 pub mod manual;
 
+#[cfg(any(doc, feature = "throughput"))]
+pub mod throughput;
+
 #[cfg(any(doc, feature = "example"))]
 pub mod example;
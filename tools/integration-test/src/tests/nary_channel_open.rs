@@ -0,0 +1,72 @@
+//! Tests that the N-ary channel test harness hands the test case channels
+//! that have completed their handshakes, between all pairs of chains.
+
+use ibc_relayer_types::core::ics04_channel::channel::State as ChannelState;
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::channel::query_channel_end;
+
+#[test]
+fn test_nary_channels_open() -> Result<(), Error> {
+    run_nary_channel_test(&NaryChannelsOpenTest)
+}
+
+pub struct NaryChannelsOpenTest;
+
+impl TestOverrides for NaryChannelsOpenTest {
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl PortsOverride<3> for NaryChannelsOpenTest {}
+
+impl NaryChannelTest<3> for NaryChannelsOpenTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+        channels: NaryConnectedChannels<Handle, 3>,
+    ) -> Result<(), Error> {
+        let handles = chains.chain_handles();
+
+        for (i, row) in channels.channels().iter().enumerate() {
+            for (j, channel) in row.iter().enumerate() {
+                let channel = channel
+                    .as_ref()
+                    .ok_or_else(|| eyre!("expect chains {} and {} to be connected", i, j))?;
+
+                let channel_end_a = query_channel_end(
+                    &handles[i],
+                    &channel.channel_id_a.as_ref(),
+                    &channel.port_a.as_ref(),
+                )?;
+
+                let channel_end_b = query_channel_end(
+                    &handles[j],
+                    &channel.channel_id_b.as_ref(),
+                    &channel.port_b.as_ref(),
+                )?;
+
+                info!(
+                    "channel {} on chain {} is connected to channel {} on chain {}",
+                    channel.channel_id_a, i, channel.channel_id_b, j
+                );
+
+                assert_eq(
+                    &format!("expect channel on chain {} to chain {} to be open", i, j),
+                    channel_end_a.value().state(),
+                    &ChannelState::Open,
+                )?;
+
+                assert_eq(
+                    &format!("expect channel on chain {} to chain {} to be open", j, i),
+                    channel_end_b.value().state(),
+                    &ChannelState::Open,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
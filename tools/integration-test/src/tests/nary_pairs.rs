@@ -0,0 +1,51 @@
+//! Tests that [`NaryConnectedChains::for_each_pair`] visits every
+//! ordered pair of distinct chains.
+
+use std::collections::BTreeSet;
+
+use ibc_test_framework::prelude::*;
+
+#[test]
+fn test_nary_for_each_pair() -> Result<(), Error> {
+    run_nary_chain_test(&NaryForEachPairTest)
+}
+
+pub struct NaryForEachPairTest;
+
+impl TestOverrides for NaryForEachPairTest {}
+
+impl NaryChainTest<3> for NaryForEachPairTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+    ) -> Result<(), Error> {
+        let handles = chains.chain_handles();
+
+        let mut visited = BTreeSet::new();
+        let mut mismatched = Vec::new();
+
+        chains.for_each_pair(|i, j, handle_a, handle_b| {
+            if handle_a.id() != handles[i].id() || handle_b.id() != handles[j].id() {
+                mismatched.push((i, j));
+            }
+
+            visited.insert((i, j));
+        });
+
+        assert_eq(
+            "expect every ordered pair of distinct chains to be visited",
+            &visited,
+            &BTreeSet::from([(0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1)]),
+        )?;
+
+        assert_eq(
+            "expect the handles to match the visited positions",
+            &mismatched,
+            &Vec::new(),
+        )?;
+
+        Ok(())
+    }
+}
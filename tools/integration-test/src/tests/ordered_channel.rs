@@ -1,5 +1,9 @@
 use ibc_test_framework::ibc::denom::derive_ibc_denom;
 use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::misordered_recv::MisorderedRecvChainHandle;
+use ibc_test_framework::relayer::packet::{
+    query_next_sequence_receive, relay_and_assert_ordered, relay_and_assert_ordered_with,
+};
 use ibc_test_framework::util::random::random_u64_range;
 
 #[test]
@@ -107,3 +111,112 @@ impl BinaryChannelTest for OrderedChannelTest {
         })
     }
 }
+
+#[test]
+fn test_nary_ordered_channel() -> Result<(), Error> {
+    run_nary_channel_test(&NaryOrderedChannelTest)
+}
+
+#[test]
+fn test_out_of_order_recv_detected() -> Result<(), Error> {
+    run_binary_channel_test(&OutOfOrderRecvTest)
+}
+
+pub struct NaryOrderedChannelTest;
+
+impl TestOverrides for NaryOrderedChannelTest {
+    fn modify_test_config(&self, config: &mut TestConfig) {
+        config.bootstrap_with_random_ids = false;
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+
+    fn channel_order(&self) -> Order {
+        Order::Ordered
+    }
+}
+
+impl PortsOverride<3> for NaryOrderedChannelTest {}
+
+impl NaryChannelTest<3> for NaryOrderedChannelTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+        channels: NaryConnectedChannels<Handle, 3>,
+    ) -> Result<(), Error> {
+        relay_and_assert_ordered(
+            &chains.connected_chains_at::<0, 1>()?,
+            &channels.channel_at::<0, 1>()?,
+            5,
+        )?;
+
+        relay_and_assert_ordered(
+            &chains.connected_chains_at::<1, 2>()?,
+            &channels.channel_at::<1, 2>()?,
+            5,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub struct OutOfOrderRecvTest;
+
+impl TestOverrides for OutOfOrderRecvTest {
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+
+    fn channel_order(&self) -> Order {
+        Order::Ordered
+    }
+}
+
+impl BinaryChannelTest for OutOfOrderRecvTest {
+    fn run<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: ConnectedChains<ChainA, ChainB>,
+        channel: ConnectedChannel<ChainA, ChainB>,
+    ) -> Result<(), Error> {
+        let start_sequence = query_next_sequence_receive(
+            chains.handle_b(),
+            &channel.port_b.as_ref(),
+            &channel.channel_id_b.as_ref(),
+        )?;
+
+        info!("Relaying packets received out of order, which should be detected");
+
+        let handle_b = MisorderedRecvChainHandle::new(chains.handle_b().clone());
+
+        let result = relay_and_assert_ordered_with(&chains, &channel, handle_b, 3);
+
+        assert!(
+            result.is_err(),
+            "expect relaying packets out of order to be detected"
+        );
+
+        let sequence = query_next_sequence_receive(
+            chains.handle_b(),
+            &channel.port_b.as_ref(),
+            &channel.channel_id_b.as_ref(),
+        )?;
+
+        assert_eq(
+            "expect no packet to be received out of order",
+            &sequence,
+            &start_sequence,
+        )?;
+
+        info!("Relaying the pending packets in order, which should succeed");
+
+        relay_and_assert_ordered(&chains, &channel, 2)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+//! Tests that the N-ary connections between different pairs of chains
+//! can be bootstrapped with different connection delays.
+
+use ibc_test_framework::prelude::*;
+
+const HUB_CONNECTION_DELAY: Duration = Duration::from_secs(10);
+
+#[test]
+fn test_nary_pair_connection_delay() -> Result<(), Error> {
+    run_nary_connection_test(&NaryPairConnectionDelayTest)
+}
+
+pub struct NaryPairConnectionDelayTest;
+
+impl TestOverrides for NaryPairConnectionDelayTest {
+    fn pair_connection_delay(&self, chain_a: usize, _chain_b: usize) -> Duration {
+        // Only the connections of the hub at position 0 are delayed
+        if chain_a == 0 {
+            HUB_CONNECTION_DELAY
+        } else {
+            self.connection_delay()
+        }
+    }
+}
+
+impl NaryConnectionTest<3> for NaryPairConnectionDelayTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        _chains: NaryConnectedChains<Handle, 3>,
+        connections: NaryConnectedConnections<Handle, 3>,
+    ) -> Result<(), Error> {
+        let delay_0_1 = connections.delay_at::<0, 1>()?;
+        let delay_0_2 = connections.delay_at::<0, 2>()?;
+        let delay_1_2 = connections.delay_at::<1, 2>()?;
+
+        assert_eq(
+            "expect the connections of the hub to be delayed",
+            &delay_0_1,
+            &HUB_CONNECTION_DELAY,
+        )?;
+
+        assert_eq(
+            "expect the connections of the hub to be delayed",
+            &delay_0_2,
+            &HUB_CONNECTION_DELAY,
+        )?;
+
+        assert_eq(
+            "expect the connection between the spokes to have the default delay",
+            &delay_1_2,
+            &self.connection_delay(),
+        )?;
+
+        assert_not_eq(
+            "expect the connections to have different delays",
+            &delay_0_1,
+            &delay_1_2,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+//! Tests that the channels of each chain can be bound to a custom port.
+//!
+//! This requires the chain at position 0 to run an IBC module bound to
+//! the `custommod` port, and is only enabled with the `custom-port` feature.
+
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::channel::query_channel_end;
+use std::str::FromStr;
+
+#[test]
+fn test_custom_port_binding() -> Result<(), Error> {
+    run_binary_channel_test(&CustomPortBindingTest)
+}
+
+pub struct CustomPortBindingTest;
+
+impl TestOverrides for CustomPortBindingTest {
+    fn port_binding(&self, chain_index: usize) -> PortId {
+        if chain_index == 0 {
+            PortId::from_str("custommod").unwrap()
+        } else {
+            PortId::transfer()
+        }
+    }
+}
+
+impl BinaryChannelTest for CustomPortBindingTest {
+    fn run<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: ConnectedChains<ChainA, ChainB>,
+        channel: ConnectedChannel<ChainA, ChainB>,
+    ) -> Result<(), Error> {
+        let channel_end_a = query_channel_end(
+            chains.handle_a(),
+            &channel.channel_id_a.as_ref(),
+            &channel.port_a.as_ref(),
+        )?;
+
+        let channel_end_b = query_channel_end(
+            chains.handle_b(),
+            &channel.channel_id_b.as_ref(),
+            &channel.port_b.as_ref(),
+        )?;
+
+        assert_eq(
+            "expect the channel on chain A to be bound to the custom port",
+            channel.port_a.value(),
+            &PortId::from_str("custommod").unwrap(),
+        )?;
+
+        assert_eq(
+            "expect the channel end on chain B to have the custom port as counterparty",
+            channel_end_b.value().counterparty().port_id(),
+            &PortId::from_str("custommod").unwrap(),
+        )?;
+
+        assert_eq(
+            "expect the channel end on chain A to have the transfer port as counterparty",
+            channel_end_a.value().counterparty().port_id(),
+            &PortId::transfer(),
+        )?;
+
+        Ok(())
+    }
+}
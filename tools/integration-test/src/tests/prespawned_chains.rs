@@ -0,0 +1,117 @@
+use ibc_relayer::chain::handle::BaseChainHandle;
+use ibc_test_framework::bootstrap::binary::chain::{
+    add_keys_to_chain_handle, bootstrap_foreign_client,
+};
+use ibc_test_framework::prelude::*;
+
+/// A test to exercise the construction of [`NaryConnectedChains`] from
+/// chain handles spawned by the test itself rather than by the framework,
+/// running a connection test on them.
+#[test]
+fn test_nary_prespawned_chains() -> Result<(), Error> {
+    run_nary_chain_test(&PrespawnedChainsTest)
+}
+
+struct PrespawnedChainsTest;
+
+struct TrivialConnectionTest;
+
+impl TestOverrides for PrespawnedChainsTest {
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl TestOverrides for TrivialConnectionTest {}
+
+impl NaryChainTest<3> for PrespawnedChainsTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        config: &TestConfig,
+        relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+    ) -> Result<(), Error> {
+        let full_nodes = chains.full_nodes().to_vec();
+
+        // Spawn new chain handles for the running full nodes from a registry
+        // of our own, independently of the handles spawned by the framework.
+        let registry = SharedRegistry::<BaseChainHandle>::new(relayer.config.clone());
+
+        let mut handles = Vec::new();
+
+        for node in full_nodes.iter() {
+            let handle = registry
+                .get_or_spawn(&node.chain_driver.chain_id)
+                .map_err(handle_generic_error)?;
+
+            add_keys_to_chain_handle(&handle, &node.wallets)?;
+
+            handles.push(handle);
+        }
+
+        let mut foreign_clients = Vec::new();
+
+        for handle_a in handles.iter() {
+            let mut foreign_clients_b = Vec::new();
+
+            for handle_b in handles.iter() {
+                foreign_clients_b.push(bootstrap_foreign_client(
+                    handle_a,
+                    handle_b,
+                    Default::default(),
+                )?);
+            }
+
+            foreign_clients.push(foreign_clients_b);
+        }
+
+        let too_few = NaryConnectedChains::<BaseChainHandle, 3>::from_handles(
+            handles[..2].to_vec(),
+            full_nodes[..2].to_vec(),
+            foreign_clients.clone(),
+        );
+
+        assert!(too_few.is_err());
+
+        let prespawned = NaryConnectedChains::<BaseChainHandle, 3>::from_handles(
+            handles,
+            full_nodes,
+            foreign_clients,
+        )?;
+
+        for (handle, expected) in prespawned
+            .chain_handles()
+            .iter()
+            .zip(chains.chain_handles())
+        {
+            assert_eq(
+                "chain handles should be kept in order",
+                &handle.id(),
+                &expected.id(),
+            )?;
+        }
+
+        RunNaryConnectionTest::new(&TrivialConnectionTest).run(config, relayer, prespawned)
+    }
+}
+
+impl NaryConnectionTest<3> for TrivialConnectionTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+        connections: NaryConnectedConnections<Handle, 3>,
+    ) -> Result<(), Error> {
+        let connection = connections.connection_at::<0, 2>()?;
+
+        info!(
+            "connected chain {} to chain {} over {}",
+            chains.chain_handles()[0].id(),
+            chains.chain_handles()[2].id(),
+            connection.connection_id_a
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//! Tests that N-ary connections and channels can be bootstrapped in a star
+//! topology, where the hub chain is connected to each spoke chain, but the
+//! spoke chains are not connected with each other.
+
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::types::nary::topology::Topology;
+
+const HUB: usize = 0;
+
+#[test]
+fn test_nary_star_topology() -> Result<(), Error> {
+    run_nary_connection_test(&NaryStarTopologyTest)
+}
+
+#[test]
+fn test_nary_star_topology_channels() -> Result<(), Error> {
+    run_nary_channel_test(&NaryStarTopologyTest)
+}
+
+pub struct NaryStarTopologyTest;
+
+impl TestOverrides for NaryStarTopologyTest {
+    fn topology(&self) -> Topology {
+        Topology::Star { hub: HUB }
+    }
+
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl NaryConnectionTest<4> for NaryStarTopologyTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        _chains: NaryConnectedChains<Handle, 4>,
+        connections: NaryConnectedConnections<Handle, 4>,
+    ) -> Result<(), Error> {
+        assert_eq(
+            "expect the hub to be connected to each of the 3 spokes",
+            &connections.connection_count(),
+            &3,
+        )?;
+
+        let matrix = connections.adjacency_matrix();
+
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, connected) in row.iter().enumerate() {
+                assert_eq(
+                    &format!("expect connection between chains {} and {}", i, j),
+                    connected,
+                    &(i != j && (i == HUB || j == HUB)),
+                )?;
+            }
+        }
+
+        connections.connection_at::<0, 3>()?;
+
+        assert_eq(
+            "expect no connection between two spokes",
+            &connections.connection_at::<1, 2>().is_err(),
+            &true,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl PortsOverride<3> for NaryStarTopologyTest {}
+
+impl NaryChannelTest<3> for NaryStarTopologyTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        _chains: NaryConnectedChains<Handle, 3>,
+        channels: NaryConnectedChannels<Handle, 3>,
+    ) -> Result<(), Error> {
+        channels.channel_at::<0, 1>()?;
+        channels.channel_at::<2, 0>()?;
+
+        assert_eq(
+            "expect no channel between two spokes",
+            &channels.channel_at::<1, 2>().is_err(),
+            &true,
+        )?;
+
+        Ok(())
+    }
+}
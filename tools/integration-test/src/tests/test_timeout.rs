@@ -0,0 +1,51 @@
+//! Tests that an N-ary connection test exceeding its timeout is aborted
+//! with a timeout error, instead of hanging indefinitely.
+
+use ibc_test_framework::error::ErrorDetail;
+use ibc_test_framework::prelude::*;
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn test_nary_test_timeout() -> Result<(), Error> {
+    match run_nary_connection_test(&NaryTestTimeoutTest) {
+        Err(e) => match e.detail() {
+            ErrorDetail::Timeout(_) => Ok(()),
+            _ => Err(e),
+        },
+        Ok(()) => Err(Error::assertion(
+            "expect the test to be aborted with a timeout error".to_string(),
+        )),
+    }
+}
+
+pub struct NaryTestTimeoutTest;
+
+impl TestOverrides for NaryTestTimeoutTest {
+    fn test_timeout(&self) -> Option<Duration> {
+        Some(TEST_TIMEOUT)
+    }
+}
+
+impl NaryConnectionTest<2> for NaryTestTimeoutTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 2>,
+        _connections: NaryConnectedConnections<Handle, 2>,
+    ) -> Result<(), Error> {
+        let chain = chains.chain_handle_at::<0>()?;
+
+        // Keep querying the chain past the timeout, until its full node
+        // is killed and the query fails.
+        loop {
+            chain
+                .value()
+                .query_latest_height()
+                .map_err(handle_generic_error)?;
+
+            sleep(Duration::from_secs(1));
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Measures the packet relaying throughput across multiple pairs of
+//! chains in an N-ary setup, to help catching performance regressions.
+
+use ibc_test_framework::prelude::*;
+use ibc_test_framework::relayer::throughput::measure_throughput;
+
+#[test]
+fn test_nary_relaying_throughput() -> Result<(), Error> {
+    run_nary_channel_test(&NaryThroughputTest)
+}
+
+pub struct NaryThroughputTest;
+
+impl TestOverrides for NaryThroughputTest {
+    fn modify_relayer_config(&self, config: &mut Config) {
+        config.mode.clients.misbehaviour = false;
+    }
+}
+
+impl PortsOverride<3> for NaryThroughputTest {}
+
+impl NaryChannelTest<3> for NaryThroughputTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        chains: NaryConnectedChains<Handle, 3>,
+        channels: NaryConnectedChannels<Handle, 3>,
+    ) -> Result<(), Error> {
+        let pairs = [(0, 1), (1, 2), (2, 0)];
+
+        let report = measure_throughput(&chains, &channels, &pairs, Duration::from_secs(30))?;
+
+        for (chain_a, chain_b) in pairs {
+            let throughput = report.packets_per_second(chain_a, chain_b);
+
+            match throughput {
+                Some(throughput) if throughput > 0.0 => {}
+                _ => {
+                    return Err(Error::assertion(format!(
+                        "expect non-zero throughput from chain {} to chain {}, but got {:?}",
+                        chain_a, chain_b, throughput
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+//! Tests that N-ary chains can be bootstrapped declaratively from a
+//! topology spec file.
+
+use ibc_test_framework::bootstrap::nary::topology::bootstrap_nary_from_spec;
+use ibc_test_framework::chain::builder::ChainBuilder;
+use ibc_test_framework::framework::base::{run_basic_test, BasicTest};
+use ibc_test_framework::prelude::*;
+
+#[test]
+fn test_nary_topology_from_spec() -> Result<(), Error> {
+    run_basic_test(&NaryTopologyTest)
+}
+
+pub struct NaryTopologyTest;
+
+impl TestOverrides for NaryTopologyTest {}
+
+impl BasicTest for NaryTopologyTest {
+    fn run(&self, config: &TestConfig, builder: &ChainBuilder) -> Result<(), Error> {
+        let spec_path = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/nary-topology.toml");
+
+        let (_relayer, topology) = bootstrap_nary_from_spec(spec_path, config, builder)?;
+
+        assert_eq(
+            "expect three chains to be bootstrapped",
+            &topology.chains.chain_handles().len(),
+            &3,
+        )?;
+
+        assert_eq(
+            "expect the chains to be connected as declared in the spec",
+            &topology.edges(),
+            &vec![(0, 1), (1, 2)],
+        )?;
+
+        assert_eq(
+            "expect no connection between the first and the last chain",
+            &topology.connection_between(0, 2).is_none(),
+            &true,
+        )?;
+
+        let connection = topology.connection_between(1, 0).ok_or_else(|| {
+            Error::assertion("expect a connection from beta to alpha".to_string())
+        })?;
+
+        assert_eq(
+            "expect the flipped connection to be from beta to alpha",
+            &connection.connection.a_chain().id(),
+            &topology.chains.chain_handles()[1].id(),
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Tests that the connections bootstrapped between N-ary chains use
+//! distinct connection IDs on each chain, and that a collision is detected.
+
+use ibc_test_framework::prelude::*;
+
+#[test]
+fn test_nary_unique_connection_ids() -> Result<(), Error> {
+    run_nary_connection_test(&NaryUniqueConnectionIdsTest)
+}
+
+pub struct NaryUniqueConnectionIdsTest;
+
+impl TestOverrides for NaryUniqueConnectionIdsTest {
+    fn should_spawn_supervisor(&self) -> bool {
+        false
+    }
+}
+
+impl NaryConnectionTest<3> for NaryUniqueConnectionIdsTest {
+    fn run<Handle: ChainHandle>(
+        &self,
+        _config: &TestConfig,
+        _relayer: RelayerDriver,
+        _chains: NaryConnectedChains<Handle, 3>,
+        connections: NaryConnectedConnections<Handle, 3>,
+    ) -> Result<(), Error> {
+        connections.assert_unique_ids()?;
+
+        info!("injecting a connection ID collision on the chain at position 0");
+
+        let mut raw_connections = connections
+            .connections()
+            .iter()
+            .map(|connections_b| connections_b.to_vec())
+            .collect::<Vec<_>>();
+
+        let colliding_id = raw_connections[0][1]
+            .as_ref()
+            .map(|connection| connection.connection_id_a.clone())
+            .ok_or_else(|| eyre!("expect chains 0 and 1 to be connected"))?;
+
+        if let Some(connection) = raw_connections[0][2].as_mut() {
+            connection.connection_id_a = colliding_id;
+        }
+
+        let colliding_connections = NaryConnectedConnections::<Handle, 3>::new(raw_connections)?;
+
+        assert_eq(
+            "expect the injected connection ID collision to be detected",
+            &colliding_connections.assert_unique_ids().is_err(),
+            &true,
+        )?;
+
+        Ok(())
+    }
+}
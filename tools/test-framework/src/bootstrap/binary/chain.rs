@@ -82,6 +82,7 @@ pub fn bootstrap_chains_with_full_nodes(
         config,
         registry,
         hang_on_fail: test_config.hang_on_fail,
+        supervisors: Default::default(),
     };
 
     let chains = ConnectedChains::new(
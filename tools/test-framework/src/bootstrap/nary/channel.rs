@@ -22,7 +22,8 @@ use crate::util::array::{assert_same_dimension, into_nested_vec};
 
 /**
    Bootstrap a dynamic number of channels based on the number of
-   connections in `DynamicConnectedConnections`.
+   connections in `DynamicConnectedConnections`, with no channel
+   between the pairs of chains that have no connection.
 */
 pub fn bootstrap_channels_with_connections_dynamic<Handle: ChainHandle>(
     connections: DynamicConnectedConnections<Handle>,
@@ -36,12 +37,20 @@ pub fn bootstrap_channels_with_connections_dynamic<Handle: ChainHandle>(
     assert_same_dimension(size, connections.connections())?;
     assert_same_dimension(size, ports)?;
 
-    let mut channels: Vec<Vec<ConnectedChannel<Handle, Handle>>> = Vec::new();
+    let mut channels: Vec<Vec<Option<ConnectedChannel<Handle, Handle>>>> = Vec::new();
 
     for (i, connections_b) in connections.connections().iter().enumerate() {
-        let mut channels_b: Vec<ConnectedChannel<Handle, Handle>> = Vec::new();
+        let mut channels_b: Vec<Option<ConnectedChannel<Handle, Handle>>> = Vec::new();
 
         for (j, connection) in connections_b.iter().enumerate() {
+            let connection = match connection {
+                Some(connection) => connection,
+                None => {
+                    channels_b.push(None);
+                    continue;
+                }
+            };
+
             if i <= j {
                 let chain_a = &chains[i];
                 let chain_b = &chains[j];
@@ -62,10 +71,10 @@ pub fn bootstrap_channels_with_connections_dynamic<Handle: ChainHandle>(
                     bootstrap_options,
                 )?;
 
-                channels_b.push(channel);
+                channels_b.push(Some(channel));
             } else {
                 let counter_channel = &channels[j][i];
-                let channel = counter_channel.clone().flip();
+                let channel = counter_channel.clone().map(|channel| channel.flip());
 
                 channels_b.push(channel);
             }
@@ -74,7 +83,7 @@ pub fn bootstrap_channels_with_connections_dynamic<Handle: ChainHandle>(
         channels.push(channels_b);
     }
 
-    Ok(DynamicConnectedChannels::new(channels))
+    Ok(DynamicConnectedChannels::new_sparse(channels))
 }
 
 /**
@@ -4,15 +4,21 @@
 
 use core::convert::TryInto;
 use core::time::Duration;
+use eyre::eyre;
 use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{PageRequest, QueryClientStatesRequest};
 use ibc_relayer::foreign_client::ForeignClient;
+use ibc_relayer_types::core::ics02_client::client_state::ClientState;
 
 use crate::bootstrap::binary::connection::{bootstrap_connection, BootstrapConnectionOptions};
-use crate::error::Error;
+use crate::chain::ext::bootstrap::ChainBootstrapMethodsExt;
+use crate::error::{handle_generic_error, Error};
 use crate::types::binary::connection::ConnectedConnection;
 use crate::types::binary::foreign_client::ForeignClientPair;
 use crate::types::nary::connection::{ConnectedConnections, DynamicConnectedConnections};
 use crate::types::nary::foreign_client::ForeignClientPairs;
+use crate::types::nary::topology::Topology;
+use crate::types::single::node::FullNode;
 use crate::util::array::assert_same_dimension;
 
 /**
@@ -24,31 +30,96 @@ pub fn bootstrap_connections_dynamic<Handle: ChainHandle>(
     connection_delay: Duration,
     bootstrap_with_random_ids: bool,
 ) -> Result<DynamicConnectedConnections<Handle>, Error> {
+    bootstrap_connections_dynamic_with_initiator(
+        foreign_clients,
+        |_, _| connection_delay,
+        bootstrap_with_random_ids,
+        |chain_a, chain_b| chain_a.min(chain_b),
+    )
+}
+
+/**
+   Bootstrap a dynamic number of connections based on the
+   given foreign client NxN matrix, with the connection between each
+   pair of chains `i < j` having the delay returned by
+   `connection_delay(i, j)`, and its handshake initiated by the chain at
+   the position returned by `initiator(i, j)`.
+*/
+pub fn bootstrap_connections_dynamic_with_initiator<Handle: ChainHandle>(
+    foreign_clients: &Vec<Vec<ForeignClient<Handle, Handle>>>,
+    connection_delay: impl Fn(usize, usize) -> Duration,
+    bootstrap_with_random_ids: bool,
+    initiator: impl Fn(usize, usize) -> usize,
+) -> Result<DynamicConnectedConnections<Handle>, Error> {
+    let connections = bootstrap_connections_dynamic_with_topology(
+        foreign_clients,
+        &Topology::Complete,
+        connection_delay,
+        bootstrap_with_random_ids,
+        initiator,
+    )?;
+
+    Ok(DynamicConnectedConnections::new_sparse(connections))
+}
+
+/**
+   Bootstrap connections between the pairs of chains that are connected
+   in the given [`Topology`], based on the given foreign client NxN matrix,
+   with the delays and handshake initiators as in
+   [`bootstrap_connections_dynamic_with_initiator`].
+
+   The entry `[i][j]` of the returned matrix is `None` if the chains at
+   position `i` and `j` are not connected in the topology.
+*/
+pub fn bootstrap_connections_dynamic_with_topology<Handle: ChainHandle>(
+    foreign_clients: &Vec<Vec<ForeignClient<Handle, Handle>>>,
+    topology: &Topology,
+    connection_delay: impl Fn(usize, usize) -> Duration,
+    bootstrap_with_random_ids: bool,
+    initiator: impl Fn(usize, usize) -> usize,
+) -> Result<Vec<Vec<Option<ConnectedConnection<Handle, Handle>>>>, Error> {
     let size = foreign_clients.len();
 
     assert_same_dimension(size, foreign_clients)?;
 
-    let mut connections: Vec<Vec<ConnectedConnection<Handle, Handle>>> = Vec::new();
+    let mut connections: Vec<Vec<Option<ConnectedConnection<Handle, Handle>>>> = Vec::new();
 
     for (i, foreign_clients_b) in foreign_clients.iter().enumerate() {
-        let mut connections_b: Vec<ConnectedConnection<Handle, Handle>> = Vec::new();
+        let mut connections_b: Vec<Option<ConnectedConnection<Handle, Handle>>> = Vec::new();
 
         for (j, foreign_client) in foreign_clients_b.iter().enumerate() {
-            if i <= j {
+            if !topology.connects(size, i, j) {
+                connections_b.push(None);
+            } else if i <= j {
                 let counter_foreign_client = &foreign_clients[j][i];
                 let foreign_clients =
                     ForeignClientPair::new(foreign_client.clone(), counter_foreign_client.clone());
 
                 let bootstrap_options = BootstrapConnectionOptions::default()
-                    .connection_delay(connection_delay)
+                    .connection_delay(connection_delay(i, j))
                     .bootstrap_with_random_ids(bootstrap_with_random_ids);
 
-                let connection = bootstrap_connection(&foreign_clients, bootstrap_options)?;
+                let initiator = initiator(i, j);
 
-                connections_b.push(connection);
+                let connection = if initiator == i {
+                    bootstrap_connection(&foreign_clients, bootstrap_options)?
+                } else if initiator == j {
+                    bootstrap_connection(&foreign_clients.flip(), bootstrap_options)?.flip()
+                } else {
+                    return Err(Error::generic(eyre!(
+                        "handshake initiator {} is not part of the chain pair {}/{}",
+                        initiator,
+                        i,
+                        j
+                    )));
+                };
+
+                connections_b.push(Some(connection));
             } else {
                 let counter_connection = &connections[j][i];
-                let connection = counter_connection.clone().flip();
+                let connection = counter_connection
+                    .clone()
+                    .map(|connection| connection.flip());
 
                 connections_b.push(connection);
             }
@@ -57,7 +128,104 @@ pub fn bootstrap_connections_dynamic<Handle: ChainHandle>(
         connections.push(connections_b);
     }
 
-    Ok(DynamicConnectedConnections::new(connections))
+    Ok(connections)
+}
+
+/**
+   Replace each foreign client in the given NxN matrix with the first
+   client already existing on its destination chain that tracks the same
+   source chain, so that connections bootstrapped with the result are
+   bound to the existing clients instead of new ones.
+
+   Foreign clients with no matching client on their destination chain
+   are kept as is.
+*/
+pub fn reuse_existing_clients<Handle: ChainHandle>(
+    foreign_clients: &Vec<Vec<ForeignClient<Handle, Handle>>>,
+) -> Result<Vec<Vec<ForeignClient<Handle, Handle>>>, Error> {
+    let size = foreign_clients.len();
+
+    assert_same_dimension(size, foreign_clients)?;
+
+    let mut reused_clients = Vec::new();
+
+    for foreign_clients_b in foreign_clients.iter() {
+        let mut reused_clients_b = Vec::new();
+
+        for foreign_client in foreign_clients_b.iter() {
+            let src_chain_id = foreign_client.src_chain().id();
+
+            let mut existing_clients = foreign_client
+                .dst_chain()
+                .query_clients(QueryClientStatesRequest {
+                    pagination: Some(PageRequest::all()),
+                })
+                .map_err(handle_generic_error)?
+                .into_iter()
+                .filter(|client| client.client_state.chain_id() == src_chain_id)
+                .map(|client| client.client_id)
+                .collect::<Vec<_>>();
+
+            // Pick the oldest client, by the counter at the end of its identifier.
+            existing_clients.sort_by_key(|client_id| {
+                client_id
+                    .as_str()
+                    .rsplit('-')
+                    .next()
+                    .and_then(|counter| counter.parse::<u64>().ok())
+            });
+
+            let reused_client = match existing_clients.into_iter().next() {
+                Some(client_id) => ForeignClient::restore(
+                    client_id,
+                    foreign_client.dst_chain(),
+                    foreign_client.src_chain(),
+                ),
+                None => foreign_client.clone(),
+            };
+
+            reused_clients_b.push(reused_client);
+        }
+
+        reused_clients.push(reused_clients_b);
+    }
+
+    Ok(reused_clients)
+}
+
+/**
+   Derive a connection delay shared by all the given chains from their
+   genesis, as the longest `max_expected_time_per_block` IBC connection
+   parameter among them.
+*/
+pub fn connection_delay_from_genesis(full_nodes: &[FullNode]) -> Result<Duration, Error> {
+    let mut connection_delay = Duration::ZERO;
+
+    for node in full_nodes {
+        let genesis: serde_json::Value =
+            serde_json::from_str(&node.chain_driver.read_file("config/genesis.json")?)
+                .map_err(handle_generic_error)?;
+
+        let max_expected_time_per_block = genesis
+            .get("app_state")
+            .and_then(|state| state.get("ibc"))
+            .and_then(|ibc| ibc.get("connection_genesis"))
+            .and_then(|connection| connection.get("params"))
+            .and_then(|params| params.get("max_expected_time_per_block"))
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to find max_expected_time_per_block in the genesis of chain {}",
+                    node.chain_driver.chain_id
+                )
+            })?
+            .parse::<u64>()
+            .map_err(handle_generic_error)?;
+
+        connection_delay = connection_delay.max(Duration::from_nanos(max_expected_time_per_block));
+    }
+
+    Ok(connection_delay)
 }
 
 pub fn bootstrap_connections<Handle: ChainHandle, const SIZE: usize>(
@@ -73,3 +241,70 @@ pub fn bootstrap_connections<Handle: ChainHandle, const SIZE: usize>(
 
     connections.try_into()
 }
+
+pub fn bootstrap_connections_with_initiator<Handle: ChainHandle, const SIZE: usize>(
+    foreign_clients: ForeignClientPairs<Handle, SIZE>,
+    connection_delay: impl Fn(usize, usize) -> Duration,
+    bootstrap_with_random_ids: bool,
+    initiator: impl Fn(usize, usize) -> usize,
+) -> Result<ConnectedConnections<Handle, SIZE>, Error> {
+    let connections = bootstrap_connections_dynamic_with_initiator(
+        &foreign_clients.into_nested_vec(),
+        connection_delay,
+        bootstrap_with_random_ids,
+        initiator,
+    )?;
+
+    connections.try_into()
+}
+
+/**
+   Bootstrap a fixed number of connections between the pairs of chains
+   that are connected in the given [`Topology`], as with
+   [`bootstrap_connections_dynamic_with_topology`].
+*/
+pub fn bootstrap_connections_with_topology<Handle: ChainHandle, const SIZE: usize>(
+    foreign_clients: ForeignClientPairs<Handle, SIZE>,
+    topology: &Topology,
+    connection_delay: impl Fn(usize, usize) -> Duration,
+    bootstrap_with_random_ids: bool,
+    initiator: impl Fn(usize, usize) -> usize,
+) -> Result<ConnectedConnections<Handle, SIZE>, Error> {
+    let connections = bootstrap_connections_dynamic_with_topology(
+        &foreign_clients.into_nested_vec(),
+        topology,
+        connection_delay,
+        bootstrap_with_random_ids,
+        initiator,
+    )?;
+
+    ConnectedConnections::new(connections)
+}
+
+/**
+   Bootstrap connections the same way as
+   [`bootstrap_connections_with_initiator`], except that each connection
+   is bound to the client already existing between its chain pair, as
+   found by [`reuse_existing_clients`], so that bootstrapping connections
+   repeatedly does not require fresh clients. Only the pairs of chains
+   connected in the given [`Topology`] are connected.
+*/
+pub fn bootstrap_connections_with_client_reuse<Handle: ChainHandle, const SIZE: usize>(
+    foreign_clients: ForeignClientPairs<Handle, SIZE>,
+    topology: &Topology,
+    connection_delay: impl Fn(usize, usize) -> Duration,
+    bootstrap_with_random_ids: bool,
+    initiator: impl Fn(usize, usize) -> usize,
+) -> Result<ConnectedConnections<Handle, SIZE>, Error> {
+    let foreign_clients = reuse_existing_clients(&foreign_clients.into_nested_vec())?;
+
+    let connections = bootstrap_connections_dynamic_with_topology(
+        &foreign_clients,
+        topology,
+        connection_delay,
+        bootstrap_with_random_ids,
+        initiator,
+    )?;
+
+    ConnectedConnections::new(connections)
+}
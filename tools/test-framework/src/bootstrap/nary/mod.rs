@@ -11,3 +11,4 @@
 pub mod chain;
 pub mod channel;
 pub mod connection;
+pub mod topology;
@@ -0,0 +1,65 @@
+/*!
+   Functions for bootstrapping N-ary chains from a declarative
+   topology spec.
+*/
+
+use ibc_relayer::chain::handle::ChainHandle;
+use std::path::Path;
+
+use crate::bootstrap::nary::chain::boostrap_chains_with_any_nodes;
+use crate::bootstrap::nary::connection::bootstrap_connections_dynamic_with_topology;
+use crate::bootstrap::single::bootstrap_single_node;
+use crate::chain::builder::ChainBuilder;
+use crate::error::Error;
+use crate::relayer::driver::RelayerDriver;
+use crate::types::config::TestConfig;
+use crate::types::nary::connection::DynamicConnectedConnections;
+use crate::types::nary::topology::{ConnectedTopology, NaryTopologySpec};
+
+/**
+   Load the [`NaryTopologySpec`] at `path`, and bootstrap a full node
+   for each of the declared chains, together with connections between
+   the declared pairs of chains.
+
+   Foreign clients are still created between every pair of chains, as
+   done by [`boostrap_chains_with_any_nodes`].
+*/
+pub fn bootstrap_nary_from_spec(
+    path: impl AsRef<Path>,
+    test_config: &TestConfig,
+    builder: &ChainBuilder,
+) -> Result<(RelayerDriver, ConnectedTopology<impl ChainHandle>), Error> {
+    let spec = NaryTopologySpec::load(path)?;
+
+    let mut nodes = Vec::new();
+
+    for prefix in spec.chains.iter() {
+        let node = bootstrap_single_node(
+            builder,
+            prefix,
+            test_config.bootstrap_with_random_ids,
+            |_| Ok(()),
+            |_| Ok(()),
+        )?;
+
+        nodes.push(node);
+    }
+
+    let (relayer, chains) = boostrap_chains_with_any_nodes(test_config, nodes, |_| {})?;
+
+    let connections = bootstrap_connections_dynamic_with_topology(
+        &chains.foreign_clients,
+        &spec.topology(),
+        |_, _| Default::default(),
+        test_config.bootstrap_with_random_ids,
+        |chain_a, chain_b| chain_a.min(chain_b),
+    )?;
+
+    Ok((
+        relayer,
+        ConnectedTopology {
+            chains,
+            connections: DynamicConnectedConnections::new_sparse(connections),
+        },
+    ))
+}
@@ -42,7 +42,7 @@ pub fn bootstrap_single_node(
     config_modifier: impl FnOnce(&mut toml::Value) -> Result<(), Error>,
     genesis_modifier: impl FnOnce(&mut serde_json::Value) -> Result<(), Error>,
 ) -> Result<FullNode, Error> {
-    let stake_denom = Denom::base("stake");
+    let mut stake_denom = Denom::base("stake");
 
     let denom = if use_random_id {
         Denom::base(&format!("coin{:x}", random_u32()))
@@ -59,7 +59,21 @@ pub fn bootstrap_single_node(
 
     chain_driver.initialize()?;
 
-    chain_driver.update_genesis_file("genesis.json", genesis_modifier)?;
+    chain_driver.update_genesis_file("genesis.json", |genesis| {
+        genesis_modifier(genesis)?;
+
+        // The genesis modifier may have changed the staking denom, in which
+        // case the validator must bond, and the wallets be funded with, that
+        // denom instead.
+        if let Some(bond_denom) = genesis
+            .pointer("/app_state/staking/params/bond_denom")
+            .and_then(|bond_denom| bond_denom.as_str())
+        {
+            stake_denom = Denom::base(bond_denom);
+        }
+
+        Ok(())
+    })?;
 
     let validator = add_wallet(&chain_driver, "validator", use_random_id)?;
     let relayer = add_wallet(&chain_driver, "relayer", use_random_id)?;
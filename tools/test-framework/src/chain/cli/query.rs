@@ -79,6 +79,41 @@ pub fn query_recipient_transactions(
     }
 }
 
+/**
+    Query for the transactions on `Chain` that initiated the handshake
+    of the connection with the given ID.
+*/
+pub fn query_connection_open_init_transactions(
+    chain_id: &str,
+    command_path: &str,
+    rpc_listen_address: &str,
+    connection_id: &str,
+) -> Result<json::Value, Error> {
+    let res = simple_exec(
+        chain_id,
+        command_path,
+        &[
+            "--node",
+            rpc_listen_address,
+            "query",
+            "txs",
+            "--events",
+            &format!("connection_open_init.connection_id={}", connection_id),
+        ],
+    )?
+    .stdout;
+
+    tracing::debug!("parsing tx result: {}", res);
+
+    match json::from_str(&res) {
+        Ok(res) => Ok(res),
+        _ => {
+            let value: yaml::Value = yaml::from_str(&res).map_err(handle_generic_error)?;
+            Ok(yaml_to_json_value(value)?)
+        }
+    }
+}
+
 // Hack to convert yaml::Value to json::Value. Unfortunately there is
 // no builtin conversion provided even though both Value types are
 // essentially the same. We just convert the two types to and from
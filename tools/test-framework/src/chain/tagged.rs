@@ -6,11 +6,13 @@ use ibc_proto::google::protobuf::Any;
 use ibc_relayer::chain::cosmos::types::config::TxConfig;
 use serde_json as json;
 
-use crate::chain::cli::query::query_recipient_transactions;
+use crate::chain::cli::query::{
+    query_connection_open_init_transactions, query_recipient_transactions,
+};
 use crate::chain::driver::ChainDriver;
 use crate::error::Error;
 use crate::ibc::denom::Denom;
-use crate::types::id::TaggedChainIdRef;
+use crate::types::id::{TaggedChainIdRef, TaggedConnectionIdRef};
 use crate::types::tagged::*;
 use crate::types::wallet::{Wallet, WalletAddress};
 
@@ -67,6 +69,17 @@ pub trait TaggedChainDriverExt<Chain> {
         &self,
         recipient_address: &MonoTagged<Chain, &WalletAddress>,
     ) -> Result<json::Value, Error>;
+
+    /**
+        Taggged version of [`query_connection_open_init_transactions`].
+
+        Query for the transactions on `Chain` that initiated the handshake
+        of a connection belonging to `Chain`.
+    */
+    fn query_connection_open_init_transactions<Counterparty>(
+        &self,
+        connection_id: &TaggedConnectionIdRef<Chain, Counterparty>,
+    ) -> Result<json::Value, Error>;
 }
 
 impl<'a, Chain: Send> TaggedChainDriverExt<Chain> for MonoTagged<Chain, &'a ChainDriver> {
@@ -116,4 +129,17 @@ impl<'a, Chain: Send> TaggedChainDriverExt<Chain> for MonoTagged<Chain, &'a Chai
             &recipient_address.value().0,
         )
     }
+
+    fn query_connection_open_init_transactions<Counterparty>(
+        &self,
+        connection_id: &TaggedConnectionIdRef<Chain, Counterparty>,
+    ) -> Result<json::Value, Error> {
+        let driver = *self.value();
+        query_connection_open_init_transactions(
+            driver.chain_id.as_str(),
+            &driver.command_path,
+            &driver.rpc_listen_address(),
+            connection_id.value().as_str(),
+        )
+    }
 }
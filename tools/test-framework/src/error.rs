@@ -1,6 +1,7 @@
 //! Error type used for the tests.
 
 use core::convert::{From, Into};
+use core::time::Duration;
 use eyre::Report;
 use flex_error::{define_error, TraceError};
 use ibc_relayer::channel::error::ChannelError;
@@ -54,6 +55,15 @@ define_error! {
             [ LinkError ]
             | _ | { "link error" },
 
+        Timeout
+            { timeout: Duration }
+            | e | {
+                format_args!(
+                    "test did not complete within its timeout of {:?}, the full nodes were killed",
+                    e.timeout
+                )
+            },
+
         Retry
             {
                 task_name: String,
@@ -4,6 +4,9 @@
 */
 
 use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::config::gas_multiplier::GasMultiplier;
+use ibc_relayer::config::{Config, GasPrice};
+use ibc_relayer_types::core::ics23_commitment::commitment::CommitmentPrefix;
 use tracing::info;
 
 use crate::bootstrap::nary::chain::{
@@ -12,9 +15,11 @@ use crate::bootstrap::nary::chain::{
 use crate::error::Error;
 use crate::framework::base::{HasOverrides, TestConfigOverride};
 use crate::framework::binary::chain::RelayerConfigOverride;
-use crate::framework::binary::node::{NodeConfigOverride, NodeGenesisOverride};
-use crate::framework::nary::node::{run_nary_node_test, NaryNodeTest};
+use crate::framework::nary::node::{
+    run_nary_node_test, ConsensusParamsOverride, NaryNodeTest, PerNodeConfigOverride,
+};
 use crate::framework::supervisor::{RunWithSupervisor, SupervisorOverride};
+use crate::relayer::clock_skew::{ClockSkew, ClockSkewChainHandle};
 use crate::relayer::driver::RelayerDriver;
 use crate::types::binary::chains::DropChainHandle;
 use crate::types::config::TestConfig;
@@ -45,9 +50,14 @@ where
     Test: NaryChainTest<SIZE>,
     Test: HasOverrides<Overrides = Overrides>,
     Overrides: TestConfigOverride
-        + NodeConfigOverride
-        + NodeGenesisOverride
+        + PerNodeConfigOverride
+        + ConsensusParamsOverride
         + RelayerConfigOverride
+        + GasSettingsOverride
+        + ClearOnStartOverride
+        + ClearIntervalOverride
+        + ClockSkewOverride
+        + CommitmentPrefixOverride
         + SupervisorOverride,
 {
     run_nary_node_test(&RunNaryChainTest::new(&RunWithSupervisor::new(test)))
@@ -74,8 +84,8 @@ where
     Test: NaryChainTest<SIZE>,
     Test: HasOverrides<Overrides = Overrides>,
     Overrides: TestConfigOverride
-        + NodeConfigOverride
-        + NodeGenesisOverride
+        + PerNodeConfigOverride
+        + ConsensusParamsOverride
         + RelayerConfigOverride
         + SupervisorOverride,
 {
@@ -102,6 +112,129 @@ pub trait NaryChainTest<const SIZE: usize> {
     ) -> Result<(), Error>;
 }
 
+/**
+    An internal trait that can be implemented by test cases to override
+    the gas settings used by the relayer for each chain in an N-ary setup.
+
+    The methods are given the position of the chain, and return `None`
+    to keep the default settings from the generated chain config.
+
+    This is called by [`RunNaryChainTest`] before the relayer config
+    is modified through
+    [`RelayerConfigOverride`], so explicit config modifications
+    still take precedence.
+*/
+pub trait GasSettingsOverride {
+    fn gas_price(&self, chain_index: usize) -> Option<GasPrice>;
+
+    fn gas_multiplier(&self, chain_index: usize) -> Option<GasMultiplier>;
+}
+
+/**
+    Applies the per-chain gas settings returned by [`GasSettingsOverride`]
+    to the chain configs in the relayer [`Config`].
+*/
+pub fn apply_gas_settings_override(config: &mut Config, overrides: &impl GasSettingsOverride) {
+    for (i, chain_config) in config.chains.iter_mut().enumerate() {
+        if let Some(gas_price) = overrides.gas_price(i) {
+            chain_config.gas_price = gas_price;
+        }
+
+        if let Some(gas_multiplier) = overrides.gas_multiplier(i) {
+            chain_config.gas_multiplier = Some(gas_multiplier);
+        }
+    }
+}
+
+/**
+    An internal trait that can be implemented by test cases to override
+    whether the relayer clears pending packets on start for each chain
+    in an N-ary setup.
+
+    The method is given the position of the chain, and returns `None`
+    to inherit the global `mode.packets.clear_on_start` setting.
+
+    Like [`GasSettingsOverride`], this is applied by [`RunNaryChainTest`]
+    before [`RelayerConfigOverride`].
+*/
+pub trait ClearOnStartOverride {
+    fn clear_on_start(&self, chain_index: usize) -> Option<bool>;
+}
+
+/**
+    Applies the per-chain clear-on-start settings returned by
+    [`ClearOnStartOverride`] to the chain configs in the relayer [`Config`].
+*/
+pub fn apply_clear_on_start_override(config: &mut Config, overrides: &impl ClearOnStartOverride) {
+    for (i, chain_config) in config.chains.iter_mut().enumerate() {
+        if let Some(clear_on_start) = overrides.clear_on_start(i) {
+            chain_config.clear_on_start = Some(clear_on_start);
+        }
+    }
+}
+
+/**
+    An internal trait that can be implemented by test cases to override
+    the interval, in number of blocks, at which the relayer clears pending
+    packets in an N-ary setup.
+
+    Like [`GasSettingsOverride`], this is applied by [`RunNaryChainTest`]
+    before [`RelayerConfigOverride`].
+*/
+pub trait ClearIntervalOverride {
+    fn clear_interval(&self) -> u64;
+}
+
+/**
+    Applies the packet clearing interval returned by [`ClearIntervalOverride`]
+    to the relayer [`Config`].
+*/
+pub fn apply_clear_interval_override(config: &mut Config, overrides: &impl ClearIntervalOverride) {
+    config.mode.packets.clear_interval = overrides.clear_interval();
+}
+
+/**
+    An internal trait that can be implemented by test cases to override
+    the commitment prefix of the store of each chain in an N-ary setup,
+    for chains that do not use the default `ibc` store prefix.
+
+    The method is given the position of the chain. Like
+    [`GasSettingsOverride`], this is applied by [`RunNaryChainTest`]
+    before [`RelayerConfigOverride`].
+*/
+pub trait CommitmentPrefixOverride {
+    fn commitment_prefix(&self, chain_index: usize) -> CommitmentPrefix;
+}
+
+/**
+    Applies the per-chain commitment prefixes returned by
+    [`CommitmentPrefixOverride`] to the store prefix of the chain configs
+    in the relayer [`Config`], which the relayer uses as the prefix of the
+    chains' commitment proofs.
+*/
+pub fn apply_commitment_prefix_override(
+    config: &mut Config,
+    overrides: &impl CommitmentPrefixOverride,
+) {
+    for (i, chain_config) in config.chains.iter_mut().enumerate() {
+        let prefix = overrides.commitment_prefix(i);
+        chain_config.store_prefix = String::from_utf8_lossy(prefix.as_bytes()).into_owned();
+    }
+}
+
+/**
+    An internal trait that can be implemented by test cases to simulate
+    the clock of each chain in an N-ary setup being offset from the others.
+
+    The method is given the position of the chain. After the foreign clients
+    are created, [`RunNaryChainTest`] wraps the chain handles given to the
+    test in [`ClockSkewChainHandle`]s, which skew the block timestamps
+    reported by the chains by the returned offset.
+*/
+pub trait ClockSkewOverride {
+    fn clock_skew(&self, chain_index: usize) -> ClockSkew;
+}
+
 /**
     A wrapper type that lifts a test case that implements [`RunNaryChainTest`]
     into a test case the implements [`NaryNodeTest`].
@@ -125,11 +258,22 @@ impl<'a, Test, Overrides, const SIZE: usize> NaryNodeTest<SIZE> for RunNaryChain
 where
     Test: NaryChainTest<SIZE>,
     Test: HasOverrides<Overrides = Overrides>,
-    Overrides: RelayerConfigOverride,
+    Overrides: RelayerConfigOverride
+        + GasSettingsOverride
+        + ClearOnStartOverride
+        + ClearIntervalOverride
+        + ClockSkewOverride
+        + CommitmentPrefixOverride,
 {
     fn run(&self, config: &TestConfig, nodes: [FullNode; SIZE]) -> Result<(), Error> {
+        let overrides = self.test.get_overrides();
+
         let (relayer, chains) = boostrap_chains_with_nodes(config, nodes, |config| {
-            self.test.get_overrides().modify_relayer_config(config);
+            apply_gas_settings_override(config, overrides);
+            apply_clear_on_start_override(config, overrides);
+            apply_clear_interval_override(config, overrides);
+            apply_commitment_prefix_override(config, overrides);
+            overrides.modify_relayer_config(config);
         })?;
 
         let env_path = config.chain_store_dir.join("nary-chains.env");
@@ -144,6 +288,10 @@ where
             .map(|handle| DropChainHandle(handle.clone()))
             .collect::<Vec<_>>();
 
+        let chains = chains.map_chain_handles(|i, handle| {
+            ClockSkewChainHandle::new(handle, overrides.clock_skew(i))
+        })?;
+
         self.test.run(config, relayer, chains)?;
 
         Ok(())
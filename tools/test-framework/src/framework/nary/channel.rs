@@ -14,10 +14,18 @@ use crate::framework::base::{HasOverrides, TestConfigOverride};
 use crate::framework::binary::chain::RelayerConfigOverride;
 use crate::framework::binary::channel::{BinaryChannelTest, ChannelOrderOverride};
 use crate::framework::binary::connection::ConnectionDelayOverride;
-use crate::framework::binary::node::{NodeConfigOverride, NodeGenesisOverride};
-use crate::framework::nary::chain::RunNaryChainTest;
-use crate::framework::nary::connection::{NaryConnectionTest, RunNaryConnectionTest};
-use crate::framework::nary::node::run_nary_node_test;
+use crate::framework::nary::chain::{
+    ClearIntervalOverride, ClearOnStartOverride, ClockSkewOverride, CommitmentPrefixOverride,
+    GasSettingsOverride, RunNaryChainTest,
+};
+use crate::framework::nary::connection::{
+    ClientReuseOverride, GenesisConnectionDelayOverride, HandshakeInitiatorOverride,
+    NaryConnectionTest, PairConnectionDelayOverride, RunNaryConnectionTest, TestTimeoutOverride,
+    TopologyOverride,
+};
+use crate::framework::nary::node::{
+    run_nary_node_test, ConsensusParamsOverride, PerNodeConfigOverride,
+};
 use crate::framework::supervisor::{RunWithSupervisor, SupervisorOverride};
 use crate::relayer::driver::RelayerDriver;
 use crate::types::config::TestConfig;
@@ -32,11 +40,22 @@ where
     Test: NaryChannelTest<SIZE>,
     Test: HasOverrides<Overrides = Overrides>,
     Overrides: TestConfigOverride
-        + NodeConfigOverride
-        + NodeGenesisOverride
+        + PerNodeConfigOverride
+        + ConsensusParamsOverride
         + RelayerConfigOverride
+        + GasSettingsOverride
+        + ClearOnStartOverride
+        + ClearIntervalOverride
+        + ClockSkewOverride
+        + CommitmentPrefixOverride
         + SupervisorOverride
         + ConnectionDelayOverride
+        + GenesisConnectionDelayOverride
+        + PairConnectionDelayOverride
+        + HandshakeInitiatorOverride
+        + ClientReuseOverride
+        + TopologyOverride
+        + TestTimeoutOverride
         + PortsOverride<SIZE>
         + ChannelOrderOverride,
 {
@@ -50,11 +69,22 @@ where
     Test: BinaryChannelTest,
     Test: HasOverrides<Overrides = Overrides>,
     Overrides: TestConfigOverride
-        + NodeConfigOverride
-        + NodeGenesisOverride
+        + PerNodeConfigOverride
+        + ConsensusParamsOverride
         + RelayerConfigOverride
+        + GasSettingsOverride
+        + ClearOnStartOverride
+        + ClearIntervalOverride
+        + ClockSkewOverride
+        + CommitmentPrefixOverride
         + SupervisorOverride
         + ConnectionDelayOverride
+        + GenesisConnectionDelayOverride
+        + PairConnectionDelayOverride
+        + HandshakeInitiatorOverride
+        + ClientReuseOverride
+        + TopologyOverride
+        + TestTimeoutOverride
         + PortsOverride<2>
         + ChannelOrderOverride,
 {
@@ -74,6 +104,37 @@ pub fn transfer_port_overrides<const SIZE: usize>() -> [[PortId; SIZE]; SIZE] {
     ports_ref.map(|inner_ports| inner_ports.map(Clone::clone))
 }
 
+/**
+    An internal trait that can be implemented by test cases to bind the
+    N-ary channels of each chain to a given port, e.g. the port of a custom
+    IBC module, instead of the `"transfer"` port.
+
+    The method is given the position of the chain, and returns the port
+    bound by the channels of that chain to all its counterparties.
+*/
+pub trait PortBindingOverride {
+    fn port_binding(&self, chain_index: usize) -> PortId;
+}
+
+/**
+   Returns a `SIZE`x`SIZE` matrix of port IDs, with the channels of the
+   chain at each position bound to the port returned by the given
+   [`PortBindingOverride`].
+
+   This can be used by N-ary channel test cases to implement
+   `PortsOverride` from per-chain port bindings.
+*/
+pub fn port_binding_overrides<const SIZE: usize>(
+    overrides: &impl PortBindingOverride,
+) -> [[PortId; SIZE]; SIZE] {
+    let mut i = 0;
+    [(); SIZE].map(|_| {
+        let port = overrides.port_binding(i);
+        i += 1;
+        [(); SIZE].map(|_| port.clone())
+    })
+}
+
 /**
     This trait is implemented for test cases that need to have more than
     two chains running with connected channels.
@@ -4,23 +4,36 @@
    as well as connected IBC connections with completed handshakes.
 */
 
+use core::time::Duration;
+use eyre::eyre;
 use ibc_relayer::chain::handle::ChainHandle;
-use tracing::info;
+use std::sync::mpsc;
+use std::thread;
+use tracing::{error, info};
 
-use crate::bootstrap::nary::connection::bootstrap_connections;
+use crate::bootstrap::nary::connection::{
+    bootstrap_connections_with_client_reuse, bootstrap_connections_with_topology,
+    connection_delay_from_genesis,
+};
 use crate::error::Error;
 use crate::framework::base::{HasOverrides, TestConfigOverride};
 use crate::framework::binary::chain::RelayerConfigOverride;
 use crate::framework::binary::connection::{BinaryConnectionTest, ConnectionDelayOverride};
-use crate::framework::binary::node::{NodeConfigOverride, NodeGenesisOverride};
-use crate::framework::nary::chain::{NaryChainTest, RunNaryChainTest};
-use crate::framework::nary::node::run_nary_node_test;
+use crate::framework::nary::chain::{
+    ClearIntervalOverride, ClearOnStartOverride, ClockSkewOverride, CommitmentPrefixOverride,
+    GasSettingsOverride, NaryChainTest, RunNaryChainTest,
+};
+use crate::framework::nary::node::{
+    run_nary_node_test, ConsensusParamsOverride, PerNodeConfigOverride,
+};
 use crate::framework::supervisor::{RunWithSupervisor, SupervisorOverride};
 use crate::relayer::driver::RelayerDriver;
 use crate::types::config::TestConfig;
 use crate::types::env::write_env;
 use crate::types::nary::chains::NaryConnectedChains;
 use crate::types::nary::connection::ConnectedConnections;
+use crate::types::nary::topology::Topology;
+use crate::types::single::node::FullNode;
 use crate::util::suspend::hang_on_error;
 
 pub fn run_nary_connection_test<Test, Overrides, const SIZE: usize>(
@@ -30,17 +43,95 @@ where
     Test: NaryConnectionTest<SIZE>,
     Test: HasOverrides<Overrides = Overrides>,
     Overrides: TestConfigOverride
-        + NodeConfigOverride
-        + NodeGenesisOverride
+        + PerNodeConfigOverride
+        + ConsensusParamsOverride
         + RelayerConfigOverride
+        + GasSettingsOverride
+        + ClearOnStartOverride
+        + ClearIntervalOverride
+        + ClockSkewOverride
+        + CommitmentPrefixOverride
         + SupervisorOverride
-        + ConnectionDelayOverride,
+        + ConnectionDelayOverride
+        + GenesisConnectionDelayOverride
+        + PairConnectionDelayOverride
+        + HandshakeInitiatorOverride
+        + ClientReuseOverride
+        + TopologyOverride
+        + TestTimeoutOverride,
 {
     run_nary_node_test(&RunNaryChainTest::new(&RunNaryConnectionTest::new(
         &RunWithSupervisor::new(test),
     )))
 }
 
+/**
+   An internal trait that can be implemented by test cases to derive the
+   connection delay used for bootstrapping N-ary connections from the
+   genesis of the chains, instead of [`ConnectionDelayOverride`].
+
+   When enabled, the delay is the longest `max_expected_time_per_block`
+   IBC connection parameter among the chains' genesis files.
+*/
+pub trait GenesisConnectionDelayOverride {
+    fn connection_delay_from_genesis(&self) -> bool;
+}
+
+/**
+   An internal trait that can be implemented by test cases to specify the
+   delay of the N-ary connection between each pair of chains, by their
+   positions, instead of the same delay for all connections.
+*/
+pub trait PairConnectionDelayOverride {
+    fn pair_connection_delay(&self, chain_a: usize, chain_b: usize) -> Duration;
+}
+
+/**
+   An internal trait that can be implemented by test cases to choose which
+   chain of each pair initiates the handshake of the N-ary connection
+   between them, by submitting the `ConnOpenInit` message.
+*/
+pub trait HandshakeInitiatorOverride {
+    fn handshake_initiator(&self, chain_a: usize, chain_b: usize) -> usize;
+}
+
+/**
+   An internal trait that can be implemented by test cases to bind each
+   N-ary connection to the client already existing between its pair of
+   chains, instead of the foreign clients bootstrapped with the chains.
+*/
+pub trait ClientReuseOverride {
+    fn reuse_existing_clients(&self) -> bool;
+}
+
+/**
+   An internal trait that can be implemented by test cases to choose the
+   [`Topology`] in which the N-ary chains are connected, so that only the
+   pairs of chains connected in the topology have a connection in the
+   [`ConnectedConnections`] given to the test.
+
+   The channels of an N-ary channel test are likewise only bootstrapped
+   between the connected pairs of chains, and getting the connection or
+   channel between two chains that are not connected returns an error.
+   As a binary test run with two chains takes the connection and channel
+   between them, it requires a topology that connects the two chains.
+*/
+pub trait TopologyOverride {
+    fn topology(&self) -> Topology;
+}
+
+/**
+   An internal trait that can be implemented by test cases to set a deadline
+   for running the inner N-ary connection test.
+
+   When the deadline is exceeded, the full nodes of the chains are killed so
+   that the inner test aborts, and [`Error::timeout`] is returned instead of
+   its result.
+*/
+pub trait TestTimeoutOverride {
+    fn test_timeout(&self) -> Option<Duration>;
+}
+
 /**
    This trait is implemented for test cases that need to have more than
    two chains running with connected connections.
@@ -96,7 +187,13 @@ impl<'a, Test, Overrides, const SIZE: usize> NaryChainTest<SIZE>
 where
     Test: NaryConnectionTest<SIZE>,
     Test: HasOverrides<Overrides = Overrides>,
-    Overrides: ConnectionDelayOverride,
+    Overrides: ConnectionDelayOverride
+        + GenesisConnectionDelayOverride
+        + PairConnectionDelayOverride
+        + HandshakeInitiatorOverride
+        + ClientReuseOverride
+        + TopologyOverride
+        + TestTimeoutOverride,
 {
     fn run<Handle: ChainHandle>(
         &self,
@@ -104,13 +201,40 @@ where
         relayer: RelayerDriver,
         chains: NaryConnectedChains<Handle, SIZE>,
     ) -> Result<(), Error> {
-        let connection_delay = self.get_overrides().connection_delay();
+        let overrides = self.get_overrides();
+
+        let genesis_connection_delay = if overrides.connection_delay_from_genesis() {
+            Some(connection_delay_from_genesis(chains.full_nodes())?)
+        } else {
+            None
+        };
+
+        let connection_delay = |chain_a, chain_b| {
+            genesis_connection_delay
+                .unwrap_or_else(|| overrides.pair_connection_delay(chain_a, chain_b))
+        };
 
-        let connections = bootstrap_connections(
-            chains.foreign_clients().clone(),
-            connection_delay,
-            config.bootstrap_with_random_ids,
-        )?;
+        let initiator = |chain_a, chain_b| overrides.handshake_initiator(chain_a, chain_b);
+
+        let topology = overrides.topology();
+
+        let connections = if overrides.reuse_existing_clients() {
+            bootstrap_connections_with_client_reuse(
+                chains.foreign_clients().clone(),
+                &topology,
+                connection_delay,
+                config.bootstrap_with_random_ids,
+                initiator,
+            )?
+        } else {
+            bootstrap_connections_with_topology(
+                chains.foreign_clients().clone(),
+                &topology,
+                connection_delay,
+                config.bootstrap_with_random_ids,
+                initiator,
+            )?
+        };
 
         let env_path = config.chain_store_dir.join("nary-connections.env");
 
@@ -118,12 +242,72 @@ where
 
         info!("written channel environment to {}", env_path.display());
 
-        self.test.run(config, relayer, chains, connections)?;
+        match overrides.test_timeout() {
+            Some(timeout) => {
+                let full_nodes = chains.full_nodes().to_vec();
+
+                run_with_timeout(timeout, full_nodes, || {
+                    self.test.run(config, relayer, chains, connections)
+                })?;
+            }
+            None => {
+                self.test.run(config, relayer, chains, connections)?;
+            }
+        }
 
         Ok(())
     }
 }
 
+/**
+   Run the given test while a watchdog thread waits for it to complete
+   within `timeout`. Past the deadline, the watchdog kills the given full
+   nodes, so that the test fails on its next query instead of hanging,
+   and [`Error::timeout`] is returned once the test has returned.
+*/
+fn run_with_timeout(
+    timeout: Duration,
+    full_nodes: Vec<FullNode>,
+    test: impl FnOnce() -> Result<(), Error>,
+) -> Result<(), Error> {
+    let (done_sender, done_receiver) = mpsc::channel::<()>();
+
+    let watchdog = thread::spawn(move || match done_receiver.recv_timeout(timeout) {
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            error!(
+                "test did not complete within {:?}, killing the full nodes",
+                timeout
+            );
+
+            for node in full_nodes.iter() {
+                if let Err(e) = node.kill() {
+                    error!(
+                        "failed to kill full node {}: {}",
+                        node.chain_driver.chain_id, e
+                    );
+                }
+            }
+
+            true
+        }
+        _ => false,
+    });
+
+    let result = test();
+
+    let _ = done_sender.send(());
+
+    let timed_out = watchdog
+        .join()
+        .map_err(|_| Error::generic(eyre!("test timeout watchdog panicked")))?;
+
+    if timed_out {
+        Err(Error::timeout(timeout))
+    } else {
+        result
+    }
+}
+
 impl<'a, Test> NaryConnectionTest<2> for RunBinaryAsNaryConnectionTest<'a, Test>
 where
     Test: BinaryConnectionTest,
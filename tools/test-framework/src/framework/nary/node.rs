@@ -3,12 +3,14 @@
    running without setting up the relayer.
 */
 
+use eyre::eyre;
+use serde_json::Value;
+
 use crate::bootstrap::single::bootstrap_single_node;
 use crate::chain::builder::ChainBuilder;
 use crate::error::Error;
 use crate::framework::base::HasOverrides;
 use crate::framework::base::{run_basic_test, BasicTest, TestConfigOverride};
-use crate::framework::binary::node::{NodeConfigOverride, NodeGenesisOverride};
 use crate::types::config::TestConfig;
 use crate::types::single::node::FullNode;
 use crate::util::array::try_into_array;
@@ -17,7 +19,7 @@ pub fn run_nary_node_test<Test, Overrides, const SIZE: usize>(test: &Test) -> Re
 where
     Test: NaryNodeTest<SIZE>,
     Test: HasOverrides<Overrides = Overrides>,
-    Overrides: NodeConfigOverride + NodeGenesisOverride + TestConfigOverride,
+    Overrides: PerNodeConfigOverride + ConsensusParamsOverride + TestConfigOverride,
 {
     run_basic_test(&RunNaryNodeTest { test })
 }
@@ -37,6 +39,110 @@ pub trait NaryNodeTest<const SIZE: usize> {
     fn run(&self, config: &TestConfig, nodes: [FullNode; SIZE]) -> Result<(), Error>;
 }
 
+/**
+   An internal trait that can be implemented by test cases to override the
+   full node config and genesis file of each chain in an N-ary setup
+   separately, e.g. to mix chains with different parameters in one test.
+
+   The methods are given the position of the chain, in `0..SIZE`. When
+   implemented through
+   [`TestOverrides`](crate::framework::overrides::TestOverrides), they
+   default to applying the same
+   [`modify_node_config`](crate::framework::overrides::TestOverrides::modify_node_config)
+   and
+   [`modify_genesis_file`](crate::framework::overrides::TestOverrides::modify_genesis_file)
+   overrides to all chains.
+*/
+pub trait PerNodeConfigOverride {
+    /// Modify the full node config of the chain at the given position
+    fn modify_node_config_at(
+        &self,
+        chain_index: usize,
+        config: &mut toml::Value,
+    ) -> Result<(), Error>;
+
+    /// Modify the genesis file of the chain at the given position
+    fn modify_genesis_file_at(
+        &self,
+        chain_index: usize,
+        genesis: &mut serde_json::Value,
+    ) -> Result<(), Error>;
+}
+
+/**
+   An internal trait that can be implemented by test cases to override the
+   block consensus params in the genesis of each chain in an N-ary setup,
+   e.g. to test relaying under constrained block sizes.
+
+   The methods are given the position of the chain, and return `None`
+   to keep the default params from the generated genesis. The overrides
+   are applied by [`RunNaryNodeTest`] before
+   [`PerNodeConfigOverride::modify_genesis_file_at`], so explicit genesis
+   modifications still take precedence.
+*/
+pub trait ConsensusParamsOverride {
+    fn max_block_bytes(&self, chain_index: usize) -> Option<i64>;
+
+    fn max_block_gas(&self, chain_index: usize) -> Option<i64>;
+}
+
+/**
+   Applies the block consensus params returned by [`ConsensusParamsOverride`]
+   for the chain at the given position to its genesis file.
+
+   As Tendermint requires the max evidence size to not exceed the max block
+   size, it is lowered to a tenth of an overridden max block size if needed.
+*/
+pub fn apply_consensus_params_override(
+    genesis: &mut Value,
+    chain_index: usize,
+    overrides: &impl ConsensusParamsOverride,
+) -> Result<(), Error> {
+    let max_bytes = overrides.max_block_bytes(chain_index);
+    let max_gas = overrides.max_block_gas(chain_index);
+
+    if max_bytes.is_none() && max_gas.is_none() {
+        return Ok(());
+    }
+
+    let consensus_params = genesis
+        .get_mut("consensus_params")
+        .ok_or_else(|| eyre!("failed to find the consensus params in genesis"))?;
+
+    if let Some(max_bytes) = max_bytes {
+        let evidence_max_bytes = consensus_params
+            .pointer_mut("/evidence/max_bytes")
+            .ok_or_else(|| eyre!("failed to find the evidence max bytes in genesis"))?;
+
+        let current = evidence_max_bytes
+            .as_str()
+            .and_then(|max_bytes| max_bytes.parse::<i64>().ok())
+            .unwrap_or_default();
+
+        if current > max_bytes {
+            *evidence_max_bytes = Value::String((max_bytes / 10).to_string());
+        }
+    }
+
+    let block = consensus_params
+        .get_mut("block")
+        .and_then(|block| block.as_object_mut())
+        .ok_or_else(|| eyre!("failed to find the block consensus params in genesis"))?;
+
+    if let Some(max_bytes) = max_bytes {
+        block.insert(
+            "max_bytes".to_string(),
+            Value::String(max_bytes.to_string()),
+        );
+    }
+
+    if let Some(max_gas) = max_gas {
+        block.insert("max_gas".to_string(), Value::String(max_gas.to_string()));
+    }
+
+    Ok(())
+}
+
 /**
    A wrapper type that lifts a test case that implements [`NaryNodeTest`]
    into a test case the implements [`BasicTest`].
@@ -49,19 +155,24 @@ impl<'a, Test, Overrides, const SIZE: usize> BasicTest for RunNaryNodeTest<'a, T
 where
     Test: NaryNodeTest<SIZE>,
     Test: HasOverrides<Overrides = Overrides>,
-    Overrides: NodeConfigOverride + NodeGenesisOverride,
+    Overrides: PerNodeConfigOverride + ConsensusParamsOverride,
 {
     fn run(&self, config: &TestConfig, builder: &ChainBuilder) -> Result<(), Error> {
         let mut nodes = Vec::new();
         let mut node_processes = Vec::new();
 
+        let overrides = self.test.get_overrides();
+
         for i in 0..SIZE {
             let node = bootstrap_single_node(
                 builder,
                 &format!("{}", i + 1),
                 config.bootstrap_with_random_ids,
-                |config| self.test.get_overrides().modify_node_config(config),
-                |genesis| self.test.get_overrides().modify_genesis_file(genesis),
+                |config| overrides.modify_node_config_at(i, config),
+                |genesis| {
+                    apply_consensus_params_override(genesis, i, overrides)?;
+                    overrides.modify_genesis_file_at(i, genesis)
+                },
             )?;
 
             node_processes.push(node.process.clone());
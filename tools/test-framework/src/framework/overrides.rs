@@ -3,11 +3,14 @@
 */
 
 use core::time::Duration;
+use ibc_relayer::config::default::clear_packets_interval as default_clear_packets_interval;
 use ibc_relayer::config::default::connection_delay as default_connection_delay;
-use ibc_relayer::config::Config;
+use ibc_relayer::config::gas_multiplier::GasMultiplier;
+use ibc_relayer::config::{Config, GasPrice};
 use ibc_relayer::foreign_client::CreateOptions as ClientOptions;
 use ibc_relayer_types::core::ics04_channel::channel::Order;
 use ibc_relayer_types::core::ics04_channel::version::Version;
+use ibc_relayer_types::core::ics23_commitment::commitment::CommitmentPrefix;
 use ibc_relayer_types::core::ics24_host::identifier::PortId;
 
 use crate::error::Error;
@@ -19,9 +22,20 @@ use crate::framework::binary::channel::{
 };
 use crate::framework::binary::connection::ConnectionDelayOverride;
 use crate::framework::binary::node::{NodeConfigOverride, NodeGenesisOverride};
-use crate::framework::nary::channel::PortsOverride as NaryPortsOverride;
+use crate::framework::nary::chain::{
+    ClearIntervalOverride, ClearOnStartOverride, ClockSkewOverride, CommitmentPrefixOverride,
+    GasSettingsOverride,
+};
+use crate::framework::nary::channel::{PortBindingOverride, PortsOverride as NaryPortsOverride};
+use crate::framework::nary::connection::{
+    ClientReuseOverride, GenesisConnectionDelayOverride, HandshakeInitiatorOverride,
+    PairConnectionDelayOverride, TestTimeoutOverride, TopologyOverride,
+};
+use crate::framework::nary::node::{ConsensusParamsOverride, PerNodeConfigOverride};
 use crate::framework::supervisor::SupervisorOverride;
+use crate::relayer::clock_skew::ClockSkew;
 use crate::types::config::TestConfig;
+use crate::types::nary::topology::Topology;
 
 /**
    This trait should be implemented for all test cases to allow overriding
@@ -68,6 +82,60 @@ pub trait TestOverrides {
         Ok(())
     }
 
+    /**
+        Modify the full node config of the chain at the given position in an
+        N-ary test, before the chain gets initialized. Defaults to
+        [`modify_node_config`](TestOverrides::modify_node_config), which is
+        the same for all chains.
+
+        Implemented for [`PerNodeConfigOverride`].
+    */
+    fn modify_node_config_at(
+        &self,
+        _chain_index: usize,
+        config: &mut toml::Value,
+    ) -> Result<(), Error> {
+        self.modify_node_config(config)
+    }
+
+    /**
+        Modify the genesis file of the chain at the given position in an
+        N-ary test, before the chain gets initialized. Defaults to
+        [`modify_genesis_file`](TestOverrides::modify_genesis_file), which
+        is the same for all chains.
+
+        Implemented for [`PerNodeConfigOverride`].
+    */
+    fn modify_genesis_file_at(
+        &self,
+        _chain_index: usize,
+        genesis: &mut serde_json::Value,
+    ) -> Result<(), Error> {
+        self.modify_genesis_file(genesis)
+    }
+
+    /**
+       Return the max size in bytes of the blocks of the chain at the given
+       position in an N-ary test. Returns `None` by default, which keeps
+       the consensus params from the generated genesis.
+
+       Implemented for [`ConsensusParamsOverride`].
+    */
+    fn max_block_bytes(&self, _chain_index: usize) -> Option<i64> {
+        None
+    }
+
+    /**
+       Return the max gas of the blocks of the chain at the given position
+       in an N-ary test. Returns `None` by default, which keeps the consensus
+       params from the generated genesis.
+
+       Implemented for [`ConsensusParamsOverride`].
+    */
+    fn max_block_gas(&self, _chain_index: usize) -> Option<i64> {
+        None
+    }
+
     /**
        Modify the relayer config before initializing the relayer. Does no
        modification by default.
@@ -96,6 +164,71 @@ pub trait TestOverrides {
         true
     }
 
+    /**
+       Return the gas price used by the relayer for the chain at the given
+       position in an N-ary test. Returns `None` by default, which keeps
+       the gas price from the generated chain config.
+
+       Implemented for [`GasSettingsOverride`].
+    */
+    fn gas_price(&self, _chain_index: usize) -> Option<GasPrice> {
+        None
+    }
+
+    /**
+       Return the gas multiplier used by the relayer for the chain at the
+       given position in an N-ary test. Returns `None` by default, which
+       keeps the library default.
+
+       Implemented for [`GasSettingsOverride`].
+    */
+    fn gas_multiplier(&self, _chain_index: usize) -> Option<GasMultiplier> {
+        None
+    }
+
+    /**
+       Return whether the relayer should clear pending packets on start
+       for the chain at the given position in an N-ary test. Returns `None`
+       by default, which inherits the global `mode.packets.clear_on_start`.
+
+       Implemented for [`ClearOnStartOverride`].
+    */
+    fn clear_on_start(&self, _chain_index: usize) -> Option<bool> {
+        None
+    }
+
+    /**
+       Return the interval, in number of blocks, at which the relayer clears
+       pending packets in an N-ary test. Defaults to the library default
+       `mode.packets.clear_interval`.
+
+       Implemented for [`ClearIntervalOverride`].
+    */
+    fn clear_interval(&self) -> u64 {
+        default_clear_packets_interval()
+    }
+
+    /**
+       Return the commitment prefix of the store of the chain at the given
+       position in an N-ary test. Defaults to `ibc`.
+
+       Implemented for [`CommitmentPrefixOverride`].
+    */
+    fn commitment_prefix(&self, _chain_index: usize) -> CommitmentPrefix {
+        CommitmentPrefix::try_from(b"ibc".to_vec()).expect("ibc is a valid commitment prefix")
+    }
+
+    /**
+       Return the offset of the clock of the chain at position `chain_index`
+       in an N-ary test, simulated by skewing the block timestamps reported
+       by its chain handle. Defaults to [`ClockSkew::Synced`].
+
+       Implemented for [`ClockSkewOverride`].
+    */
+    fn clock_skew(&self, _chain_index: usize) -> ClockSkew {
+        ClockSkew::Synced
+    }
+
     /**
        Return the connection delay used for creating connections as [`Duration`].
        Defaults to zero.
@@ -106,24 +239,104 @@ pub trait TestOverrides {
         default_connection_delay()
     }
 
+    /**
+       Return whether the connection delay of N-ary connections is derived
+       from the `max_expected_time_per_block` IBC connection parameter in
+       the genesis of the chains, instead of [`connection_delay`](TestOverrides::connection_delay).
+       Returns `false` by default.
+
+       Implemented for [`GenesisConnectionDelayOverride`].
+    */
+    fn connection_delay_from_genesis(&self) -> bool {
+        false
+    }
+
+    /**
+       Return the connection delay used for creating the N-ary connection
+       between the chains at positions `chain_a` and `chain_b`. Defaults to
+       [`connection_delay`](TestOverrides::connection_delay) for all pairs.
+
+       Implemented for [`PairConnectionDelayOverride`].
+    */
+    fn pair_connection_delay(&self, _chain_a: usize, _chain_b: usize) -> Duration {
+        self.connection_delay()
+    }
+
+    /**
+       Return the position of the chain that initiates the handshake of the
+       N-ary connection between the chains at positions `chain_a` and
+       `chain_b`. Defaults to the lower position.
+
+       Implemented for [`HandshakeInitiatorOverride`].
+    */
+    fn handshake_initiator(&self, chain_a: usize, chain_b: usize) -> usize {
+        chain_a.min(chain_b)
+    }
+
+    /**
+       Whether to bind each N-ary connection to the client already existing
+       between its pair of chains, instead of the foreign clients bootstrapped
+       with the chains. Defaults to `false`.
+
+       Implemented for [`ClientReuseOverride`].
+    */
+    fn reuse_existing_clients(&self) -> bool {
+        false
+    }
+
+    /**
+       Return the deadline for running the N-ary connection test, past
+       which the full nodes are killed and the test fails with a timeout
+       error. Defaults to `None`, with no deadline.
+
+       Implemented for [`TestTimeoutOverride`].
+    */
+    fn test_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /**
+       Return the topology in which the chains of an N-ary connection or
+       channel test are connected. Defaults to [`Topology::Complete`], which
+       connects every pair of chains. With any other topology, there is no
+       connection nor channel between the chains that are not connected.
+
+       Implemented for [`TopologyOverride`].
+    */
+    fn topology(&self) -> Topology {
+        Topology::Complete
+    }
+
+    /**
+       Return the port bound by the channels of the chain at position
+       `chain_index`. Returns the "transfer" port by default.
+
+       Implemented for [`PortBindingOverride`].
+    */
+    fn port_binding(&self, _chain_index: usize) -> PortId {
+        PortId::transfer()
+    }
+
     /**
        Return the port ID used for creating the channel for the first chain.
-       Returns the "transfer" port by default.
+       Defaults to the [`port_binding`](TestOverrides::port_binding) of the
+       chain at position 0.
 
        Implemented for [`PortsOverride`].
     */
     fn channel_port_a(&self) -> PortId {
-        PortId::transfer()
+        self.port_binding(0)
     }
 
     /**
        Return the port ID used for creating the channel for the second chain.
-       Returns the "transfer" port by default.
+       Defaults to the [`port_binding`](TestOverrides::port_binding) of the
+       chain at position 1.
 
        Implemented for [`PortsOverride`].
     */
     fn channel_port_b(&self) -> PortId {
-        PortId::transfer()
+        self.port_binding(1)
     }
 
     /**
@@ -173,6 +386,34 @@ impl<Test: TestOverrides> NodeGenesisOverride for Test {
     }
 }
 
+impl<Test: TestOverrides> PerNodeConfigOverride for Test {
+    fn modify_node_config_at(
+        &self,
+        chain_index: usize,
+        config: &mut toml::Value,
+    ) -> Result<(), Error> {
+        TestOverrides::modify_node_config_at(self, chain_index, config)
+    }
+
+    fn modify_genesis_file_at(
+        &self,
+        chain_index: usize,
+        genesis: &mut serde_json::Value,
+    ) -> Result<(), Error> {
+        TestOverrides::modify_genesis_file_at(self, chain_index, genesis)
+    }
+}
+
+impl<Test: TestOverrides> ConsensusParamsOverride for Test {
+    fn max_block_bytes(&self, chain_index: usize) -> Option<i64> {
+        TestOverrides::max_block_bytes(self, chain_index)
+    }
+
+    fn max_block_gas(&self, chain_index: usize) -> Option<i64> {
+        TestOverrides::max_block_gas(self, chain_index)
+    }
+}
+
 impl<Test: TestOverrides> RelayerConfigOverride for Test {
     fn modify_relayer_config(&self, config: &mut Config) {
         TestOverrides::modify_relayer_config(self, config)
@@ -195,6 +436,76 @@ impl<Test: TestOverrides> SupervisorOverride for Test {
     }
 }
 
+impl<Test: TestOverrides> GasSettingsOverride for Test {
+    fn gas_price(&self, chain_index: usize) -> Option<GasPrice> {
+        TestOverrides::gas_price(self, chain_index)
+    }
+
+    fn gas_multiplier(&self, chain_index: usize) -> Option<GasMultiplier> {
+        TestOverrides::gas_multiplier(self, chain_index)
+    }
+}
+
+impl<Test: TestOverrides> ClearOnStartOverride for Test {
+    fn clear_on_start(&self, chain_index: usize) -> Option<bool> {
+        TestOverrides::clear_on_start(self, chain_index)
+    }
+}
+
+impl<Test: TestOverrides> ClearIntervalOverride for Test {
+    fn clear_interval(&self) -> u64 {
+        TestOverrides::clear_interval(self)
+    }
+}
+
+impl<Test: TestOverrides> CommitmentPrefixOverride for Test {
+    fn commitment_prefix(&self, chain_index: usize) -> CommitmentPrefix {
+        TestOverrides::commitment_prefix(self, chain_index)
+    }
+}
+
+impl<Test: TestOverrides> ClockSkewOverride for Test {
+    fn clock_skew(&self, chain_index: usize) -> ClockSkew {
+        TestOverrides::clock_skew(self, chain_index)
+    }
+}
+
+impl<Test: TestOverrides> GenesisConnectionDelayOverride for Test {
+    fn connection_delay_from_genesis(&self) -> bool {
+        TestOverrides::connection_delay_from_genesis(self)
+    }
+}
+
+impl<Test: TestOverrides> PairConnectionDelayOverride for Test {
+    fn pair_connection_delay(&self, chain_a: usize, chain_b: usize) -> Duration {
+        TestOverrides::pair_connection_delay(self, chain_a, chain_b)
+    }
+}
+
+impl<Test: TestOverrides> HandshakeInitiatorOverride for Test {
+    fn handshake_initiator(&self, chain_a: usize, chain_b: usize) -> usize {
+        TestOverrides::handshake_initiator(self, chain_a, chain_b)
+    }
+}
+
+impl<Test: TestOverrides> TopologyOverride for Test {
+    fn topology(&self) -> Topology {
+        TestOverrides::topology(self)
+    }
+}
+
+impl<Test: TestOverrides> ClientReuseOverride for Test {
+    fn reuse_existing_clients(&self) -> bool {
+        TestOverrides::reuse_existing_clients(self)
+    }
+}
+
+impl<Test: TestOverrides> TestTimeoutOverride for Test {
+    fn test_timeout(&self) -> Option<Duration> {
+        TestOverrides::test_timeout(self)
+    }
+}
+
 impl<Test: TestOverrides> ConnectionDelayOverride for Test {
     fn connection_delay(&self) -> Duration {
         TestOverrides::connection_delay(self)
@@ -223,6 +534,12 @@ impl<Test: TestOverrides> ChannelVersionOverride for Test {
     }
 }
 
+impl<Test: TestOverrides> PortBindingOverride for Test {
+    fn port_binding(&self, chain_index: usize) -> PortId {
+        TestOverrides::port_binding(self, chain_index)
+    }
+}
+
 impl<Test: TestOverrides> NaryPortsOverride<2> for Test {
     fn channel_ports(&self) -> [[PortId; 2]; 2] {
         let port_a = self.channel_port_a();
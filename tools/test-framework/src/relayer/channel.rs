@@ -1,10 +1,14 @@
 use core::time::Duration;
 use eyre::eyre;
 use ibc_relayer::chain::handle::ChainHandle;
-use ibc_relayer::chain::requests::{IncludeProof, QueryChannelRequest, QueryHeight};
+use ibc_relayer::chain::requests::{
+    IncludeProof, PageRequest, QueryChannelRequest, QueryChannelsRequest, QueryHeight,
+};
 use ibc_relayer::channel::{extract_channel_id, Channel, ChannelSide};
+use ibc_relayer::config::filter::PacketFilter;
 use ibc_relayer_types::core::ics04_channel::channel::State as ChannelState;
 use ibc_relayer_types::core::ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd, Order};
+use ibc_relayer_types::core::ics24_host::identifier::{ChannelId, PortId};
 
 use crate::error::Error;
 use crate::types::id::{
@@ -109,6 +113,25 @@ pub fn query_identified_channel_end<ChainA: ChainHandle, ChainB>(
     )))
 }
 
+/**
+   Query all the channels of the chain, and partition them into the
+   channels on which the given filter allows relaying packets, and the
+   ones on which it does not.
+*/
+pub fn partition_chain_channels<Chain: ChainHandle>(
+    handle: &Chain,
+    filter: &PacketFilter,
+) -> Result<(Vec<(PortId, ChannelId)>, Vec<(PortId, ChannelId)>), Error> {
+    let channels = handle.query_channels(QueryChannelsRequest {
+        pagination: Some(PageRequest::all()),
+    })?;
+
+    Ok(channels
+        .into_iter()
+        .map(|channel| (channel.port_id, channel.channel_id))
+        .partition(|(port_id, channel_id)| filter.is_allowed(port_id, channel_id)))
+}
+
 pub fn assert_eventually_channel_established<ChainA: ChainHandle, ChainB: ChainHandle>(
     handle_a: &ChainA,
     handle_b: &ChainB,
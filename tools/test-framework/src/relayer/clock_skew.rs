@@ -0,0 +1,82 @@
+/*!
+   A [`ChainHandle`](ibc_relayer::chain::handle::ChainHandle) wrapper that
+   skews the block timestamps reported by a chain, to test how the relayer
+   behaves when the clocks of the chains are offset from each other.
+*/
+
+use core::time::Duration;
+
+use ibc_relayer::chain::endpoint::ChainStatus;
+use ibc_relayer::error::Error;
+use ibc_relayer_types::timestamp::Timestamp;
+
+use crate::relayer::hooked::{ChainHandleHooks, HookedChainHandle};
+
+/**
+   The offset of the clock of a chain from the actual time, as simulated
+   by a [`ClockSkewChainHandle`].
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSkew {
+    /// The clock of the chain is in sync.
+    Synced,
+    /// The clock of the chain is ahead of the actual time by the given duration.
+    Ahead(Duration),
+    /// The clock of the chain is behind the actual time by the given duration.
+    Behind(Duration),
+}
+
+impl ClockSkew {
+    /**
+       Skews the given timestamp by this offset.
+    */
+    pub fn apply(&self, timestamp: Timestamp) -> Result<Timestamp, Error> {
+        let skewed = match self {
+            ClockSkew::Synced => Ok(timestamp),
+            ClockSkew::Ahead(offset) => timestamp + *offset,
+            ClockSkew::Behind(offset) => timestamp - *offset,
+        };
+
+        skewed.map_err(|_| Error::query(format!("timestamp {} skewed by {:?}", timestamp, self)))
+    }
+}
+
+impl Default for ClockSkew {
+    fn default() -> Self {
+        ClockSkew::Synced
+    }
+}
+
+impl ChainHandleHooks for ClockSkew {
+    fn on_application_status(&self, status: ChainStatus) -> Result<ChainStatus, Error> {
+        Ok(ChainStatus {
+            height: status.height,
+            timestamp: self.apply(status.timestamp)?,
+        })
+    }
+}
+
+/**
+   Wraps a [`ChainHandle`](ibc_relayer::chain::handle::ChainHandle) and
+   skews the timestamp of the latest block returned by
+   [`query_application_status`](ibc_relayer::chain::handle::ChainHandle::query_application_status)
+   by a [`ClockSkew`], to simulate a chain whose clock is offset from the
+   other chains.
+
+   The relayer relies on this timestamp to check that the headers it
+   submits in client updates are not too far in the future for the
+   destination chain, given the `max_clock_drift` of the client.
+
+   All other methods are forwarded unchanged to the inner handle.
+*/
+pub type ClockSkewChainHandle<Handle> = HookedChainHandle<Handle, ClockSkew>;
+
+impl<Handle> ClockSkewChainHandle<Handle> {
+    pub fn new(handle: Handle, skew: ClockSkew) -> Self {
+        Self::with_hooks(handle, skew)
+    }
+
+    pub fn skew(&self) -> ClockSkew {
+        *self.hooks()
+    }
+}
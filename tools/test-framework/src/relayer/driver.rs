@@ -2,12 +2,19 @@
    Driver for spawning the relayer.
 */
 
+use crossbeam_channel::Sender;
+use eyre::eyre;
 use ibc_relayer::chain::handle::CountingAndCachingChainHandle;
+use ibc_relayer::config::filter::PacketFilter;
 use ibc_relayer::config::Config;
 use ibc_relayer::registry::SharedRegistry;
+use ibc_relayer::supervisor::cmd::SupervisorCmd;
 use ibc_relayer::supervisor::{spawn_supervisor, SupervisorHandle, SupervisorOptions};
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
+use crate::bootstrap::binary::chain::save_relayer_config;
 use crate::error::Error;
 use crate::types::env::{EnvWriter, ExportEnv};
 use crate::util::suspend::hang_on_error;
@@ -48,6 +55,13 @@ pub struct RelayerDriver {
        closure in [`with_supervisor`](Self::with_supervisor) fails.
     */
     pub hang_on_fail: bool,
+
+    /**
+       The command senders of the supervisors spawned by this driver, which
+       are notified by [`set_filter`](Self::set_filter). The senders of the
+       supervisors that have stopped are dropped on the next notification.
+    */
+    pub supervisors: Arc<Mutex<Vec<Sender<SupervisorCmd>>>>,
 }
 
 impl RelayerDriver {
@@ -55,7 +69,7 @@ impl RelayerDriver {
        Spawns the relayer supervisor and return the [`SupervisorHandle`].
     */
     pub fn spawn_supervisor(&self) -> Result<SupervisorHandle, Error> {
-        spawn_supervisor(
+        let handle = spawn_supervisor(
             self.config.clone(),
             self.registry.clone(),
             None,
@@ -64,7 +78,14 @@ impl RelayerDriver {
                 force_full_scan: false,
             },
         )
-        .map_err(Error::supervisor)
+        .map_err(Error::supervisor)?;
+
+        self.supervisors
+            .lock()
+            .map_err(|_| eyre!("poisoned supervisors lock"))?
+            .push(handle.sender.clone());
+
+        Ok(handle)
     }
 
     /**
@@ -80,6 +101,71 @@ impl RelayerDriver {
 
         hang_on_error(self.hang_on_fail, cont)
     }
+
+    /**
+       Replaces the packet filter of the chain with the given [`ChainId`],
+       and writes the updated config back to [`config_path`](Self::config_path).
+
+       The new filter is also hot-loaded into the running supervisors spawned
+       by this driver, which stop relaying on the channels it no longer allows,
+       as well as used by the supervisors spawned after this call. This call
+       returns once every running supervisor has applied the new filter.
+    */
+    pub fn set_filter(&mut self, chain_id: &ChainId, filter: PacketFilter) -> Result<(), Error> {
+        let chain_config = self
+            .config
+            .find_chain_mut(chain_id)
+            .ok_or_else(|| eyre!("chain {} not found in relayer config", chain_id))?;
+
+        chain_config.packet_filter = filter.clone();
+
+        save_relayer_config(&self.config, &self.config_path)?;
+
+        self.supervisors
+            .lock()
+            .map_err(|_| eyre!("poisoned supervisors lock"))?
+            .retain(|sender| {
+                let (reply_to, reply) = crossbeam_channel::bounded(1);
+
+                // A supervisor that has stopped drops the command, and thus the reply sender
+                sender
+                    .send(SupervisorCmd::SetPacketFilter(
+                        chain_id.clone(),
+                        filter.clone(),
+                        reply_to,
+                    ))
+                    .is_ok()
+                    && reply.recv().is_ok()
+            });
+
+        Ok(())
+    }
+
+    /**
+       Replaces the name of the key that the relayer signs transactions with
+       on the chain with the given [`ChainId`], and writes the updated config
+       back to [`config_path`](Self::config_path).
+
+       The key must already be in the keyring of the chain. As the chain
+       runtimes read the key name from the config they are spawned with, the
+       [`registry`](Self::registry) is replaced by a new registry, so that the
+       runtimes spawned from then on sign with the new key. A test should stop
+       any running supervisor before calling this method.
+    */
+    pub fn set_key(&mut self, chain_id: &ChainId, key_name: String) -> Result<(), Error> {
+        let chain_config = self
+            .config
+            .find_chain_mut(chain_id)
+            .ok_or_else(|| eyre!("chain {} not found in relayer config", chain_id))?;
+
+        chain_config.key_name = key_name;
+
+        save_relayer_config(&self.config, &self.config_path)?;
+
+        self.registry = SharedRegistry::new(self.config.clone());
+
+        Ok(())
+    }
 }
 
 impl ExportEnv for RelayerDriver {
@@ -0,0 +1,478 @@
+/*!
+   A [`ChainHandle`] wrapper that forwards all methods to an inner handle,
+   except for a few hook points where the result of the inner handle can be
+   altered, to simulate misbehaving chains in tests.
+*/
+
+use core::fmt::{self, Debug, Display};
+use crossbeam_channel as channel;
+use tracing::Span;
+
+use ibc_relayer::account::Balance;
+use ibc_relayer::chain::client::ClientSettings;
+use ibc_relayer::chain::endpoint::{ChainStatus, HealthCheck};
+use ibc_relayer::chain::handle::{ChainHandle, ChainRequest, Subscription};
+use ibc_relayer::chain::requests::{
+    IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
+    QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
+    QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
+    QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
+    QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
+    QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
+    QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
+    QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
+    QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+};
+use ibc_relayer::chain::tracking::TrackedMsgs;
+use ibc_relayer::client_state::{AnyClientState, IdentifiedAnyClientState};
+use ibc_relayer::config::ChainConfig;
+use ibc_relayer::connection::ConnectionMsgType;
+use ibc_relayer::consensus_state::{AnyConsensusState, AnyConsensusStateWithHeight};
+use ibc_relayer::denom::DenomTrace;
+use ibc_relayer::error::Error;
+use ibc_relayer::event::IbcEventWithHeight;
+use ibc_relayer::keyring::KeyEntry;
+use ibc_relayer::light_client::AnyHeader;
+use ibc_relayer::misbehaviour::MisbehaviourEvidence;
+use ibc_relayer_types::core::ics02_client::events::UpdateClient;
+use ibc_relayer_types::core::ics03_connection::connection::IdentifiedConnectionEnd;
+use ibc_relayer_types::core::ics04_channel::channel::IdentifiedChannelEnd;
+use ibc_relayer_types::core::ics04_channel::packet::{PacketMsgType, Sequence};
+use ibc_relayer_types::core::ics23_commitment::merkle::MerkleProof;
+use ibc_relayer_types::{
+    core::ics03_connection::connection::ConnectionEnd,
+    core::ics03_connection::version::Version,
+    core::ics04_channel::channel::ChannelEnd,
+    core::ics23_commitment::commitment::CommitmentPrefix,
+    core::ics24_host::identifier::ChainId,
+    core::ics24_host::identifier::ChannelId,
+    core::ics24_host::identifier::{ClientId, ConnectionId, PortId},
+    events::IbcEvent,
+    proofs::Proofs,
+    signer::Signer,
+    Height,
+};
+
+/**
+   The hook points of a [`HookedChainHandle`]. Each hook is called with
+   either the arguments passed to, or the result returned by, the
+   corresponding method of the inner handle, and returns what is passed on
+   in their place. By default, they are passed on unchanged.
+
+   The [`Default`] value of the hooks is used when the wrapper is constructed
+   through [`ChainHandle::new`].
+*/
+pub trait ChainHandleHooks: Clone + Debug + Default + Send + Sync + 'static {
+    /**
+       Called with the messages passed to
+       [`ChainHandle::send_messages_and_wait_commit`] and
+       [`ChainHandle::send_messages_and_wait_check_tx`], before they are
+       sent by the inner handle.
+    */
+    fn on_send_messages(&self, tracked_msgs: TrackedMsgs) -> Result<TrackedMsgs, Error> {
+        Ok(tracked_msgs)
+    }
+
+    /**
+       Called with the status returned by
+       [`ChainHandle::query_application_status`].
+    */
+    fn on_application_status(&self, status: ChainStatus) -> Result<ChainStatus, Error> {
+        Ok(status)
+    }
+
+    /**
+       Called with the proofs returned by [`ChainHandle::build_packet_proofs`].
+    */
+    fn on_packet_proofs(&self, proofs: Proofs) -> Result<Proofs, Error> {
+        Ok(proofs)
+    }
+}
+
+/**
+   Wraps a [`ChainHandle`] and passes the arguments or results of the
+   methods with a hook point in [`ChainHandleHooks`] through the given hooks.
+
+   All other methods are forwarded unchanged to the inner handle.
+*/
+#[derive(Debug, Clone)]
+pub struct HookedChainHandle<Handle, Hooks> {
+    inner: Handle,
+    hooks: Hooks,
+}
+
+impl<Handle, Hooks> HookedChainHandle<Handle, Hooks> {
+    pub fn with_hooks(handle: Handle, hooks: Hooks) -> Self {
+        Self {
+            inner: handle,
+            hooks,
+        }
+    }
+
+    pub fn inner(&self) -> &Handle {
+        &self.inner
+    }
+
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+}
+
+impl<Handle: ChainHandle, Hooks: Debug> Display for HookedChainHandle<Handle, Hooks> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "HookedChainHandle {{ chain_id: {}, hooks: {:?} }}",
+            self.inner.id(),
+            self.hooks
+        )
+    }
+}
+
+impl<Handle: ChainHandle, Hooks: ChainHandleHooks> ChainHandle
+    for HookedChainHandle<Handle, Hooks>
+{
+    fn new(chain_id: ChainId, sender: channel::Sender<(Span, ChainRequest)>) -> Self {
+        Self::with_hooks(Handle::new(chain_id, sender), Hooks::default())
+    }
+
+    fn id(&self) -> ChainId {
+        self.inner.id()
+    }
+
+    fn shutdown(&self) -> Result<(), Error> {
+        self.inner.shutdown()
+    }
+
+    fn health_check(&self) -> Result<HealthCheck, Error> {
+        self.inner.health_check()
+    }
+
+    fn subscribe(&self) -> Result<Subscription, Error> {
+        self.inner.subscribe()
+    }
+
+    fn send_messages_and_wait_commit(
+        &self,
+        tracked_msgs: TrackedMsgs,
+    ) -> Result<Vec<IbcEventWithHeight>, Error> {
+        let tracked_msgs = self.hooks.on_send_messages(tracked_msgs)?;
+
+        self.inner.send_messages_and_wait_commit(tracked_msgs)
+    }
+
+    fn send_messages_and_wait_check_tx(
+        &self,
+        tracked_msgs: TrackedMsgs,
+    ) -> Result<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>, Error> {
+        let tracked_msgs = self.hooks.on_send_messages(tracked_msgs)?;
+
+        self.inner.send_messages_and_wait_check_tx(tracked_msgs)
+    }
+
+    fn get_signer(&self) -> Result<Signer, Error> {
+        self.inner.get_signer()
+    }
+
+    fn config(&self) -> Result<ChainConfig, Error> {
+        self.inner.config()
+    }
+
+    fn get_key(&self) -> Result<KeyEntry, Error> {
+        self.inner.get_key()
+    }
+
+    fn add_key(&self, key_name: String, key: KeyEntry) -> Result<(), Error> {
+        self.inner.add_key(key_name, key)
+    }
+
+    fn ibc_version(&self) -> Result<Option<semver::Version>, Error> {
+        self.inner.ibc_version()
+    }
+
+    fn query_application_status(&self) -> Result<ChainStatus, Error> {
+        let status = self.inner.query_application_status()?;
+
+        self.hooks.on_application_status(status)
+    }
+
+    fn query_latest_height(&self) -> Result<Height, Error> {
+        self.inner.query_latest_height()
+    }
+
+    fn query_clients(
+        &self,
+        request: QueryClientStatesRequest,
+    ) -> Result<Vec<IdentifiedAnyClientState>, Error> {
+        self.inner.query_clients(request)
+    }
+
+    fn query_client_state(
+        &self,
+        request: QueryClientStateRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(AnyClientState, Option<MerkleProof>), Error> {
+        self.inner.query_client_state(request, include_proof)
+    }
+
+    fn query_client_connections(
+        &self,
+        request: QueryClientConnectionsRequest,
+    ) -> Result<Vec<ConnectionId>, Error> {
+        self.inner.query_client_connections(request)
+    }
+
+    fn query_consensus_states(
+        &self,
+        request: QueryConsensusStatesRequest,
+    ) -> Result<Vec<AnyConsensusStateWithHeight>, Error> {
+        self.inner.query_consensus_states(request)
+    }
+
+    fn query_consensus_state(
+        &self,
+        request: QueryConsensusStateRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error> {
+        self.inner.query_consensus_state(request, include_proof)
+    }
+
+    fn query_upgraded_client_state(
+        &self,
+        request: QueryUpgradedClientStateRequest,
+    ) -> Result<(AnyClientState, MerkleProof), Error> {
+        self.inner.query_upgraded_client_state(request)
+    }
+
+    fn query_upgraded_consensus_state(
+        &self,
+        request: QueryUpgradedConsensusStateRequest,
+    ) -> Result<(AnyConsensusState, MerkleProof), Error> {
+        self.inner.query_upgraded_consensus_state(request)
+    }
+
+    fn query_commitment_prefix(&self) -> Result<CommitmentPrefix, Error> {
+        self.inner.query_commitment_prefix()
+    }
+
+    fn query_compatible_versions(&self) -> Result<Vec<Version>, Error> {
+        self.inner.query_compatible_versions()
+    }
+
+    fn query_connection(
+        &self,
+        request: QueryConnectionRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error> {
+        self.inner.query_connection(request, include_proof)
+    }
+
+    fn query_connections(
+        &self,
+        request: QueryConnectionsRequest,
+    ) -> Result<Vec<IdentifiedConnectionEnd>, Error> {
+        self.inner.query_connections(request)
+    }
+
+    fn query_connection_channels(
+        &self,
+        request: QueryConnectionChannelsRequest,
+    ) -> Result<Vec<IdentifiedChannelEnd>, Error> {
+        self.inner.query_connection_channels(request)
+    }
+
+    fn query_next_sequence_receive(
+        &self,
+        request: QueryNextSequenceReceiveRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+        self.inner
+            .query_next_sequence_receive(request, include_proof)
+    }
+
+    fn query_channels(
+        &self,
+        request: QueryChannelsRequest,
+    ) -> Result<Vec<IdentifiedChannelEnd>, Error> {
+        self.inner.query_channels(request)
+    }
+
+    fn query_channel(
+        &self,
+        request: QueryChannelRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error> {
+        self.inner.query_channel(request, include_proof)
+    }
+
+    fn query_channel_client_state(
+        &self,
+        request: QueryChannelClientStateRequest,
+    ) -> Result<Option<IdentifiedAnyClientState>, Error> {
+        self.inner.query_channel_client_state(request)
+    }
+
+    fn build_header(
+        &self,
+        trusted_height: Height,
+        target_height: Height,
+        client_state: AnyClientState,
+    ) -> Result<(AnyHeader, Vec<AnyHeader>), Error> {
+        self.inner
+            .build_header(trusted_height, target_height, client_state)
+    }
+
+    /// Constructs a client state at the given height
+    fn build_client_state(
+        &self,
+        height: Height,
+        settings: ClientSettings,
+    ) -> Result<AnyClientState, Error> {
+        self.inner.build_client_state(height, settings)
+    }
+
+    /// Constructs a consensus state at the given height
+    fn build_consensus_state(
+        &self,
+        trusted: Height,
+        target: Height,
+        client_state: AnyClientState,
+    ) -> Result<AnyConsensusState, Error> {
+        self.inner
+            .build_consensus_state(trusted, target, client_state)
+    }
+
+    fn check_misbehaviour(
+        &self,
+        update: UpdateClient,
+        client_state: AnyClientState,
+    ) -> Result<Option<MisbehaviourEvidence>, Error> {
+        self.inner.check_misbehaviour(update, client_state)
+    }
+
+    fn build_connection_proofs_and_client_state(
+        &self,
+        message_type: ConnectionMsgType,
+        connection_id: &ConnectionId,
+        client_id: &ClientId,
+        height: Height,
+    ) -> Result<(Option<AnyClientState>, Proofs), Error> {
+        self.inner.build_connection_proofs_and_client_state(
+            message_type,
+            connection_id,
+            client_id,
+            height,
+        )
+    }
+
+    fn build_channel_proofs(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        height: Height,
+    ) -> Result<Proofs, Error> {
+        self.inner.build_channel_proofs(port_id, channel_id, height)
+    }
+
+    fn build_packet_proofs(
+        &self,
+        packet_type: PacketMsgType,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: Sequence,
+        height: Height,
+    ) -> Result<Proofs, Error> {
+        let proofs =
+            self.inner
+                .build_packet_proofs(packet_type, port_id, channel_id, sequence, height)?;
+
+        self.hooks.on_packet_proofs(proofs)
+    }
+
+    fn query_packet_commitment(
+        &self,
+        request: QueryPacketCommitmentRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+        self.inner.query_packet_commitment(request, include_proof)
+    }
+
+    fn query_packet_commitments(
+        &self,
+        request: QueryPacketCommitmentsRequest,
+    ) -> Result<(Vec<Sequence>, Height), Error> {
+        self.inner.query_packet_commitments(request)
+    }
+
+    fn query_packet_receipt(
+        &self,
+        request: QueryPacketReceiptRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+        self.inner.query_packet_receipt(request, include_proof)
+    }
+
+    fn query_unreceived_packets(
+        &self,
+        request: QueryUnreceivedPacketsRequest,
+    ) -> Result<Vec<Sequence>, Error> {
+        self.inner.query_unreceived_packets(request)
+    }
+
+    fn query_packet_acknowledgement(
+        &self,
+        request: QueryPacketAcknowledgementRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+        self.inner
+            .query_packet_acknowledgement(request, include_proof)
+    }
+
+    fn query_packet_acknowledgements(
+        &self,
+        request: QueryPacketAcknowledgementsRequest,
+    ) -> Result<(Vec<Sequence>, Height), Error> {
+        self.inner.query_packet_acknowledgements(request)
+    }
+
+    fn query_unreceived_acknowledgements(
+        &self,
+        request: QueryUnreceivedAcksRequest,
+    ) -> Result<Vec<Sequence>, Error> {
+        self.inner.query_unreceived_acknowledgements(request)
+    }
+
+    fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEventWithHeight>, Error> {
+        self.inner.query_txs(request)
+    }
+
+    fn query_blocks(
+        &self,
+        request: QueryBlockRequest,
+    ) -> Result<(Vec<IbcEvent>, Vec<IbcEvent>), Error> {
+        self.inner.query_blocks(request)
+    }
+
+    fn query_host_consensus_state(
+        &self,
+        request: QueryHostConsensusStateRequest,
+    ) -> Result<AnyConsensusState, Error> {
+        self.inner.query_host_consensus_state(request)
+    }
+
+    fn query_balance(
+        &self,
+        key_name: Option<String>,
+        denom: Option<String>,
+    ) -> Result<Balance, Error> {
+        self.inner.query_balance(key_name, denom)
+    }
+
+    fn query_all_balances(&self, key_name: Option<String>) -> Result<Vec<Balance>, Error> {
+        self.inner.query_all_balances(key_name)
+    }
+
+    fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error> {
+        self.inner.query_denom_trace(hash)
+    }
+}
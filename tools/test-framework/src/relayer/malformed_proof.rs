@@ -0,0 +1,80 @@
+/*!
+   A [`ChainHandle`](ibc_relayer::chain::handle::ChainHandle) wrapper that
+   can be switched to return malformed packet proofs, to test that the
+   relayer handles undecodable proofs gracefully instead of panicking.
+*/
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use ibc_relayer::error::Error;
+use ibc_relayer_types::core::ics23_commitment::commitment::CommitmentProofBytes;
+use ibc_relayer_types::proofs::Proofs;
+
+use crate::relayer::hooked::{ChainHandleHooks, HookedChainHandle};
+
+/**
+   The switch of a [`MalformedProofChainHandle`]. Clones of the switch
+   share the same state.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct MalformedProofInjection {
+    inject: Arc<AtomicBool>,
+}
+
+impl ChainHandleHooks for MalformedProofInjection {
+    fn on_packet_proofs(&self, proofs: Proofs) -> Result<Proofs, Error> {
+        if self.inject.load(Ordering::SeqCst) {
+            malformed_proofs(&proofs)
+        } else {
+            Ok(proofs)
+        }
+    }
+}
+
+/**
+   Returns a copy of the given proofs, with the object proof replaced by
+   bytes that cannot be decoded as a `MerkleProof`.
+*/
+fn malformed_proofs(proofs: &Proofs) -> Result<Proofs, Error> {
+    let object_proof =
+        CommitmentProofBytes::try_from(vec![0xFF; 32]).map_err(Error::malformed_proof)?;
+
+    Proofs::new(
+        object_proof,
+        proofs.client_proof().clone(),
+        proofs.consensus_proof(),
+        proofs.other_proof().clone(),
+        proofs.height(),
+    )
+    .map_err(Error::malformed_proof)
+}
+
+/**
+   Wraps a [`ChainHandle`](ibc_relayer::chain::handle::ChainHandle) and,
+   while injection is enabled, replaces the object proof returned by
+   [`build_packet_proofs`](ibc_relayer::chain::handle::ChainHandle::build_packet_proofs)
+   with bytes that do not decode into a valid commitment proof.
+
+   All other methods are forwarded unchanged to the inner handle. Clones of
+   the wrapper share the same injection switch, so the switch can be
+   toggled after the handle has been passed to a [`Link`](ibc_relayer::link::Link).
+*/
+pub type MalformedProofChainHandle<Handle> = HookedChainHandle<Handle, MalformedProofInjection>;
+
+impl<Handle> MalformedProofChainHandle<Handle> {
+    pub fn new(handle: Handle) -> Self {
+        Self::with_hooks(handle, MalformedProofInjection::default())
+    }
+
+    /**
+       Enable or disable the injection of malformed packet proofs.
+    */
+    pub fn inject_malformed_proofs(&self, inject: bool) {
+        self.hooks().inject.store(inject, Ordering::SeqCst);
+    }
+
+    pub fn is_injecting(&self) -> bool {
+        self.hooks().inject.load(Ordering::SeqCst)
+    }
+}
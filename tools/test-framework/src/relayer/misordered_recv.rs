@@ -0,0 +1,60 @@
+/*!
+   A [`ChainHandle`](ibc_relayer::chain::handle::ChainHandle) wrapper that
+   submits the packets received by a chain out of order, to test that the
+   relaying of packets out of order on ordered channels is detected.
+*/
+
+use ibc_relayer::chain::tracking::TrackedMsgs;
+use ibc_relayer::error::Error;
+use ibc_relayer_types::core::ics04_channel::msgs::recv_packet::TYPE_URL as RECV_PACKET_TYPE_URL;
+
+use crate::relayer::hooked::{ChainHandleHooks, HookedChainHandle};
+
+/**
+   Reverses the order of the `MsgRecvPacket` messages in each batch of
+   messages sent, leaving all other messages in place.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct RecvPacketMisordering;
+
+impl ChainHandleHooks for RecvPacketMisordering {
+    fn on_send_messages(&self, mut tracked_msgs: TrackedMsgs) -> Result<TrackedMsgs, Error> {
+        let positions: Vec<usize> = tracked_msgs
+            .msgs
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| msg.type_url == RECV_PACKET_TYPE_URL)
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut recv_msgs: Vec<_> = positions
+            .iter()
+            .map(|i| tracked_msgs.msgs[*i].clone())
+            .collect();
+
+        recv_msgs.reverse();
+
+        for (i, msg) in positions.into_iter().zip(recv_msgs) {
+            tracked_msgs.msgs[i] = msg;
+        }
+
+        Ok(tracked_msgs)
+    }
+}
+
+/**
+   Wraps a [`ChainHandle`](ibc_relayer::chain::handle::ChainHandle) of the
+   destination chain of packets and reverses the order of the packets
+   received in each transaction sent by the relayer.
+
+   On an ordered channel, the destination chain rejects a transaction that
+   receives any packet before the previous ones, so relaying more than one
+   packet at a time through this handle is expected to fail.
+*/
+pub type MisorderedRecvChainHandle<Handle> = HookedChainHandle<Handle, RecvPacketMisordering>;
+
+impl<Handle> MisorderedRecvChainHandle<Handle> {
+    pub fn new(handle: Handle) -> Self {
+        Self::with_hooks(handle, RecvPacketMisordering)
+    }
+}
@@ -20,9 +20,15 @@
 
 pub mod chain;
 pub mod channel;
+pub mod clock_skew;
 pub mod connection;
 pub mod driver;
 pub mod foreign_client;
+pub mod hooked;
+pub mod malformed_proof;
+pub mod misordered_recv;
+pub mod packet;
 pub mod refresh;
+pub mod throughput;
 pub mod transfer;
 pub mod tx;
@@ -0,0 +1,309 @@
+/*!
+   Helpers for sending and asserting the relaying of packets between
+   connected chains.
+*/
+
+use core::time::Duration;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{
+    IncludeProof, QueryHeight, QueryNextSequenceReceiveRequest, QueryPacketCommitmentsRequest,
+    QueryUnreceivedPacketsRequest,
+};
+use ibc_relayer::link::{Link, LinkParameters};
+use ibc_relayer_types::core::ics04_channel::packet::Sequence;
+use ibc_relayer_types::events::IbcEvent;
+use std::thread::sleep;
+use tracing::info;
+
+use crate::chain::ext::transfer::ChainTransferMethodsExt;
+use crate::error::Error;
+use crate::types::binary::chains::ConnectedChains;
+use crate::types::binary::channel::ConnectedChannel;
+use crate::types::id::{TaggedChannelIdRef, TaggedPortIdRef};
+use crate::types::single::node::TaggedFullNodeExt;
+use crate::types::wallet::TaggedTestWalletsExt;
+
+/**
+   Query the next sequence number that is expected to be received
+   on `Chain` for the given port and channel.
+*/
+pub fn query_next_sequence_receive<Chain: ChainHandle, Counterparty>(
+    handle: &Chain,
+    port_id: &TaggedPortIdRef<Chain, Counterparty>,
+    channel_id: &TaggedChannelIdRef<Chain, Counterparty>,
+) -> Result<Sequence, Error> {
+    let (sequence, _) = handle.query_next_sequence_receive(
+        QueryNextSequenceReceiveRequest {
+            port_id: port_id.into_value().clone(),
+            channel_id: channel_id.into_value().clone(),
+            height: QueryHeight::Latest,
+        },
+        IncludeProof::No,
+    )?;
+
+    Ok(sequence)
+}
+
+/**
+   Query the sequences of the packets sent from `Chain` on the given
+   port and channel that still have their commitments stored.
+*/
+pub fn query_packet_commitments<Chain: ChainHandle, Counterparty>(
+    handle: &Chain,
+    port_id: &TaggedPortIdRef<Chain, Counterparty>,
+    channel_id: &TaggedChannelIdRef<Chain, Counterparty>,
+) -> Result<Vec<Sequence>, Error> {
+    let (sequences, _) = handle.query_packet_commitments(QueryPacketCommitmentsRequest {
+        port_id: port_id.into_value().clone(),
+        channel_id: channel_id.into_value().clone(),
+        pagination: None,
+    })?;
+
+    Ok(sequences)
+}
+
+/**
+   Query the sequences of the packets sent from chain A over the given
+   channel that have not been received yet on chain B.
+
+   Unlike the next receive sequence, which is only advanced on ordered
+   channels, this relies on the packet commitments on chain A and the
+   packet receipts on chain B, and so works with unordered channels too.
+*/
+pub fn query_unreceived_packets<ChainA: ChainHandle, ChainB: ChainHandle>(
+    handle_a: &ChainA,
+    handle_b: &ChainB,
+    channel: &ConnectedChannel<ChainA, ChainB>,
+) -> Result<Vec<Sequence>, Error> {
+    let commitments = query_packet_commitments(
+        handle_a,
+        &channel.port_a.as_ref(),
+        &channel.channel_id_a.as_ref(),
+    )?;
+
+    if commitments.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sequences = handle_b.query_unreceived_packets(QueryUnreceivedPacketsRequest {
+        port_id: channel.port_b.value().clone(),
+        channel_id: channel.channel_id_b.value().clone(),
+        packet_commitment_sequences: commitments,
+    })?;
+
+    Ok(sequences)
+}
+
+/**
+   Assert that the given sequences of received packets, in the order in
+   which they were received, are strictly increasing.
+
+   On an ordered channel, each packet can only be received after all the
+   packets with a smaller sequence, so a sequence received after a larger
+   or equal one indicates that a packet has been received out of order.
+*/
+pub fn assert_sequences_in_order(sequences: &[Sequence]) -> Result<(), Error> {
+    for window in sequences.windows(2) {
+        if window[1] <= window[0] {
+            return Err(Error::assertion(format!(
+                "expect packets to be received in order, but observed sequence {} after {}",
+                window[1], window[0]
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/**
+   Extract the sequences of the packets received in the given events
+   returned by the relayer, in the order in which they were received.
+
+   Fails if any of the events is a chain error, e.g. because the
+   destination chain rejected a transaction receiving the packets.
+*/
+pub fn received_sequences(events: &[IbcEvent]) -> Result<Vec<Sequence>, Error> {
+    let mut sequences = Vec::new();
+
+    for event in events {
+        match event {
+            IbcEvent::ReceivePacket(event) => sequences.push(event.packet.sequence),
+            IbcEvent::ChainError(e) => {
+                return Err(Error::assertion(format!(
+                    "expect packets to be received, but relaying failed with chain error: {}",
+                    e
+                )))
+            }
+            _ => {}
+        }
+    }
+
+    Ok(sequences)
+}
+
+/**
+   Send a burst of `packet_count` IBC token transfers from chain A to
+   chain B over the given channel, and assert that the relayer delivers
+   all of them in sequence order.
+
+   This is the same as [`relay_and_assert_ordered_with`], with the
+   handle of chain B taken from the connected chains.
+*/
+pub fn relay_and_assert_ordered<ChainA: ChainHandle, ChainB: ChainHandle>(
+    chains: &ConnectedChains<ChainA, ChainB>,
+    channel: &ConnectedChannel<ChainA, ChainB>,
+    packet_count: u64,
+) -> Result<(), Error> {
+    relay_and_assert_ordered_with(chains, channel, chains.handle_b().clone(), packet_count)
+}
+
+/**
+   Send a burst of `packet_count` IBC token transfers from chain A to
+   chain B over the given channel, relay all pending packets through a
+   [`Link`] to the given handle of chain B, and assert that they are
+   received in sequence order.
+
+   The received sequences are taken from the `ReceivePacket` events of
+   the transactions submitted by the relayer, which must start at the
+   next receive sequence of chain B before relaying and have no gaps,
+   and no packet may be left unreceived.
+
+   The relayer supervisor is expected to *not* be running when this is
+   called, so that the packets are relayed by the link only.
+*/
+pub fn relay_and_assert_ordered_with<
+    ChainA: ChainHandle,
+    ChainB: ChainHandle,
+    HandleB: ChainHandle,
+>(
+    chains: &ConnectedChains<ChainA, ChainB>,
+    channel: &ConnectedChannel<ChainA, ChainB>,
+    handle_b: HandleB,
+    packet_count: u64,
+) -> Result<(), Error> {
+    let denom_a = chains.node_a.denom();
+
+    let wallet_a = chains.node_a.wallets().user1().cloned();
+    let wallet_b = chains.node_b.wallets().user1().cloned();
+
+    let start_sequence = query_next_sequence_receive(
+        chains.handle_b(),
+        &channel.port_b.as_ref(),
+        &channel.channel_id_b.as_ref(),
+    )?;
+
+    info!(
+        "sending {} packets from chain {} to chain {} on ordered channel {}/{}",
+        packet_count,
+        chains.chain_id_a(),
+        chains.chain_id_b(),
+        channel.port_a,
+        channel.channel_id_a,
+    );
+
+    for _ in 0..packet_count {
+        chains.node_a.chain_driver().ibc_transfer_token(
+            &channel.port_a.as_ref(),
+            &channel.channel_id_a.as_ref(),
+            &wallet_a.as_ref(),
+            &wallet_b.address(),
+            &denom_a,
+            1,
+        )?;
+    }
+
+    let link = Link::new_from_opts(
+        chains.handle_a().clone(),
+        handle_b,
+        LinkParameters {
+            src_port_id: channel.port_a.value().clone(),
+            src_channel_id: channel.channel_id_a.value().clone(),
+        },
+        false,
+    )?;
+
+    let events = link.relay_recv_packet_and_timeout_messages()?;
+
+    let received = received_sequences(&events)?;
+
+    assert_sequences_in_order(&received)?;
+
+    let expected: Vec<Sequence> = (0..received.len() as u64)
+        .map(|i| Sequence::from(u64::from(start_sequence) + i))
+        .collect();
+
+    if received != expected {
+        return Err(Error::assertion(format!(
+            "expect packets to be received in order from sequence {}, but received {:?}",
+            start_sequence, received
+        )));
+    }
+
+    let unreceived = query_unreceived_packets(chains.handle_a(), chains.handle_b(), channel)?;
+
+    if (received.len() as u64) < packet_count || !unreceived.is_empty() {
+        return Err(Error::assertion(format!(
+            "expect all packets to be received, but received {:?} and left {:?} unreceived",
+            received, unreceived
+        )));
+    }
+
+    Ok(())
+}
+
+/**
+   Assert that the relayer does not relay any of the pending packets sent
+   from chain A on the given port and channel during `window`.
+
+   The packet commitments on chain A are queried before and after the
+   window. Since a commitment is only cleared once the acknowledgement
+   has been relayed back to chain A, any commitment that is missing after
+   the window indicates that the packet has been relayed.
+
+   This is typically used together with a deny packet filter, after
+   sending some packets over the filtered channel.
+*/
+pub fn assert_no_relay<ChainA: ChainHandle, ChainB: ChainHandle>(
+    chains: &ConnectedChains<ChainA, ChainB>,
+    port_id: &TaggedPortIdRef<ChainA, ChainB>,
+    channel_id: &TaggedChannelIdRef<ChainA, ChainB>,
+    window: Duration,
+) -> Result<(), Error> {
+    let before = query_packet_commitments(chains.handle_a(), port_id, channel_id)?;
+
+    if before.is_empty() {
+        return Err(Error::assertion(format!(
+            "expect pending packet commitments on chain {} for {}/{}, but found none",
+            chains.chain_id_a(),
+            port_id,
+            channel_id,
+        )));
+    }
+
+    info!(
+        "asserting that {} pending packets on chain {} for {}/{} are not relayed in {:?}",
+        before.len(),
+        chains.chain_id_a(),
+        port_id,
+        channel_id,
+        window,
+    );
+
+    sleep(window);
+
+    let after = query_packet_commitments(chains.handle_a(), port_id, channel_id)?;
+
+    let cleared: Vec<&Sequence> = before
+        .iter()
+        .filter(|sequence| !after.contains(sequence))
+        .collect();
+
+    if !cleared.is_empty() {
+        return Err(Error::assertion(format!(
+            "expect no packets on {}/{} to be relayed, but commitments for sequences {:?} were cleared",
+            port_id, channel_id, cleared
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+/*!
+   Helpers for measuring the packet relaying throughput between
+   connected chains in an N-ary setup.
+*/
+
+use core::fmt;
+use core::time::Duration;
+use eyre::eyre;
+use ibc_relayer::chain::handle::ChainHandle;
+use std::time::Instant;
+use tracing::info;
+
+use crate::chain::ext::transfer::ChainTransferMethodsExt;
+use crate::error::Error;
+use crate::relayer::packet::query_unreceived_packets;
+use crate::types::binary::channel::ConnectedChannel;
+use crate::types::nary::chains::NaryConnectedChains;
+use crate::types::nary::channel::ConnectedChannels;
+use crate::types::single::node::TaggedFullNodeExt;
+use crate::types::tagged::MonoTagged;
+use crate::types::wallet::TaggedTestWalletsExt;
+
+/**
+   The throughput measured for the channel from the chain at position
+   `chain_a` to the chain at position `chain_b`.
+*/
+#[derive(Debug, Clone)]
+pub struct PairThroughput {
+    pub chain_a: usize,
+    pub chain_b: usize,
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    pub packets_per_second: f64,
+}
+
+/**
+   The throughput measured by [`measure_throughput`] for each of the
+   requested pairs of chains.
+*/
+#[derive(Debug, Clone)]
+pub struct ThroughputReport {
+    pub duration: Duration,
+    pub pairs: Vec<PairThroughput>,
+}
+
+impl ThroughputReport {
+    /**
+       Get the measured throughput in packets per second from the chain at
+       position `chain_a` to the chain at position `chain_b`, if that pair
+       was measured.
+    */
+    pub fn packets_per_second(&self, chain_a: usize, chain_b: usize) -> Option<f64> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.chain_a == chain_a && pair.chain_b == chain_b)
+            .map(|pair| pair.packets_per_second)
+    }
+}
+
+impl fmt::Display for ThroughputReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "throughput over {:?}:", self.duration)?;
+
+        for pair in &self.pairs {
+            write!(
+                f,
+                " {} -> {}: {:.2} packets/s ({}/{} received);",
+                pair.chain_a,
+                pair.chain_b,
+                pair.packets_per_second,
+                pair.packets_received,
+                pair.packets_sent,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/**
+   Continuously send IBC token transfers over the channels between each
+   of the given pairs of chain positions for `duration`, and report the
+   number of packets per second that the relayer has delivered to the
+   destination chain of each pair.
+
+   The relayer supervisor is expected to be running when this is called.
+   The transfers are sent in a round-robin fashion across the pairs, and
+   the delivered packets are counted from the packets that are not yet
+   received on the destination chains at the start and the end of the
+   measurement, so that both ordered and unordered channels are supported.
+*/
+pub fn measure_throughput<Handle: ChainHandle, const SIZE: usize>(
+    chains: &NaryConnectedChains<Handle, SIZE>,
+    channels: &ConnectedChannels<Handle, SIZE>,
+    pairs: &[(usize, usize)],
+    duration: Duration,
+) -> Result<ThroughputReport, Error> {
+    for &(chain_a, chain_b) in pairs {
+        if chain_a >= SIZE || chain_b >= SIZE {
+            return Err(Error::generic(eyre!(
+                "cannot measure throughput beyond position {}/{}",
+                chain_a,
+                chain_b
+            )));
+        }
+
+        channel_between(channels, chain_a, chain_b)?;
+    }
+
+    let handles = chains.chain_handles();
+    let nodes = chains.full_nodes();
+
+    let unreceived_at_start = pairs
+        .iter()
+        .map(|&(chain_a, chain_b)| {
+            let unreceived = query_unreceived_packets(
+                &handles[chain_a],
+                &handles[chain_b],
+                channel_between(channels, chain_a, chain_b)?,
+            )?;
+
+            Ok(unreceived.len() as u64)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    info!(
+        "measuring relaying throughput for {} pairs of chains over {:?}",
+        pairs.len(),
+        duration
+    );
+
+    let mut packets_sent = vec![0; pairs.len()];
+
+    let start = Instant::now();
+
+    while start.elapsed() < duration {
+        for (i, &(chain_a, chain_b)) in pairs.iter().enumerate() {
+            let channel = channel_between(channels, chain_a, chain_b)?;
+
+            let node_a = MonoTagged::<Handle, _>::new(&nodes[chain_a]);
+            let node_b = MonoTagged::<Handle, _>::new(&nodes[chain_b]);
+
+            let wallet_a = node_a.wallets().user1().cloned();
+            let wallet_b = node_b.wallets().user1().cloned();
+
+            node_a.chain_driver().ibc_transfer_token(
+                &channel.port_a.as_ref(),
+                &channel.channel_id_a.as_ref(),
+                &wallet_a.as_ref(),
+                &wallet_b.address(),
+                &node_a.denom(),
+                1,
+            )?;
+
+            packets_sent[i] += 1;
+        }
+    }
+
+    let elapsed = start.elapsed();
+
+    let pairs = pairs
+        .iter()
+        .zip(unreceived_at_start)
+        .zip(packets_sent)
+        .map(
+            |((&(chain_a, chain_b), unreceived_at_start), packets_sent)| {
+                let unreceived_at_end = query_unreceived_packets(
+                    &handles[chain_a],
+                    &handles[chain_b],
+                    channel_between(channels, chain_a, chain_b)?,
+                )?
+                .len() as u64;
+
+                // Every packet pending at the start or sent since then has
+                // either been received by now, or is still pending.
+                let packets_received =
+                    (unreceived_at_start + packets_sent).saturating_sub(unreceived_at_end);
+
+                Ok(PairThroughput {
+                    chain_a,
+                    chain_b,
+                    packets_sent,
+                    packets_received,
+                    packets_per_second: packets_received as f64 / elapsed.as_secs_f64(),
+                })
+            },
+        )
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let report = ThroughputReport {
+        duration: elapsed,
+        pairs,
+    };
+
+    info!("{}", report);
+
+    Ok(report)
+}
+
+/**
+   Get the channel between the chains at position `chain_a` and `chain_b`,
+   which must be connected.
+*/
+fn channel_between<Handle: ChainHandle, const SIZE: usize>(
+    channels: &ConnectedChannels<Handle, SIZE>,
+    chain_a: usize,
+    chain_b: usize,
+) -> Result<&ConnectedChannel<Handle, Handle>, Error> {
+    channels.channels()[chain_a][chain_b]
+        .as_ref()
+        .ok_or_else(|| {
+            Error::generic(eyre!(
+                "no channel between the chains at position {}/{}",
+                chain_a,
+                chain_b
+            ))
+        })
+}
@@ -2,10 +2,13 @@
    Type definitions for connection that is connected between two chains.
 */
 
+use core::time::Duration;
 use ibc_relayer::chain::handle::ChainHandle;
 use ibc_relayer::connection::Connection;
 
 use super::client::ClientIdPair;
+use crate::error::Error;
+use crate::relayer::connection::query_connection_end;
 use crate::types::env::{EnvWriter, ExportEnv};
 use crate::types::id::TaggedConnectionId;
 
@@ -54,6 +57,17 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> ConnectedConnection<ChainA, Chain
         }
     }
 
+    /**
+       Query the connection end on chain A and return its configured
+       delay period.
+    */
+    pub fn delay(&self) -> Result<Duration, Error> {
+        let connection_end =
+            query_connection_end(&self.connection.a_chain(), &self.connection_id_a.as_ref())?;
+
+        Ok(connection_end.value().delay_period())
+    }
+
     /**
        Flip the position of chain A and B of the connection.
     */
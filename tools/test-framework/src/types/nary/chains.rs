@@ -2,12 +2,16 @@
    Constructs for N-ary connected chains.
 */
 
-use core::convert::{From, TryFrom};
+use core::convert::{From, TryFrom, TryInto};
+use core::time::Duration;
 use eyre::eyre;
 use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::client_state::AnyClientState;
 use ibc_relayer::foreign_client::ForeignClient;
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use ibc_relayer_types::Height;
 
-use crate::error::Error;
+use crate::error::{handle_generic_error, Error};
 use crate::types::binary::chains::ConnectedChains as BinaryConnectedChains;
 use crate::types::env::{prefix_writer, EnvWriter, ExportEnv};
 use crate::types::nary::aliases::*;
@@ -15,6 +19,7 @@ use crate::types::nary::foreign_client::*;
 use crate::types::single::node::FullNode;
 use crate::types::tagged::*;
 use crate::util::array::try_into_array;
+use crate::util::retry::assert_eventually_succeed;
 
 /**
    A fixed-size N-ary connected chains as specified by `SIZE`.
@@ -79,6 +84,40 @@ pub type NthConnectedChains<const CHAIN_A: usize, const CHAIN_B: usize, Handle>
 pub type NthFullNode<const TAG: usize, Handle> = MonoTagged<NthChainHandle<TAG, Handle>, FullNode>;
 
 impl<Handle: ChainHandle, const SIZE: usize> NaryConnectedChains<Handle, SIZE> {
+    /**
+       Construct a [`NaryConnectedChains`] from chains that are already
+       spawned, e.g. by a test case that manages the chain processes itself,
+       given their chain handles, full nodes and the `SIZE`x`SIZE` foreign
+       clients between them, without the framework spawning anything.
+
+       Fails if there is not exactly `SIZE` chain handles and full nodes,
+       or if the foreign clients are not `SIZE`x`SIZE`.
+    */
+    pub fn from_handles(
+        chain_handles: Vec<Handle>,
+        full_nodes: Vec<FullNode>,
+        foreign_clients: Vec<Vec<ForeignClient<Handle, Handle>>>,
+    ) -> Result<Self, Error> {
+        if chain_handles.len() != SIZE || full_nodes.len() != SIZE {
+            return Err(Error::generic(eyre!(
+                "expected {} chain handles and full nodes, got {} chain handles and {} full nodes",
+                SIZE,
+                chain_handles.len(),
+                full_nodes.len()
+            )));
+        }
+
+        if foreign_clients.len() != SIZE || foreign_clients.iter().any(|row| row.len() != SIZE) {
+            return Err(Error::generic(eyre!(
+                "expected {}x{} foreign clients",
+                SIZE,
+                SIZE
+            )));
+        }
+
+        DynamicConnectedChains::new(chain_handles, full_nodes, foreign_clients).try_into()
+    }
+
     /**
        Get a connected chain pair at position `CHAIN_A` and `CHAIN_B`, which
        must be less than `SIZE`.
@@ -177,6 +216,179 @@ impl<Handle: ChainHandle, const SIZE: usize> NaryConnectedChains<Handle, SIZE> {
     pub fn foreign_clients(&self) -> &ForeignClientPairs<Handle, SIZE> {
         &self.foreign_clients
     }
+
+    /**
+       Get the chain handle at position `index`, which is only known
+       at runtime, as when iterating over the chains.
+
+       Panics if `index` is not less than `SIZE`.
+
+       Example:
+
+       ```rust
+       # use ibc_test_framework::prelude::*;
+       fn log_chains<Handle: ChainHandle>(chains: &NaryConnectedChains<Handle, 3>) {
+           for i in 0..3 {
+               let handle: &Handle = chains.chain_handle(i);
+               let chain_id: &ChainId = chains.chain_id(i);
+
+               info!("chain {} at position {} has handle {}", chain_id, i, handle.id());
+           }
+
+           let (handle_a, handle_b) = chains.chain_handle_pair(0, 2);
+           info!("pairing chain {} with chain {}", handle_a.id(), handle_b.id());
+       }
+       ```
+    */
+    pub fn chain_handle(&self, index: usize) -> &Handle {
+        assert_index_in_bounds(index, SIZE);
+
+        &self.chain_handles[index]
+    }
+
+    /**
+       Get the chain ID of the chain at position `index`.
+
+       Panics if `index` is not less than `SIZE`.
+    */
+    pub fn chain_id(&self, index: usize) -> &ChainId {
+        assert_index_in_bounds(index, SIZE);
+
+        &self.full_nodes[index].chain_driver.chain_id
+    }
+
+    /**
+       Get the chain handles at positions `a` and `b`.
+
+       Panics if either `a` or `b` is not less than `SIZE`.
+    */
+    pub fn chain_handle_pair(&self, a: usize, b: usize) -> (&Handle, &Handle) {
+        (self.chain_handle(a), self.chain_handle(b))
+    }
+
+    /**
+       Map the chain handles by the given function, which is given the
+       position of each chain, including the chain handles held by the
+       foreign clients.
+    */
+    pub fn map_chain_handles<Handle2: ChainHandle>(
+        self,
+        f: impl Fn(usize, Handle) -> Handle2,
+    ) -> Result<NaryConnectedChains<Handle2, SIZE>, Error> {
+        let chain_handles = self
+            .chain_handles
+            .into_iter()
+            .enumerate()
+            .map(|(i, handle)| f(i, handle))
+            .collect();
+
+        // The foreign client at position `[i][j]` has chain `i` as its
+        // source and chain `j` as its destination.
+        let foreign_clients = self
+            .foreign_clients
+            .into_nested_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(i, foreign_clients_b)| {
+                foreign_clients_b
+                    .into_iter()
+                    .enumerate()
+                    .map(|(j, foreign_client)| {
+                        foreign_client.map_chain(|dst| f(j, dst), |src| f(i, src))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        DynamicConnectedChains::new(chain_handles, self.full_nodes.into(), foreign_clients)
+            .try_into()
+    }
+
+    /**
+       Wait until every chain has reached at least the given `height`,
+       polling the chains until `timeout` has elapsed.
+    */
+    pub fn wait_for_min_height(&self, height: Height, timeout: Duration) -> Result<(), Error> {
+        let interval = Duration::from_millis(500);
+        let attempts = (timeout.as_millis() / interval.as_millis()).clamp(1, u16::MAX as u128);
+
+        assert_eventually_succeed(
+            &format!("all chains to reach height {height}"),
+            attempts as u16,
+            interval,
+            || {
+                for (i, handle) in self.chain_handles.iter().enumerate() {
+                    let latest_height = handle.query_latest_height()?;
+
+                    if latest_height < height {
+                        return Err(Error::generic(eyre!(
+                            "chain {} at position {} is at height {}, below {}",
+                            handle.id(),
+                            i,
+                            latest_height,
+                            height
+                        )));
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    /**
+       Assert that every foreign client between the chains still has at
+       least `min_remaining` of its trusting period left, based on the time
+       elapsed since its latest consensus state.
+    */
+    pub fn assert_clients_valid(&self, min_remaining: Duration) -> Result<(), Error> {
+        for (source, clients) in self
+            .foreign_clients
+            .clone()
+            .into_nested_vec()
+            .iter()
+            .enumerate()
+        {
+            for (destination, client) in clients.iter().enumerate() {
+                let (client_state, elapsed) = client
+                    .validated_client_state()
+                    .map_err(handle_generic_error)?;
+
+                let trusting_period = match client_state {
+                    AnyClientState::Tendermint(state) => state.trusting_period,
+                };
+
+                let remaining = trusting_period.saturating_sub(elapsed.unwrap_or_default());
+
+                if remaining < min_remaining {
+                    return Err(Error::assertion(format!(
+                        "expect client {} from chain {} to chain {} to have at least {:?} of trusting period remaining, but only {:?} remains",
+                        client.id(),
+                        source,
+                        destination,
+                        min_remaining,
+                        remaining,
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+       Call `f` with the positions and the chain handles of every ordered
+       pair of distinct chains, i.e. both `(i, j)` and `(j, i)` are visited.
+    */
+    pub fn for_each_pair(&self, mut f: impl FnMut(usize, usize, &Handle, &Handle)) {
+        for (i, handle_a) in self.chain_handles.iter().enumerate() {
+            for (j, handle_b) in self.chain_handles.iter().enumerate() {
+                if i != j {
+                    f(i, j, handle_a, handle_b);
+                }
+            }
+        }
+    }
 }
 
 impl<Handle: ChainHandle> DynamicConnectedChains<Handle> {
@@ -253,3 +465,12 @@ impl<Handle: ChainHandle, const SIZE: usize> ExportEnv for NaryConnectedChains<H
         }
     }
 }
+
+fn assert_index_in_bounds(index: usize, size: usize) {
+    assert!(
+        index < size,
+        "cannot get chain at position {} beyond the {} connected chains",
+        index,
+        size
+    );
+}
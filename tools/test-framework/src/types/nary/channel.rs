@@ -18,21 +18,24 @@ use crate::util::array::try_into_nested_array;
 /**
    A fixed-size N-ary connected channels as specified by `SIZE`.
 
-   Contains `SIZE`x`SIZE` number of binary [`ConnectedChannel`]s.
+   Contains up to `SIZE`x`SIZE` number of binary [`ConnectedChannel`]s,
+   with entry `[i][j]` set only if the chains at position `i` and `j` are
+   connected, as with the connections they are bootstrapped with.
 */
 #[derive(Debug, Clone)]
 pub struct ConnectedChannels<Handle: ChainHandle, const SIZE: usize> {
-    channels: [[ConnectedChannel<Handle, Handle>; SIZE]; SIZE],
+    channels: [[Option<ConnectedChannel<Handle, Handle>>; SIZE]; SIZE],
 }
 
 /**
    A dynamic-sized N-ary connected channels, consist of a nested
    vector of binary [`ConnectedChannel`]s which must be of the
-   same length.
+   same length, with `None` entries for the pairs of chains that
+   are not connected.
 */
 #[derive(Debug, Clone)]
 pub struct DynamicConnectedChannels<Handle: ChainHandle> {
-    channels: Vec<Vec<ConnectedChannel<Handle, Handle>>>,
+    channels: Vec<Vec<Option<ConnectedChannel<Handle, Handle>>>>,
 }
 
 /**
@@ -66,7 +69,7 @@ pub type NthPortId<const CHAIN_A: usize, const CHAIN_B: usize, Handle> =
 impl<Handle: ChainHandle, const SIZE: usize> ConnectedChannels<Handle, SIZE> {
     /**
        Get the binary [`ConnectedChannel`] at position `CHAIN_A` and `CHAIN_B`,
-       which must be less than `SIZE`.
+       which must be less than `SIZE` and be connected.
     */
     pub fn channel_at<const CHAIN_A: usize, const CHAIN_B: usize>(
         &self,
@@ -78,7 +81,13 @@ impl<Handle: ChainHandle, const SIZE: usize> ConnectedChannels<Handle, SIZE> {
                 CHAIN_B
             )))
         } else {
-            let raw_channel = self.channels[CHAIN_A][CHAIN_B].clone();
+            let raw_channel = self.channels[CHAIN_A][CHAIN_B].clone().ok_or_else(|| {
+                Error::generic(eyre!(
+                    "no channel between the chains at position {}/{}",
+                    CHAIN_A,
+                    CHAIN_B
+                ))
+            })?;
 
             let channel = raw_channel.map_chain(MonoTagged::new, MonoTagged::new);
 
@@ -86,17 +95,33 @@ impl<Handle: ChainHandle, const SIZE: usize> ConnectedChannels<Handle, SIZE> {
         }
     }
 
-    pub fn channels(&self) -> &[[ConnectedChannel<Handle, Handle>; SIZE]; SIZE] {
+    pub fn channels(&self) -> &[[Option<ConnectedChannel<Handle, Handle>>; SIZE]; SIZE] {
         &self.channels
     }
 }
 
 impl<Handle: ChainHandle> DynamicConnectedChannels<Handle> {
+    /**
+       Create the connected channels between every pair of chains.
+    */
     pub fn new(channels: Vec<Vec<ConnectedChannel<Handle, Handle>>>) -> Self {
+        Self::new_sparse(
+            channels
+                .into_iter()
+                .map(|channels_b| channels_b.into_iter().map(Some).collect())
+                .collect(),
+        )
+    }
+
+    /**
+       Create the connected channels with `None` entries for the pairs of
+       chains that are not connected.
+    */
+    pub fn new_sparse(channels: Vec<Vec<Option<ConnectedChannel<Handle, Handle>>>>) -> Self {
         Self { channels }
     }
 
-    pub fn channels(&self) -> &Vec<Vec<ConnectedChannel<Handle, Handle>>> {
+    pub fn channels(&self) -> &Vec<Vec<Option<ConnectedChannel<Handle, Handle>>>> {
         &self.channels
     }
 }
@@ -123,6 +148,11 @@ impl<Handle: ChainHandle, const SIZE: usize> ExportEnv for ConnectedChannels<Han
     fn export_env(&self, writer: &mut impl EnvWriter) {
         for (i, inner_channels) in self.channels.iter().enumerate() {
             for (j, channel_i_to_j) in inner_channels.iter().enumerate() {
+                let channel_i_to_j = match channel_i_to_j {
+                    Some(channel) => channel,
+                    None => continue,
+                };
+
                 writer.write_env(
                     &format!("CONNECTION_ID_{}_to_{}", j, i),
                     &format!("{}", channel_i_to_j.connection.connection_id_a),
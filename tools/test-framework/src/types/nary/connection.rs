@@ -3,9 +3,11 @@
 */
 
 use core::convert::TryFrom;
+use core::time::Duration;
 use eyre::eyre;
 use ibc_relayer::chain::handle::ChainHandle;
 use ibc_relayer_types::core::ics24_host::identifier::ConnectionId;
+use std::collections::BTreeSet;
 
 use super::aliases::NthChainHandle;
 use crate::error::Error;
@@ -17,21 +19,25 @@ use crate::util::array::{into_nested_vec, try_into_nested_array};
 /**
    A fixed-size N-ary connected connections as specified by `SIZE`.
 
-   Contains `SIZE`x`SIZE` number of binary [`ConnectedConnection`]s.
+   Contains up to `SIZE`x`SIZE` number of binary [`ConnectedConnection`]s,
+   with entry `[i][j]` set only if the chains at position `i` and `j` are
+   connected in the [`Topology`](crate::types::nary::topology::Topology)
+   the connections were bootstrapped with.
 */
 #[derive(Debug, Clone)]
 pub struct ConnectedConnections<Handle: ChainHandle, const SIZE: usize> {
-    connections: [[ConnectedConnection<Handle, Handle>; SIZE]; SIZE],
+    connections: [[Option<ConnectedConnection<Handle, Handle>>; SIZE]; SIZE],
 }
 
 /**
    A dynamic-sized N-ary connected connections, made of a
    nested vector of binary [`ConnectedConnection`] which must be
-   in the same dimension.
+   in the same dimension, with `None` entries for the pairs of chains
+   that are not connected.
 */
 #[derive(Debug, Clone)]
 pub struct DynamicConnectedConnections<Handle: ChainHandle> {
-    connections: Vec<Vec<ConnectedConnection<Handle, Handle>>>,
+    connections: Vec<Vec<Option<ConnectedConnection<Handle, Handle>>>>,
 }
 
 /**
@@ -49,9 +55,21 @@ pub type NthConnectionId<const CHAIN_A: usize, const CHAIN_B: usize, Handle> =
     DualTagged<NthChainHandle<CHAIN_A, Handle>, NthChainHandle<CHAIN_B, Handle>, ConnectionId>;
 
 impl<Handle: ChainHandle, const SIZE: usize> ConnectedConnections<Handle, SIZE> {
+    /**
+       Create the connected connections from a `SIZE`x`SIZE` nested vector,
+       with `None` entries for the pairs of chains that are not connected.
+    */
+    pub fn new(
+        connections: Vec<Vec<Option<ConnectedConnection<Handle, Handle>>>>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            connections: try_into_nested_array(connections)?,
+        })
+    }
+
     /**
        Get the connection pair for chains at position `CHAIN_A` and `CHAIN_B`,
-       which must be less then `SIZE`.
+       which must be less then `SIZE` and be connected.
     */
     pub fn connection_at<const CHAIN_A: usize, const CHAIN_B: usize>(
         &self,
@@ -63,7 +81,13 @@ impl<Handle: ChainHandle, const SIZE: usize> ConnectedConnections<Handle, SIZE>
                 CHAIN_B
             )))
         } else {
-            let raw_connection = self.connections[CHAIN_A][CHAIN_B].clone();
+            let raw_connection = self.connections[CHAIN_A][CHAIN_B].clone().ok_or_else(|| {
+                Error::generic(eyre!(
+                    "no connection between the chains at position {}/{}",
+                    CHAIN_A,
+                    CHAIN_B
+                ))
+            })?;
 
             let channel = raw_connection.map_chain(MonoTagged::new, MonoTagged::new);
 
@@ -71,17 +95,115 @@ impl<Handle: ChainHandle, const SIZE: usize> ConnectedConnections<Handle, SIZE>
         }
     }
 
-    pub fn connections(&self) -> &[[ConnectedConnection<Handle, Handle>; SIZE]; SIZE] {
+    /**
+       Query the delay period of the connection between the chains at
+       position `CHAIN_A` and `CHAIN_B`, which must be less then `SIZE`.
+    */
+    pub fn delay_at<const CHAIN_A: usize, const CHAIN_B: usize>(&self) -> Result<Duration, Error> {
+        self.connection_at::<CHAIN_A, CHAIN_B>()?.delay()
+    }
+
+    /**
+       An adjacency matrix of the chains, where entry `[i][j]` indicates
+       whether there is a connection between the chains at position `i`
+       and `j`. As connections are bootstrapped in both directions, the
+       matrix is symmetric. With the default complete topology, where all
+       pairs of chains are connected, including each chain with itself, all
+       of its entries are set.
+    */
+    pub fn adjacency_matrix(&self) -> Vec<Vec<bool>> {
+        self.connections
+            .iter()
+            .map(|connections_b| {
+                connections_b
+                    .iter()
+                    .map(|connection| connection.is_some())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /**
+       The number of connections between distinct pairs of chains, each
+       counted once regardless of direction.
+    */
+    pub fn connection_count(&self) -> usize {
+        self.connections
+            .iter()
+            .enumerate()
+            .map(|(i, connections_b)| {
+                connections_b
+                    .iter()
+                    .skip(i + 1)
+                    .filter(|connection| connection.is_some())
+                    .count()
+            })
+            .sum()
+    }
+
+    /**
+       Check that no two connections on the same chain share a connection
+       ID, as may happen with a bug in bootstrapping the connections.
+
+       The IDs on the chain at position `i` are the ones at the side of that
+       chain of each connection `[i][j]`, together with the counterparty side
+       of its self-connection `[i][i]`, if any.
+    */
+    pub fn assert_unique_ids(&self) -> Result<(), Error> {
+        for (i, connections_b) in self.connections.iter().enumerate() {
+            let mut connection_ids = BTreeSet::new();
+
+            let self_connection_id = self.connections[i][i]
+                .as_ref()
+                .map(|connection| connection.connection_id_b.value());
+
+            let ids = connections_b
+                .iter()
+                .flatten()
+                .map(|connection| connection.connection_id_a.value())
+                .chain(self_connection_id);
+
+            for connection_id in ids {
+                if !connection_ids.insert(connection_id) {
+                    return Err(Error::generic(eyre!(
+                        "connection ID {} is used by more than one connection on the chain at position {}",
+                        connection_id,
+                        i
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn connections(&self) -> &[[Option<ConnectedConnection<Handle, Handle>>; SIZE]; SIZE] {
         &self.connections
     }
 }
 
 impl<Handle: ChainHandle> DynamicConnectedConnections<Handle> {
+    /**
+       Create the connected connections between every pair of chains.
+    */
     pub fn new(connections: Vec<Vec<ConnectedConnection<Handle, Handle>>>) -> Self {
+        Self::new_sparse(
+            connections
+                .into_iter()
+                .map(|connections_b| connections_b.into_iter().map(Some).collect())
+                .collect(),
+        )
+    }
+
+    /**
+       Create the connected connections with `None` entries for the pairs
+       of chains that are not connected.
+    */
+    pub fn new_sparse(connections: Vec<Vec<Option<ConnectedConnection<Handle, Handle>>>>) -> Self {
         Self { connections }
     }
 
-    pub fn connections(&self) -> &Vec<Vec<ConnectedConnection<Handle, Handle>>> {
+    pub fn connections(&self) -> &Vec<Vec<Option<ConnectedConnection<Handle, Handle>>>> {
         &self.connections
     }
 }
@@ -102,9 +224,7 @@ impl<Handle: ChainHandle, const SIZE: usize> TryFrom<DynamicConnectedConnections
     type Error = Error;
 
     fn try_from(connections: DynamicConnectedConnections<Handle>) -> Result<Self, Error> {
-        Ok(ConnectedConnections {
-            connections: try_into_nested_array(connections.connections)?,
-        })
+        ConnectedConnections::new(connections.connections)
     }
 }
 
@@ -120,6 +240,11 @@ impl<Handle: ChainHandle, const SIZE: usize> ExportEnv for ConnectedConnections<
     fn export_env(&self, writer: &mut impl EnvWriter) {
         for (i, inner_connections) in self.connections.iter().enumerate() {
             for (j, connection_i_to_j) in inner_connections.iter().enumerate() {
+                let connection_i_to_j = match connection_i_to_j {
+                    Some(connection) => connection,
+                    None => continue,
+                };
+
                 writer.write_env(
                     &format!("CONNECTION_ID_{}_to_{}", j, i),
                     &format!("{}", connection_i_to_j.connection_id_a),
@@ -64,3 +64,4 @@ pub mod chains;
 pub mod channel;
 pub mod connection;
 pub mod foreign_client;
+pub mod topology;
@@ -0,0 +1,254 @@
+/*!
+   Constructs for N-ary chains that are connected according to a
+   declarative topology, rather than between every pair of chains.
+*/
+
+use serde::Deserialize;
+use std::path::Path;
+
+use eyre::eyre;
+use ibc_relayer::chain::handle::ChainHandle;
+
+use crate::error::{handle_generic_error, Error};
+use crate::types::binary::connection::ConnectedConnection;
+use crate::types::nary::chains::DynamicConnectedChains;
+use crate::types::nary::connection::DynamicConnectedConnections;
+
+/**
+   The shape in which N-ary chains are connected with each other, as
+   used by [`bootstrap_connections_with_topology`](crate::bootstrap::nary::connection::bootstrap_connections_with_topology).
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Topology {
+    /**
+       Each chain is connected to the next one, and the last chain back
+       to the first one.
+    */
+    Ring,
+
+    /**
+       The chain at position `hub` is connected to each of the other
+       chains, which are not connected with each other.
+    */
+    Star { hub: usize },
+
+    /**
+       Each chain is connected to the next one, without the last chain
+       being connected back to the first one.
+    */
+    Line,
+
+    /**
+       Every pair of chains is connected, including each chain with
+       itself. This is the default.
+    */
+    Complete,
+
+    /**
+       Only the pairs of chains at the positions in the given edge list
+       are connected, as declared in a [`NaryTopologySpec`].
+    */
+    Edges(Vec<(usize, usize)>),
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Self::Complete
+    }
+}
+
+impl Topology {
+    /**
+       Whether the chains at position `chain_a` and `chain_b` are connected
+       in this topology over `size` chains.
+    */
+    pub fn connects(&self, size: usize, chain_a: usize, chain_b: usize) -> bool {
+        if chain_a >= size || chain_b >= size {
+            return false;
+        }
+
+        let (low, high) = (chain_a.min(chain_b), chain_a.max(chain_b));
+
+        match self {
+            Self::Complete => true,
+            Self::Line => high == low + 1,
+            Self::Ring => high == low + 1 || (size > 2 && low == 0 && high == size - 1),
+            Self::Star { hub } => low != high && (low == *hub || high == *hub),
+            Self::Edges(edges) => edges.contains(&(low, high)) || edges.contains(&(high, low)),
+        }
+    }
+
+    /**
+       The connected pairs of chain positions in this topology over `size`
+       chains, with the smaller position first.
+    */
+    pub fn edges(&self, size: usize) -> Vec<(usize, usize)> {
+        (0..size)
+            .flat_map(|chain_a| (chain_a..size).map(move |chain_b| (chain_a, chain_b)))
+            .filter(|&(chain_a, chain_b)| self.connects(size, chain_a, chain_b))
+            .collect()
+    }
+}
+
+/**
+   A declarative description of N-ary chains and the connections
+   between them, typically loaded from a TOML file such as:
+
+   ```toml
+   chains = ["alpha", "beta", "gamma"]
+   connections = [[0, 1], [1, 2]]
+   ```
+
+   Each entry in `chains` is the prefix used for the chain ID of the
+   chain at that position, and each entry in `connections` is an edge
+   between the chains at the given positions.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NaryTopologySpec {
+    pub chains: Vec<String>,
+
+    #[serde(default)]
+    pub connections: Vec<(usize, usize)>,
+}
+
+impl NaryTopologySpec {
+    /**
+       Load and validate a topology spec from the TOML file at `path`.
+    */
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+
+        Self::parse(&content)
+    }
+
+    /**
+       Parse and validate a topology spec from a TOML string.
+    */
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let spec: Self = toml::from_str(content).map_err(handle_generic_error)?;
+
+        spec.validate()?;
+
+        Ok(spec)
+    }
+
+    /**
+       Check that every connection is between two distinct chains that
+       are declared in the spec, and that no connection is repeated.
+    */
+    pub fn validate(&self) -> Result<(), Error> {
+        let size = self.chains.len();
+
+        for (i, &(chain_a, chain_b)) in self.connections.iter().enumerate() {
+            if chain_a >= size || chain_b >= size {
+                return Err(Error::generic(eyre!(
+                    "connection {}/{} refers to a chain beyond the {} declared chains",
+                    chain_a,
+                    chain_b,
+                    size
+                )));
+            }
+
+            if chain_a == chain_b {
+                return Err(Error::generic(eyre!(
+                    "connection {}/{} must be between two distinct chains",
+                    chain_a,
+                    chain_b
+                )));
+            }
+
+            let repeated = self.connections[..i]
+                .iter()
+                .any(|&edge| edge == (chain_a, chain_b) || edge == (chain_b, chain_a));
+
+            if repeated {
+                return Err(Error::generic(eyre!(
+                    "connection {}/{} is declared more than once",
+                    chain_a,
+                    chain_b
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+       The [`Topology`] made of the connections declared in the spec.
+    */
+    pub fn topology(&self) -> Topology {
+        Topology::Edges(self.connections.clone())
+    }
+}
+
+/**
+   N-ary chains that are connected according to a [`NaryTopologySpec`],
+   where only the pairs of chains declared in the spec have a connection.
+*/
+#[derive(Clone)]
+pub struct ConnectedTopology<Handle: ChainHandle> {
+    pub chains: DynamicConnectedChains<Handle>,
+
+    /**
+       The connections between the chains, with entry `[i][j]` set only if
+       the edge between the chains at position `i` and `j` is declared in
+       the spec, in either direction.
+    */
+    pub connections: DynamicConnectedConnections<Handle>,
+}
+
+impl<Handle: ChainHandle> ConnectedTopology<Handle> {
+    /**
+       Get the connection from the chain at position `chain_a` to the
+       chain at position `chain_b`, if they are connected.
+    */
+    pub fn connection_between(
+        &self,
+        chain_a: usize,
+        chain_b: usize,
+    ) -> Option<&ConnectedConnection<Handle, Handle>> {
+        self.connections
+            .connections()
+            .get(chain_a)?
+            .get(chain_b)?
+            .as_ref()
+    }
+
+    /**
+       An adjacency matrix of the chains, where entry `[i][j]` indicates
+       whether there is a connection between the chains at position `i`
+       and `j`, as declared in the spec.
+    */
+    pub fn adjacency_matrix(&self) -> Vec<Vec<bool>> {
+        self.connections
+            .connections()
+            .iter()
+            .map(|connections_b| {
+                connections_b
+                    .iter()
+                    .map(|connection| connection.is_some())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /**
+       The connected pairs of chain positions, with the smaller position
+       first.
+    */
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.adjacency_matrix()
+            .iter()
+            .enumerate()
+            .flat_map(|(chain_a, connected_b)| {
+                connected_b
+                    .iter()
+                    .enumerate()
+                    .skip(chain_a + 1)
+                    .filter(|(_, connected)| **connected)
+                    .map(move |(chain_b, _)| (chain_a, chain_b))
+            })
+            .collect()
+    }
+}
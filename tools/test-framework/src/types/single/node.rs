@@ -152,11 +152,15 @@ impl FullNode {
             trust_threshold: Default::default(),
             gas_price: config::GasPrice::new(0.001, "stake".to_string()),
             packet_filter: Default::default(),
+            connection_filter: Default::default(),
             address_type: chain_type.address_type(),
             memo_prefix: Default::default(),
+            clear_on_start: None,
             proof_specs: Default::default(),
             extension_options: Default::default(),
             sequential_batch_tx: false,
+            client_filter: Default::default(),
+            denom_filter: Default::default(),
         })
     }
 